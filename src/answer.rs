@@ -0,0 +1,127 @@
+// Copyright (c) 2017 aoc developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Puzzle answers
+use itertools::Itertools;
+use std::fmt;
+
+/// The answer for a single star.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Answer {
+    /// A signed integer
+    Signed(i64),
+    /// An unsigned integer
+    Unsigned(u64),
+    /// A signed 128-bit integer
+    Signed128(i128),
+    /// An unsigned 128-bit integer
+    Unsigned128(u128),
+    /// A single line of text, i.e. a password or a step ordering
+    Text(String),
+    /// Multi-line ASCII art, i.e. letters drawn by points of light
+    Art(String),
+    /// A coordinate tuple, displayed as `x,y` or `x,y,z`
+    Coords(Vec<i64>),
+}
+
+impl Answer {
+    /// The name of the variant, as reported in machine-readable output.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Answer::Signed(_) => "signed",
+            Answer::Unsigned(_) => "unsigned",
+            Answer::Signed128(_) => "signed128",
+            Answer::Unsigned128(_) => "unsigned128",
+            Answer::Text(_) => "text",
+            Answer::Art(_) => "art",
+            Answer::Coords(_) => "coords",
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Signed(val) => write!(f, "{val}"),
+            Answer::Unsigned(val) => write!(f, "{val}"),
+            Answer::Signed128(val) => write!(f, "{val}"),
+            Answer::Unsigned128(val) => write!(f, "{val}"),
+            Answer::Text(val) => f.write_str(val),
+            Answer::Art(val) => f.write_str(val.trim_end_matches('\n')),
+            Answer::Coords(val) => write!(f, "{}", val.iter().join(",")),
+        }
+    }
+}
+
+/// Lossless conversions from the integer types the solvers produce.
+macro_rules! answer_from {
+    ($variant:ident, $wide:ty, $($source:ty),*) => {$(
+        impl From<$source> for Answer {
+            #[inline]
+            fn from(val: $source) -> Self {
+                Answer::$variant(<$wide>::from(val))
+            }
+        }
+    )*}
+}
+
+answer_from!(Signed, i64, i8, i16, i32, i64);
+answer_from!(Unsigned, u64, u8, u16, u32, u64);
+answer_from!(Signed128, i128, i128);
+answer_from!(Unsigned128, u128, u128);
+
+impl From<isize> for Answer {
+    fn from(val: isize) -> Self {
+        Answer::Signed(val as i64)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(val: usize) -> Self {
+        Answer::Unsigned(val as u64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(val: String) -> Self {
+        Answer::Text(val)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(val: &str) -> Self {
+        Answer::Text(val.to_string())
+    }
+}
+
+impl From<(usize, usize)> for Answer {
+    fn from((x, y): (usize, usize)) -> Self {
+        Answer::Coords(vec![x as i64, y as i64])
+    }
+}
+
+impl From<(usize, usize, usize)> for Answer {
+    fn from((x, y, z): (usize, usize, usize)) -> Self {
+        Answer::Coords(vec![x as i64, y as i64, z as i64])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Answer;
+
+    #[test]
+    fn display() {
+        assert_eq!(Answer::from(-6_i32).to_string(), "-6");
+        assert_eq!(Answer::from(1_406_506_154_usize).to_string(), "1406506154");
+        assert_eq!(Answer::from(u128::MAX).to_string(), "340282366920938463463374607431768211455");
+        assert_eq!(Answer::from("fgij").to_string(), "fgij");
+        assert_eq!(Answer::Art("#..#\n####\n".to_string()).to_string(), "#..#\n####");
+        assert_eq!(Answer::from((90_usize, 269_usize, 16_usize)).to_string(), "90,269,16");
+    }
+}
@@ -0,0 +1,199 @@
+// Copyright (c) 2017 aoc developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Stored answers for regression checking
+//!
+//! Each day may keep an `answers` file next to its input, either as TOML
+//!
+//! ```text
+//! part1 = "232"
+//! part2 = "1783"
+//! ```
+//!
+//! or as plain `part1=232` / `part2=1783` lines.  Multi-line answers can only be
+//! stored as TOML, and are always recorded that way.
+use crate::{
+    answer::Answer,
+    constants::{AoCDay, AoCYear},
+    run::input_path,
+};
+use anyhow::{Context, Result, anyhow};
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, path::PathBuf};
+
+/// The name of the answers file kept next to each day's input.
+pub(crate) const ANSWERS_FILE: &str = "answers";
+
+/// The known-good answers for a single day.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Getters, PartialEq, Serialize)]
+#[getset(get = "pub(crate)")]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Answers {
+    /// The answer for the first star
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part1: Option<String>,
+    /// The answer for the second star
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part2: Option<String>,
+}
+
+/// A computed answer that does not match the stored one.
+#[derive(Clone, Debug, Eq, Getters, PartialEq)]
+#[getset(get = "pub(crate)")]
+pub(crate) struct Mismatch {
+    /// The puzzle year
+    year: AoCYear,
+    /// The puzzle day
+    day: AoCDay,
+    /// The star, 1 or 2
+    part: u8,
+    /// The stored answer
+    expected: String,
+    /// The computed answer
+    actual: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {} part {}:", self.year, self.day, self.part)?;
+        for line in self.expected.lines() {
+            writeln!(f, "- {line}")?;
+        }
+        for line in self.actual.lines() {
+            writeln!(f, "+ {line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Answers {
+    /// The path of the answers file for the given day.
+    pub(crate) fn path(year: AoCYear, day: AoCDay) -> PathBuf {
+        input_path(year, day, ANSWERS_FILE)
+    }
+
+    /// Parse an answers file, accepting either TOML or plain `partN=` lines.
+    pub(crate) fn parse(text: &str) -> Result<Self> {
+        toml::from_str(text).or_else(|_| Self::parse_lines(text))
+    }
+
+    /// Parse plain `part1=` / `part2=` lines, skipping blanks and `#` comments.
+    fn parse_lines(text: &str) -> Result<Self> {
+        let mut answers = Self::default();
+
+        for (idx, line) in text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim().starts_with('#'))
+        {
+            let (key, value) = line.split_once('=').ok_or_else(|| anyhow!("line {}: expected `part1=` or `part2=`", idx + 1))?;
+            let value = Some(value.trim().to_string());
+            match key.trim() {
+                "part1" => answers.part1 = value,
+                "part2" => answers.part2 = value,
+                other => return Err(anyhow!("line {}: unknown key `{other}`", idx + 1)),
+            }
+        }
+
+        Ok(answers)
+    }
+
+    /// Load the answers recorded for the given day, if there are any.
+    pub(crate) fn load(year: AoCYear, day: AoCDay) -> Result<Option<Self>> {
+        let path = Self::path(year, day);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let text = fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        Self::parse(&text).map(Some).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    /// Record the answers for both stars of a day.
+    pub(crate) fn from_answers(first: &Answer, second: &Answer) -> Self {
+        Self {
+            part1: Some(first.to_string()),
+            part2: Some(second.to_string()),
+        }
+    }
+
+    /// Write these answers as TOML to the answers file for the given day.
+    pub(crate) fn save(&self, year: AoCYear, day: AoCDay) -> Result<PathBuf> {
+        let path = Self::path(year, day);
+        fs::write(&path, toml::to_string(self)?).with_context(|| format!("writing {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// The stored answer for the given star.
+    pub(crate) fn part(&self, part: u8) -> Option<&String> {
+        if part == 1 { self.part1.as_ref() } else { self.part2.as_ref() }
+    }
+
+    /// Compare a computed answer against the stored one.
+    ///
+    /// A star with no stored answer never mismatches.
+    pub(crate) fn check(&self, year: AoCYear, day: AoCDay, part: u8, answer: &Answer) -> Option<Mismatch> {
+        let expected = self.part(part)?;
+        let actual = answer.to_string();
+
+        if *expected == actual {
+            None
+        } else {
+            Some(Mismatch {
+                year,
+                day,
+                part,
+                expected: expected.clone(),
+                actual,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Answers;
+    use crate::answer::Answer;
+    use crate::constants::{AoCDay, AoCYear};
+    use anyhow::Result;
+
+    #[test]
+    fn parse_toml() -> Result<()> {
+        let answers = Answers::parse("part1 = \"232\"\npart2 = \"\"\"\n#..#\n####\"\"\"\n")?;
+        assert_eq!(answers.part1().as_deref(), Some("232"));
+        assert_eq!(answers.part2().as_deref(), Some("#..#\n####"));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_lines() -> Result<()> {
+        let answers = Answers::parse("# 2017 day 7\npart1=tknk\npart2 = 60\n")?;
+        assert_eq!(answers.part1().as_deref(), Some("tknk"));
+        assert_eq!(answers.part2().as_deref(), Some("60"));
+        assert!(Answers::parse("part3=1").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let answers = Answers::from_answers(&Answer::Text("fgij".to_string()), &Answer::Art("#..#\n####\n".to_string()));
+        assert_eq!(Answers::parse(&toml::to_string(&answers)?)?, answers);
+        Ok(())
+    }
+
+    #[test]
+    fn check() {
+        let answers = Answers::from_answers(&Answer::Unsigned(232), &Answer::Unsigned(1783));
+        assert!(answers.check(AoCYear::AOC2015, AoCDay::AOCD01, 1, &Answer::Unsigned(232)).is_none());
+        let mismatch = answers.check(AoCYear::AOC2015, AoCDay::AOCD01, 2, &Answer::Signed(1784));
+        assert_eq!(
+            mismatch.map(|mismatch| mismatch.to_string()),
+            Some("2015 day01 part 2:\n- 1783\n+ 1784\n".to_string())
+        );
+    }
+}
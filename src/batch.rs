@@ -0,0 +1,327 @@
+// Copyright (c) 2017 aoc developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Batch runner for whole years
+use crate::{
+    answer::Answer,
+    constants::{AoCDay, AoCYear},
+    context::Context,
+    limits::{self, LimitExceeded},
+    run::input_path,
+    solver::{self, Panicked, Star, Unsolved},
+};
+use anyhow::Result;
+use getset::Getters;
+use std::{
+    fmt,
+    fs::File,
+    io::{BufReader, Write},
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::channel,
+    },
+    thread,
+    time::Duration,
+};
+
+/// The status of a single star in a batch run.
+#[derive(Clone, Debug)]
+pub(crate) enum Status {
+    /// The solver produced an answer
+    Ok(Answer),
+    /// The solver returned an error
+    Error(String),
+    /// No solver is registered for the day, or it has not been solved yet
+    Unimplemented,
+    /// The solver panicked
+    Panicked(String),
+    /// The solver ran past the timeout
+    TimedOut(String),
+    /// The solver's heap grew past the memory limit
+    OutOfMemory(String),
+}
+
+impl Status {
+    /// The short status label used in the summary table.
+    fn label(&self) -> &'static str {
+        match self {
+            Status::Ok(_) => "ok",
+            Status::Error(_) => "error",
+            Status::Unimplemented => "unimplemented",
+            Status::Panicked(_) => "panicked",
+            Status::TimedOut(_) => "timed out",
+            Status::OutOfMemory(_) => "memory limit exceeded",
+        }
+    }
+
+    /// The answer, if the star produced one.
+    pub(crate) fn answer(&self) -> Option<&Answer> {
+        match self {
+            Status::Ok(answer) => Some(answer),
+            _ => None,
+        }
+    }
+
+    /// The answer, or a description of why there is none.
+    pub(crate) fn as_result(&self) -> Result<&Answer, String> {
+        match self {
+            Status::Ok(answer) => Ok(answer),
+            Status::Error(message) => Err(message.clone()),
+            Status::Unimplemented => Err("unimplemented".to_string()),
+            Status::Panicked(message) => Err(format!("panicked: {message}")),
+            Status::TimedOut(message) | Status::OutOfMemory(message) => Err(message.clone()),
+        }
+    }
+
+    /// Whether this status should fail the run.
+    pub(crate) fn is_failure(&self) -> bool {
+        !matches!(self, Status::Ok(_) | Status::Unimplemented)
+    }
+}
+
+impl From<&Result<Answer>> for Status {
+    fn from(answer: &Result<Answer>) -> Self {
+        match answer {
+            Ok(answer) => Status::Ok(answer.clone()),
+            Err(e) if e.is::<Unsolved>() => Status::Unimplemented,
+            Err(e) => match (e.downcast_ref::<Panicked>(), e.downcast_ref::<LimitExceeded>()) {
+                (Some(panicked), _) => Status::Panicked(panicked.to_string()),
+                (_, Some(LimitExceeded::TimedOut(_))) => Status::TimedOut(e.to_string()),
+                (_, Some(LimitExceeded::OutOfMemory(_))) => Status::OutOfMemory(e.to_string()),
+                (None, None) => Status::Error(e.to_string()),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Ok(Answer::Art(art)) => write!(f, "ascii art ({} lines)", art.lines().count()),
+            Status::Ok(answer) => write!(f, "{answer}"),
+            Status::Error(message) | Status::Panicked(message) | Status::TimedOut(message) | Status::OutOfMemory(message) => f.write_str(message),
+            Status::Unimplemented => Ok(()),
+        }
+    }
+}
+
+/// The result of a single star in a batch run.
+#[derive(Debug, Getters)]
+#[getset(get = "pub(crate)")]
+pub(crate) struct Report {
+    /// The puzzle year
+    year: AoCYear,
+    /// The puzzle day
+    day: AoCDay,
+    /// The star, 1 or 2
+    part: u8,
+    /// What happened
+    status: Status,
+    /// The wall time spent on the star
+    elapsed: Duration,
+}
+
+impl Report {
+    /// Build the report for one star from its timed answer.
+    fn from_star(year: AoCYear, day: AoCDay, part: u8, star: &Star) -> Self {
+        Self {
+            year,
+            day,
+            part,
+            status: star.answer().into(),
+            elapsed: *star.elapsed(),
+        }
+    }
+}
+
+/// Every (year, day) pair within `years` that has an input file named `file` under `data/`.
+pub(crate) fn days_with_input(years: &[AoCYear], file: &str) -> Vec<(AoCYear, AoCDay)> {
+    years
+        .iter()
+        .flat_map(|year| AoCDay::ALL.into_iter().map(move |day| (*year, day)))
+        .filter(|(year, day)| input_path(*year, *day, file).is_file())
+        .collect()
+}
+
+/// Run a single star of a single day, never letting a failure escape.
+///
+/// The input is read afresh for every star, so the two stars of a day can run at the same time.
+pub(crate) fn run_star(year: AoCYear, day: AoCDay, part: u8, file: &str, ctx: &Context) -> Report {
+    let Some(solver) = solver::find(year, day) else {
+        return Report {
+            year,
+            day,
+            part,
+            status: Status::Unimplemented,
+            elapsed: Duration::ZERO,
+        };
+    };
+
+    let star = Star::time(|| {
+        let mut reader = BufReader::new(File::open(input_path(year, day, file))?);
+        limits::star(solver, part, &mut reader, ctx)
+    });
+    Report::from_star(year, day, part, &star)
+}
+
+/// How many threads to run `stars` stars on, given `--jobs`.
+///
+/// The heap is only measured for the process as a whole, so stars under a memory limit
+/// run one at a time, each charged for its own allocations alone.
+fn workers(jobs: NonZeroUsize, stars: usize, ctx: &Context) -> usize {
+    if ctx.max_memory().is_some() { 1 } else { jobs.get().min(stars) }
+}
+
+/// Run both stars of every day in `days` on up to `jobs` threads.
+///
+/// Each star is a job of its own, and the reports come back in the order of `days`
+/// however the jobs happen to finish.
+pub(crate) fn run_days(days: &[(AoCYear, AoCDay)], file: &str, jobs: NonZeroUsize, ctx: &Context) -> Vec<Report> {
+    let stars: Vec<(AoCYear, AoCDay, u8)> = days.iter().flat_map(|(year, day)| [1, 2].map(|part| (*year, *day, part))).collect();
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = channel();
+
+    thread::scope(|scope| {
+        for _ in 0..workers(jobs, stars.len(), ctx) {
+            let (sender, next, stars) = (sender.clone(), &next, &stars);
+            let _ = scope.spawn(move || {
+                loop {
+                    let idx = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(&(year, day, part)) = stars.get(idx) else {
+                        break;
+                    };
+                    let _ = sender.send((idx, run_star(year, day, part, file, ctx)));
+                }
+            });
+        }
+    });
+    drop(sender);
+
+    let mut reports: Vec<_> = receiver.into_iter().collect();
+    reports.sort_by_key(|(idx, _)| *idx);
+    reports.into_iter().map(|(_, report)| report).collect()
+}
+
+/// Write the summary table for a batch run.
+pub(crate) fn write_summary<W: Write>(out: &mut W, reports: &[Report], wall_time: Duration) -> Result<()> {
+    writeln!(out, "{:<6}{:<7}{:<6}{:<23}{:>12}  answer", "year", "day", "part", "status", "time")?;
+    for report in reports {
+        writeln!(
+            out,
+            "{:<6}{:<7}{:<6}{:<23}{:>12}  {}",
+            report.year(),
+            report.day(),
+            report.part(),
+            report.status().label(),
+            format!("{:.3?}", report.elapsed()),
+            report.status()
+        )?;
+    }
+
+    let count = |label: &str| reports.iter().filter(|report| report.status().label() == label).count();
+    writeln!(
+        out,
+        "\n{} stars: {} ok, {} error, {} unimplemented, {} panicked, {} timed out, {} over the memory limit in {:.3?}",
+        reports.len(),
+        count("ok"),
+        count("error"),
+        count("unimplemented"),
+        count("panicked"),
+        count("timed out"),
+        count("memory limit exceeded"),
+        wall_time
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Report, Status, days_with_input, run_days, run_star, workers, write_summary};
+    use crate::answer::Answer;
+    use crate::constants::{AoCDay, AoCYear};
+    use crate::context::Context;
+    use crate::limits::LimitExceeded;
+    use crate::solver::{Star, Unsolved};
+    use anyhow::{Result, anyhow};
+    use std::{num::NonZeroUsize, time::Duration};
+
+    #[test]
+    fn statuses() {
+        let ok = Star::time(|| Ok(Answer::Unsigned(7)));
+        let err = Star::time(|| Err(anyhow!("bad input")));
+        let panicked = Star::time(|| panic!("tough cookies"));
+        let unsolved = Star::time(|| Err(Unsolved.into()));
+        let timed_out = Star::time(|| Err(LimitExceeded::TimedOut(Duration::from_secs(5)).into()));
+        let out_of_memory = Star::time(|| Err(LimitExceeded::OutOfMemory(1 << 20).into()));
+        assert_eq!(Status::from(ok.answer()).label(), "ok");
+        assert_eq!(Status::from(unsolved.answer()).label(), "unimplemented");
+        assert_eq!(Status::from(err.answer()).label(), "error");
+        assert_eq!(Status::from(panicked.answer()).label(), "panicked");
+        assert_eq!(Status::from(panicked.answer()).to_string(), "tough cookies");
+        assert_eq!(Status::from(timed_out.answer()).label(), "timed out");
+        assert_eq!(Status::from(out_of_memory.answer()).label(), "memory limit exceeded");
+        assert_eq!(Status::from(out_of_memory.answer()).as_result(), Err("memory limit of 1M exceeded".to_string()));
+        assert!(Status::from(timed_out.answer()).is_failure());
+    }
+
+    #[test]
+    fn unimplemented_days_are_reported() {
+        let report = run_star(AoCYear::AOC2016, AoCDay::AOCD01, 2, "data_file", &Context::default());
+        assert!(matches!(report.status(), Status::Unimplemented));
+    }
+
+    #[test]
+    fn missing_input_is_an_error() {
+        assert!(
+            run_star(AoCYear::AOC2017, AoCDay::AOCD01, 1, "no_such_file", &Context::default())
+                .status()
+                .is_failure()
+        );
+        assert!(days_with_input(&[AoCYear::AOC2017], "no_such_file").is_empty());
+    }
+
+    #[test]
+    fn parallel_reports_are_ordered() -> Result<()> {
+        let days = [AoCDay::AOCD03, AoCDay::AOCD01, AoCDay::AOCD02, AoCDay::AOCD04].map(|day| (AoCYear::AOC2015, day));
+        let key = |report: &Report| (*report.year(), *report.day(), *report.part(), report.status().answer().cloned());
+        let serial: Vec<_> = run_days(&days, "data_file", NonZeroUsize::MIN, &Context::default()).iter().map(key).collect();
+        let parallel: Vec<_> = run_days(&days, "data_file", NonZeroUsize::new(3).ok_or(anyhow!("zero jobs"))?, &Context::default())
+            .iter()
+            .map(key)
+            .collect();
+        assert_eq!(serial, parallel);
+        assert_eq!(
+            parallel.iter().map(|(_, day, part, _)| (*day, *part)).collect::<Vec<_>>()[..3],
+            [(AoCDay::AOCD03, 1), (AoCDay::AOCD03, 2), (AoCDay::AOCD01, 1)]
+        );
+        assert!(parallel.iter().all(|(_, _, _, answer)| answer.is_some()));
+        Ok(())
+    }
+
+    #[test]
+    fn memory_limits_run_alone() -> Result<()> {
+        let jobs = NonZeroUsize::new(4).ok_or(anyhow!("zero jobs"))?;
+        assert_eq!(workers(jobs, 50, &Context::default()), 4);
+        assert_eq!(workers(jobs, 2, &Context::default()), 2);
+        assert_eq!(workers(jobs, 50, &Context::default().with_limits(Some(Duration::from_secs(1)), None)), 4);
+        assert_eq!(workers(jobs, 50, &Context::default().with_limits(None, Some(1 << 30))), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn summary() -> Result<()> {
+        let reports = [1, 2].map(|part| run_star(AoCYear::AOC2016, AoCDay::AOCD02, part, "data_file", &Context::default()));
+        let mut out = Vec::new();
+        write_summary(&mut out, &reports, Duration::ZERO)?;
+        let out = String::from_utf8(out)?;
+        assert!(out.contains("2016  day02  1     unimplemented"));
+        assert!(out.contains("2 stars: 0 ok, 0 error, 2 unimplemented, 0 panicked, 0 timed out, 0 over the memory limit"));
+        Ok(())
+    }
+}
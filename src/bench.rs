@@ -0,0 +1,305 @@
+// Copyright (c) 2017 aoc developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Repeated timing of a single day
+//!
+//! A day that parses ahead of solving, see [`Solver::parse`], has its input parsed once and
+//! timed on its own, so the star timings cover solving alone.  Any other day parses inside
+//! each star, and its timings include that parsing.
+use crate::{context::Context, solver::Solver};
+use anyhow::{Context as _, Result, anyhow};
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+use std::{
+    any::Any,
+    fmt, fs,
+    io::Write,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// Summary statistics over the timed iterations of one star, in nanoseconds.
+#[derive(Clone, Copy, Debug, Deserialize, Getters, PartialEq, Serialize)]
+#[getset(get = "pub(crate)")]
+pub(crate) struct Stats {
+    /// The star, 1 or 2
+    part: u8,
+    /// The fastest iteration
+    min: u64,
+    /// The median iteration
+    median: u64,
+    /// The mean over all iterations
+    mean: u64,
+    /// The population standard deviation
+    stddev: u64,
+}
+
+impl Stats {
+    /// Summarize a set of timings.
+    pub(crate) fn from_timings(part: u8, timings: &[Duration]) -> Result<Self> {
+        if timings.is_empty() {
+            return Err(anyhow!("at least one iteration is required"));
+        }
+        let mut nanos: Vec<f64> = timings.iter().map(|timing| timing.as_nanos() as f64).collect();
+        nanos.sort_by(f64::total_cmp);
+
+        let len = nanos.len();
+        let median = if len.is_multiple_of(2) {
+            (nanos[len / 2 - 1] + nanos[len / 2]) / 2.
+        } else {
+            nanos[len / 2]
+        };
+        let mean = nanos.iter().sum::<f64>() / len as f64;
+        let variance = nanos.iter().map(|nano| (nano - mean).powi(2)).sum::<f64>() / len as f64;
+
+        Ok(Self {
+            part,
+            min: nanos[0] as u64,
+            median: median as u64,
+            mean: mean as u64,
+            stddev: variance.sqrt() as u64,
+        })
+    }
+}
+
+/// A saved benchmark run, used as the baseline for later runs.
+#[derive(Clone, Debug, Deserialize, Getters, PartialEq, Serialize)]
+#[getset(get = "pub(crate)")]
+pub(crate) struct Baseline {
+    /// The puzzle year
+    year: String,
+    /// The puzzle day
+    day: String,
+    /// The number of timed iterations per star
+    iterations: usize,
+    /// How long parsing the input once took, in nanoseconds, for a day that parses ahead of solving
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parse: Option<u64>,
+    /// The statistics for each star that was run
+    stars: Vec<Stats>,
+}
+
+impl Baseline {
+    /// Read a baseline saved by an earlier run.
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    /// Save this run as a baseline.
+    pub(crate) fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?).with_context(|| format!("writing {}", path.display()))
+    }
+
+    /// Compare this run against an earlier baseline for the same day.
+    ///
+    /// A star regresses when its median is more than `threshold` percent slower.
+    pub(crate) fn regressions(&self, baseline: &Baseline, threshold: f64) -> Result<Vec<Regression>> {
+        if self.year != baseline.year || self.day != baseline.day {
+            return Err(anyhow!(
+                "the baseline is for {} {}, not {} {}",
+                baseline.year,
+                baseline.day,
+                self.year,
+                self.day
+            ));
+        }
+
+        Ok(self
+            .stars
+            .iter()
+            .filter_map(|current| {
+                let before = baseline.stars.iter().find(|before| before.part == current.part)?;
+                let slowdown = (current.median as f64 / before.median.max(1) as f64 - 1.) * 100.;
+                (slowdown > threshold).then_some(Regression {
+                    part: current.part,
+                    before: before.median,
+                    after: current.median,
+                    slowdown,
+                })
+            })
+            .collect())
+    }
+}
+
+/// A star whose median time regressed against the baseline.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Regression {
+    /// The star, 1 or 2
+    part: u8,
+    /// The baseline median
+    before: u64,
+    /// The current median
+    after: u64,
+    /// How much slower the current median is, as a percentage
+    slowdown: f64,
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "part {}: median {:.3?} is {:.1}% slower than the baseline {:.3?}",
+            self.part,
+            Duration::from_nanos(self.after),
+            self.slowdown,
+            Duration::from_nanos(self.before)
+        )
+    }
+}
+
+/// Parse the input once, timing it, if the day parses ahead of solving.
+fn parse(solver: &dyn Solver, input: &str) -> Result<Option<(Box<dyn Any>, Duration)>> {
+    let start = Instant::now();
+    let parsed = solver.parse(&mut input.as_bytes())?;
+    Ok(parsed.map(|parsed| (parsed, start.elapsed())))
+}
+
+/// Time one star `warmup + iterations` times over the same input, discarding the warmup runs.
+///
+/// The star is solved from `parsed` when the day parsed ahead of solving, and from `input` otherwise.
+fn time_star(solver: &dyn Solver, part: u8, input: &str, parsed: Option<&dyn Any>, warmup: usize, iterations: usize) -> Result<Vec<Duration>> {
+    let mut timings = Vec::with_capacity(iterations);
+
+    for iteration in 0..warmup + iterations {
+        let reader = &mut input.as_bytes();
+        let start = Instant::now();
+        let _ = match parsed {
+            Some(parsed) => solver.solve_parsed(part, parsed, &Context::default())?,
+            None => solver.star(part, reader, &Context::default())?,
+        };
+        let elapsed = start.elapsed();
+
+        if iteration >= warmup {
+            timings.push(elapsed);
+        }
+    }
+    Ok(timings)
+}
+
+/// Benchmark the given stars of a day.
+///
+/// The input is read from disk once, so the timings never cover file IO or process startup.
+pub(crate) fn bench(solver: &dyn Solver, input: &str, parts: &[u8], warmup: usize, iterations: usize) -> Result<Baseline> {
+    let parsed = parse(solver, input)?;
+    let stars = parts
+        .iter()
+        .map(|part| {
+            let timings = time_star(solver, *part, input, parsed.as_ref().map(|(parsed, _)| &**parsed), warmup, iterations)?;
+            Stats::from_timings(*part, &timings)
+        })
+        .collect::<Result<Vec<Stats>>>()?;
+
+    Ok(Baseline {
+        year: solver.year().to_string(),
+        day: solver.day().to_string(),
+        iterations,
+        parse: parsed.map(|(_, elapsed)| elapsed.as_nanos() as u64),
+        stars,
+    })
+}
+
+/// Write the statistics table for a benchmark run.
+pub(crate) fn write_stats<W: Write>(out: &mut W, run: &Baseline) -> Result<()> {
+    writeln!(out, "{} {}: {} iterations", run.year, run.day, run.iterations)?;
+    match run.parse {
+        Some(parse) => writeln!(out, "parsed once in {:.3?}, which the timings leave out", Duration::from_nanos(parse))?,
+        None => writeln!(out, "each iteration parses the input, which the timings include")?,
+    }
+    writeln!(out, "{:<6}{:>12}{:>12}{:>12}{:>12}", "part", "min", "median", "mean", "stddev")?;
+    let fmt = |nanos: u64| format!("{:.3?}", Duration::from_nanos(nanos));
+
+    for stats in &run.stars {
+        writeln!(
+            out,
+            "{:<6}{:>12}{:>12}{:>12}{:>12}",
+            stats.part,
+            fmt(stats.min),
+            fmt(stats.median),
+            fmt(stats.mean),
+            fmt(stats.stddev)
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Baseline, Stats, bench};
+    use crate::constants::{AoCDay, AoCYear};
+    use crate::solver;
+    use anyhow::{Result, anyhow};
+    use std::time::Duration;
+
+    fn baseline(median: u64) -> Baseline {
+        Baseline {
+            year: "2018".to_string(),
+            day: "day09".to_string(),
+            iterations: 3,
+            parse: None,
+            stars: vec![Stats {
+                part: 1,
+                min: median,
+                median,
+                mean: median,
+                stddev: 0,
+            }],
+        }
+    }
+
+    #[test]
+    fn stats() -> Result<()> {
+        let timings = [4, 1, 3, 2].map(Duration::from_nanos);
+        let stats = Stats::from_timings(2, &timings)?;
+        assert_eq!((stats.min, stats.median, stats.mean, stats.stddev), (1, 2, 2, 1));
+        assert!(Stats::from_timings(1, &[]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn regressions() -> Result<()> {
+        assert!(baseline(105).regressions(&baseline(100), 10.)?.is_empty());
+        let regressions = baseline(120).regressions(&baseline(100), 10.)?;
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(
+            regressions[0].to_string(),
+            "part 1: median 120.000ns is 20.0% slower than the baseline 100.000ns"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let before = baseline(100);
+        assert_eq!(serde_json::from_str::<Baseline>(&serde_json::to_string(&before)?)?, before);
+        Ok(())
+    }
+
+    #[test]
+    fn iterations() -> Result<()> {
+        let solver = solver::find(AoCYear::AOC2017, AoCDay::AOCD01).ok_or(anyhow!("2017 day01 is not registered"))?;
+        let run = bench(solver, "1122", &[1, 2], 1, 3)?;
+        assert_eq!(run.iterations, 3);
+        assert_eq!(run.stars.iter().map(|stats| stats.part).collect::<Vec<u8>>(), vec![1, 2]);
+        assert_eq!(run.parse, None);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_once() -> Result<()> {
+        let solver = solver::find(AoCYear::AOC2018, AoCDay::AOCD09).ok_or(anyhow!("2018 day09 is not registered"))?;
+        let run = bench(solver, "9 players; last marble is worth 25 points", &[1, 2], 0, 2)?;
+        assert!(run.parse.is_some());
+        assert_eq!(run.stars.len(), 2);
+
+        // Baselines saved before parse times were recorded still load.
+        let old = r#"{"year":"2018","day":"day09","iterations":3,"stars":[]}"#;
+        assert_eq!(serde_json::from_str::<Baseline>(old)?.parse, None);
+        Ok(())
+    }
+}
@@ -6,220 +6,194 @@
 // option. All files in the project carrying such notice may not be copied,
 // modified, or distributed except according to those terms.
 
-use clap::{Args as _, Error, FromArgMatches, Parser, Subcommand, error::ErrorKind};
+use clap::{ArgGroup, Args as _, Error, FromArgMatches, Parser, Subcommand, error::ErrorKind};
 use getset::Getters;
+use std::{num::NonZeroUsize, path::PathBuf, time::Duration};
 
-use crate::constants::{
-    DAY_1, DAY_2, DAY_3, DAY_4, DAY_5, DAY_6, DAY_7, DAY_8, DAY_9, DAY_10, DAY_11, DAY_12, DAY_13, DAY_14, DAY_15, DAY_16, DAY_17, DAY_18, DAY_19, DAY_20,
-    DAY_21, DAY_22, DAY_23, DAY_24, DAY_25,
-};
+use crate::{config, constants::AoCDay, limits, output::Format, solver};
 
-pub(crate) const DEFAULT_YEAR: &str = "2018";
+const FILE_HELP: &str = "The input: a file name under <data>/<year>/<day>/, a path to a file anywhere, or - for stdin [default: data_file]";
+
+/// The `--file` given on the command line, or the configured default.
+fn file_or_default(file: &Option<String>) -> &str {
+    file.as_deref().unwrap_or(config::settings().file())
+}
 
 #[derive(Clone, Debug, Getters, Parser)]
 #[command(author, version, about = "Run Advent of Code daily problems", long_about = None)]
 #[getset(get = "pub(crate)")]
 pub(crate) struct Args {
-    #[arg(name = "year", short = 'y', long, help = "Specify the year you wish to work with", default_value_t = DEFAULT_YEAR.to_string())]
-    year: String,
+    #[arg(name = "year", short = 'y', long, help = "Specify the year you wish to work with [default: 2018]")]
+    #[getset(skip)]
+    year: Option<String>,
+    #[arg(
+        name = "format",
+        long,
+        global = true,
+        value_enum,
+        help = "How to write results to stdout; solver diagnostics always go to stderr [default: text]"
+    )]
+    #[getset(skip)]
+    format: Option<Format>,
+    #[arg(
+        name = "config",
+        long,
+        global = true,
+        help = "Read defaults from this config file instead of $XDG_CONFIG_HOME/aoc/config.toml; the command line and AOC_* variables override it"
+    )]
+    config: Option<PathBuf>,
+    #[arg(
+        name = "trace",
+        short = 'v',
+        long,
+        global = true,
+        help = "Trace the solver's steps to stderr, where it supports it"
+    )]
+    trace: bool,
+    #[arg(
+        name = "visualize",
+        long,
+        global = true,
+        value_name = "MS",
+        num_args = 0..=1,
+        default_missing_value = "100",
+        help = "Draw the solver's state to stderr as it changes, pausing this many milliseconds between frames [default: 100]"
+    )]
+    visualize: Option<u64>,
+    #[arg(
+        name = "timeout",
+        long,
+        global = true,
+        value_name = "DURATION",
+        value_parser = limits::parse_duration,
+        help = "Stop any star still running after this long, i.e. 30s, 2m or 500ms, and report it as timed out"
+    )]
+    timeout: Option<Duration>,
+    #[arg(
+        name = "max-memory",
+        long,
+        global = true,
+        value_name = "SIZE",
+        value_parser = limits::parse_size,
+        help = "Stop any star once the heap grows past this size, i.e. 512M or 2G, and report it as over the memory limit"
+    )]
+    max_memory: Option<usize>,
     #[command(subcommand)]
     command: Command,
 }
 
-#[derive(Clone, Debug)]
+impl Args {
+    /// The `--year` given on the command line, or the configured default.
+    pub(crate) fn year(&self) -> &str {
+        self.year.as_deref().unwrap_or(config::settings().year())
+    }
+
+    /// The `--format` given on the command line, or the configured default.
+    pub(crate) fn format(&self) -> Format {
+        self.format.unwrap_or(*config::settings().format())
+    }
+}
+
+#[derive(Clone, Debug, Subcommand)]
 pub(crate) enum Command {
-    Day01(AoC2Subcommand),
-    Day02(AoC2Subcommand),
-    Day03(AoC2Subcommand),
-    Day04(AoC2Subcommand),
-    Day05(AoC2Subcommand),
-    Day06(AoC2Subcommand),
-    Day07(AoC2Subcommand),
-    Day08(AoC2Subcommand),
-    Day09(AoC2Subcommand),
-    Day10(AoC2Subcommand),
-    Day11(AoC2Subcommand),
-    Day12(AoC2Subcommand),
-    Day13(AoC2Subcommand),
-    Day14(AoC2Subcommand),
-    Day15(AoC2Subcommand),
-    Day16(AoC2Subcommand),
-    Day17(AoC2Subcommand),
-    Day18(AoC2Subcommand),
-    Day19(AoC2Subcommand),
-    Day20(AoC2Subcommand),
-    Day21(AoC2Subcommand),
-    Day22(AoC2Subcommand),
-    Day23(AoC2Subcommand),
-    Day24(AoC2Subcommand),
-    Day25(AoC2Subcommand),
-}
-
-impl FromArgMatches for Command {
+    #[command(flatten)]
+    Day(DayCommand),
+    /// List the registered solutions
+    List,
+    /// Run both stars for every day that has input, and summarize the results
+    Run(RunSubcommand),
+    /// Time the stars of a single day over repeated runs
+    Bench(BenchSubcommand),
+    /// Download a day's input from the puzzle site, unless it is already cached
+    Fetch(FetchSubcommand),
+    /// Calculate a star and submit it to the puzzle site
+    Submit(SubmitSubcommand),
+    /// Generate solver stubs for a new year, or a single new day
+    New(NewSubcommand),
+    /// Run a day's stored examples and check their answers
+    Example(ExampleSubcommand),
+    /// Show which stars are implemented, and which days have input and stored answers
+    Status(StatusSubcommand),
+}
+
+/// A `dayNN` subcommand, built from the solver registry.
+#[derive(Clone, Debug, Getters)]
+#[getset(get = "pub(crate)")]
+pub(crate) struct DayCommand {
+    day: AoCDay,
+    args: AoC2Subcommand,
+}
+
+/// Parse a `dayNN` argument.
+fn parse_day(day: &str) -> Result<AoCDay, String> {
+    AoCDay::try_from(day).map_err(|_| "Valid days are `day01` through `day25`".to_string())
+}
+
+/// The days that have a registered solver for at least one year.
+fn registered_days() -> impl Iterator<Item = AoCDay> {
+    AoCDay::ALL.into_iter().filter(|day| solver::solvers().any(|solver| solver.day() == *day))
+}
+
+/// Build the `dayNN` subcommand, listing the titles registered for that day.
+fn day_subcommand(day: AoCDay) -> clap::Command {
+    let about = solver::solvers()
+        .filter(|solver| solver.day() == day)
+        .map(|solver| format!("{}: {}", solver.year(), solver.title()))
+        .collect::<Vec<String>>()
+        .join(", ");
+    AoC2Subcommand::augment_args(clap::Command::new(<&str>::from(&day)).about(about))
+}
+
+impl FromArgMatches for DayCommand {
     fn from_arg_matches(matches: &clap::ArgMatches) -> Result<Self, Error> {
         match matches.subcommand() {
-            Some((DAY_1, args)) => Ok(Self::Day01(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_2, args)) => Ok(Self::Day02(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_3, args)) => Ok(Self::Day03(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_4, args)) => Ok(Self::Day04(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_5, args)) => Ok(Self::Day05(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_6, args)) => Ok(Self::Day06(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_7, args)) => Ok(Self::Day07(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_8, args)) => Ok(Self::Day08(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_9, args)) => Ok(Self::Day09(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_10, args)) => Ok(Self::Day10(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_11, args)) => Ok(Self::Day11(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_12, args)) => Ok(Self::Day12(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_13, args)) => Ok(Self::Day13(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_14, args)) => Ok(Self::Day14(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_15, args)) => Ok(Self::Day15(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_16, args)) => Ok(Self::Day16(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_17, args)) => Ok(Self::Day17(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_18, args)) => Ok(Self::Day18(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_19, args)) => Ok(Self::Day19(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_20, args)) => Ok(Self::Day20(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_21, args)) => Ok(Self::Day21(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_22, args)) => Ok(Self::Day22(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_23, args)) => Ok(Self::Day23(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_24, args)) => Ok(Self::Day24(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((DAY_25, args)) => Ok(Self::Day25(AoC2Subcommand::from_arg_matches(args)?)),
-            Some((_, _)) => Err(Error::raw(ErrorKind::InvalidSubcommand, "Valid subcommands are `day01` through `day25`")),
+            Some((name, args)) => {
+                let day = AoCDay::try_from(name).map_err(|_| Error::raw(ErrorKind::InvalidSubcommand, "Valid subcommands are `day01` through `day25`"))?;
+                Ok(Self {
+                    day,
+                    args: AoC2Subcommand::from_arg_matches(args)?,
+                })
+            }
             None => Err(Error::raw(ErrorKind::MissingSubcommand, "Valid subcommands are `day01` through `day25`")),
         }
     }
 
     fn update_from_arg_matches(&mut self, matches: &clap::ArgMatches) -> Result<(), Error> {
-        match matches.subcommand() {
-            Some((DAY_1, args)) => *self = Self::Day01(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_2, args)) => *self = Self::Day02(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_3, args)) => *self = Self::Day03(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_4, args)) => *self = Self::Day04(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_5, args)) => *self = Self::Day05(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_6, args)) => *self = Self::Day06(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_7, args)) => *self = Self::Day07(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_8, args)) => *self = Self::Day08(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_9, args)) => *self = Self::Day09(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_10, args)) => *self = Self::Day10(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_11, args)) => *self = Self::Day11(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_12, args)) => *self = Self::Day12(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_13, args)) => *self = Self::Day13(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_14, args)) => *self = Self::Day14(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_15, args)) => *self = Self::Day15(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_16, args)) => *self = Self::Day16(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_17, args)) => *self = Self::Day17(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_18, args)) => *self = Self::Day18(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_19, args)) => *self = Self::Day19(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_20, args)) => *self = Self::Day20(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_21, args)) => *self = Self::Day21(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_22, args)) => *self = Self::Day22(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_23, args)) => *self = Self::Day23(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_24, args)) => *self = Self::Day24(AoC2Subcommand::from_arg_matches(args)?),
-            Some((DAY_25, args)) => *self = Self::Day25(AoC2Subcommand::from_arg_matches(args)?),
-            Some((_, _)) => {
-                return Err(Error::raw(ErrorKind::InvalidSubcommand, "Valid subcommands are `add` and `remove`"));
-            }
-            None => (),
+        if matches.subcommand_name().is_some() {
+            *self = Self::from_arg_matches(matches)?;
         }
         Ok(())
     }
 }
 
-impl Subcommand for Command {
+impl Subcommand for DayCommand {
     fn augment_subcommands(cmd: clap::Command) -> clap::Command {
-        cmd.subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_1)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_2)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_3)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_4)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_5)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_6)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_7)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_8)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_9)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_10)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_11)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_12)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_13)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_14)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_15)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_16)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_17)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_18)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_19)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_20)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_21)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_22)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_23)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_24)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_25)))
-            .subcommand_required(true)
+        registered_days().fold(cmd, |cmd, day| cmd.subcommand(day_subcommand(day)))
     }
 
     fn augment_subcommands_for_update(cmd: clap::Command) -> clap::Command {
-        cmd.subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_1)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_2)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_3)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_4)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_5)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_6)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_7)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_8)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_9)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_10)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_11)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_12)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_13)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_14)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_15)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_16)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_17)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_18)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_19)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_20)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_21)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_22)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_23)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_24)))
-            .subcommand(AoC2Subcommand::augment_args(clap::Command::new(DAY_25)))
-            .subcommand_required(true)
+        Self::augment_subcommands(cmd)
     }
 
     fn has_subcommand(name: &str) -> bool {
-        matches!(
-            name,
-            DAY_1
-                | DAY_2
-                | DAY_3
-                | DAY_4
-                | DAY_5
-                | DAY_6
-                | DAY_7
-                | DAY_8
-                | DAY_9
-                | DAY_10
-                | DAY_11
-                | DAY_12
-                | DAY_13
-                | DAY_14
-                | DAY_15
-                | DAY_16
-                | DAY_17
-                | DAY_18
-                | DAY_19
-                | DAY_20
-                | DAY_21
-                | DAY_22
-                | DAY_23
-                | DAY_24
-                | DAY_25
-        )
+        AoCDay::try_from(name).is_ok_and(|day| registered_days().any(|registered| registered == day))
     }
 }
 
 #[derive(Clone, Debug, Getters, Parser)]
 #[getset(get = "pub(crate)")]
 pub(crate) struct AoC2Subcommand {
-    #[arg(name = "file", short, long, default_value_t = String::from("data_file"))]
-    file: String,
+    #[arg(name = "file", short, long, help = FILE_HELP)]
+    #[getset(skip)]
+    file: Option<String>,
+    #[arg(
+        name = "input-string",
+        short,
+        long,
+        conflicts_with = "file",
+        help = "Use the given text as the input, i.e. a serial number or step count"
+    )]
+    input_string: Option<String>,
     #[arg(
         name = "second",
         short,
@@ -236,4 +210,182 @@ pub(crate) struct AoC2Subcommand {
         conflicts_with = "second"
     )]
     both: bool,
+    #[arg(
+        name = "verify",
+        long,
+        help = "Run both stars and compare them against the day's answers file",
+        conflicts_with = "second"
+    )]
+    verify: bool,
+    #[arg(
+        name = "record",
+        long,
+        help = "Run both stars and write them to the day's answers file",
+        conflicts_with = "second"
+    )]
+    record: bool,
+    #[arg(
+        name = "watch",
+        short,
+        long,
+        help = "Re-run whenever the input file, or with --verify the answers file, changes",
+        conflicts_with = "input-string"
+    )]
+    watch: bool,
+}
+
+impl AoC2Subcommand {
+    /// The `--file` given on the command line, or the configured default.
+    pub(crate) fn file(&self) -> &str {
+        file_or_default(&self.file)
+    }
+}
+
+#[derive(Clone, Debug, Getters, Parser)]
+#[getset(get = "pub(crate)")]
+#[command(group(ArgGroup::new("years").required(true).args(["year", "all"])))]
+pub(crate) struct RunSubcommand {
+    #[arg(name = "year", short, long, help = "Run every day of the given year")]
+    year: Option<String>,
+    #[arg(name = "all", short, long, help = "Run every day of every year")]
+    all: bool,
+    #[arg(name = "file", short, long, help = FILE_HELP)]
+    #[getset(skip)]
+    file: Option<String>,
+    #[arg(name = "verify", long, help = "Compare the answers against each day's answers file, where there is one")]
+    verify: bool,
+    #[arg(name = "record", long, help = "Write the answers to each day's answers file when both stars succeed")]
+    record: bool,
+    #[arg(
+        name = "jobs",
+        short,
+        long,
+        default_value_t = NonZeroUsize::MIN,
+        help = "Run up to this many stars at once; results are still reported in order, and stars under --max-memory run one at a time"
+    )]
+    jobs: NonZeroUsize,
+}
+
+impl RunSubcommand {
+    /// The `--file` given on the command line, or the configured default.
+    pub(crate) fn file(&self) -> &str {
+        file_or_default(&self.file)
+    }
+}
+
+#[derive(Clone, Debug, Getters, Parser)]
+#[getset(get = "pub(crate)")]
+pub(crate) struct BenchSubcommand {
+    #[arg(name = "year", short, long, help = "Specify the year you wish to work with, if not given before the subcommand")]
+    year: Option<String>,
+    #[arg(name = "day", value_parser = parse_day, help = "The day to benchmark, i.e. `day09`")]
+    day: AoCDay,
+    #[arg(name = "file", short, long, help = FILE_HELP)]
+    #[getset(skip)]
+    file: Option<String>,
+    #[arg(name = "part", short, long, value_parser = clap::value_parser!(u8).range(1..=2), help = "Only benchmark the given star")]
+    part: Option<u8>,
+    #[arg(name = "iterations", short = 'n', long, default_value_t = 10, help = "The number of timed runs of each star")]
+    iterations: usize,
+    #[arg(
+        name = "warmup",
+        short,
+        long,
+        default_value_t = 1,
+        help = "The number of untimed runs of each star before timing starts"
+    )]
+    warmup: usize,
+    #[arg(name = "save", long, help = "Save the results as a JSON baseline")]
+    save: Option<PathBuf>,
+    #[arg(name = "baseline", long, help = "Compare the results against a saved JSON baseline")]
+    baseline: Option<PathBuf>,
+    #[arg(
+        name = "threshold",
+        long,
+        default_value_t = 10.,
+        help = "Flag a regression when a median is more than this percent slower than the baseline"
+    )]
+    threshold: f64,
+}
+
+impl BenchSubcommand {
+    /// The `--file` given on the command line, or the configured default.
+    pub(crate) fn file(&self) -> &str {
+        file_or_default(&self.file)
+    }
+}
+
+#[derive(Clone, Debug, Getters, Parser)]
+#[getset(get = "pub(crate)")]
+pub(crate) struct FetchSubcommand {
+    #[arg(name = "year", short, long, help = "Specify the year you wish to work with, if not given before the subcommand")]
+    year: Option<String>,
+    #[arg(name = "day", value_parser = parse_day, help = "The day to fetch, i.e. `day20`")]
+    day: AoCDay,
+    #[arg(name = "file", short, long, help = FILE_HELP)]
+    #[getset(skip)]
+    file: Option<String>,
+    #[arg(name = "base-url", long, help = "The puzzle site to fetch from, overriding AOC_BASE_URL")]
+    base_url: Option<String>,
+}
+
+impl FetchSubcommand {
+    /// The `--file` given on the command line, or the configured default.
+    pub(crate) fn file(&self) -> &str {
+        file_or_default(&self.file)
+    }
+}
+
+#[derive(Clone, Debug, Getters, Parser)]
+#[getset(get = "pub(crate)")]
+pub(crate) struct SubmitSubcommand {
+    #[arg(name = "year", short, long, help = "Specify the year you wish to work with, if not given before the subcommand")]
+    year: Option<String>,
+    #[arg(name = "day", value_parser = parse_day, help = "The day to submit, i.e. `day15`")]
+    day: AoCDay,
+    #[arg(name = "part", short, long, value_parser = clap::value_parser!(u8).range(1..=2), help = "The star to submit")]
+    part: u8,
+    #[arg(name = "file", short, long, help = FILE_HELP)]
+    #[getset(skip)]
+    file: Option<String>,
+    #[arg(name = "base-url", long, help = "The puzzle site to submit to, overriding AOC_BASE_URL")]
+    base_url: Option<String>,
+}
+
+impl SubmitSubcommand {
+    /// The `--file` given on the command line, or the configured default.
+    pub(crate) fn file(&self) -> &str {
+        file_or_default(&self.file)
+    }
+}
+
+#[derive(Clone, Debug, Getters, Parser)]
+#[getset(get = "pub(crate)")]
+pub(crate) struct NewSubcommand {
+    #[arg(name = "year", short, long, help = "The year to add, or to add a day to")]
+    year: u16,
+    #[arg(name = "day", short, long, value_parser = clap::value_parser!(u8).range(1..=25), help = "Only generate this day, 1 through 25")]
+    day: Option<u8>,
+    #[arg(name = "title", short, long, default_value_t = String::from("Untitled"), help = "The puzzle title, when generating a single day")]
+    title: String,
+}
+
+#[derive(Clone, Debug, Getters, Parser)]
+#[getset(get = "pub(crate)")]
+pub(crate) struct StatusSubcommand {
+    #[arg(name = "year", short, long, help = "Only show the given year, rather than every year")]
+    year: Option<String>,
+    #[arg(name = "markdown", short, long, help = "Write Markdown tables, i.e. for a README")]
+    markdown: bool,
+}
+
+#[derive(Clone, Debug, Getters, Parser)]
+#[getset(get = "pub(crate)")]
+pub(crate) struct ExampleSubcommand {
+    #[arg(name = "year", short, long, help = "Specify the year you wish to work with, if not given before the subcommand")]
+    year: Option<String>,
+    #[arg(name = "day", value_parser = parse_day, help = "The day whose examples to run, i.e. `day15`")]
+    day: AoCDay,
+    #[arg(name = "name", short, long, help = "Only run the named example")]
+    name: Option<String>,
 }
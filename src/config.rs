@@ -0,0 +1,224 @@
+// Copyright (c) 2017 aoc developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! User configuration
+//!
+//! Defaults come from three places, and the first one that sets a value wins:
+//!
+//! 1. the command line, i.e. `--year`, `--file` or `--format`
+//! 2. `AOC_YEAR`, `AOC_DATA`, `AOC_FILE`, `AOC_FORMAT` and `AOC_SESSION`
+//! 3. the config file, given with `--config` or found at `$XDG_CONFIG_HOME/aoc/config.toml`
+//!    (`~/.config/aoc/config.toml` when `XDG_CONFIG_HOME` is not set)
+//!
+//! ```text
+//! year = "2017"
+//! data = "/home/me/aoc-inputs"
+//! file = "input"
+//! format = "json"
+//! session = "53616c7465645f5f..."
+//! ```
+//!
+//! Anything left unset falls back to the built-in defaults below.
+use crate::output::Format;
+use anyhow::{Context, Result, anyhow};
+use clap::ValueEnum;
+use getset::Getters;
+use serde::Deserialize;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// The year used when none is configured.
+pub(crate) const DEFAULT_YEAR: &str = "2018";
+/// The directory holding the `<year>/<day>/` input directories, when none is configured.
+pub(crate) const DEFAULT_DATA: &str = "data";
+/// The input file name used when none is configured.
+pub(crate) const DEFAULT_FILE: &str = "data_file";
+/// The name of the config file under the user's `aoc` config directory.
+const CONFIG_FILE: &str = "config.toml";
+
+/// The settings in effect for this run, once set by [`init`].
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+/// The user's `aoc` config directory.
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("aoc"))
+}
+
+/// Defaults from a config file or the environment, any of which may be unset.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Config {
+    /// The default year
+    year: Option<String>,
+    /// The data root
+    data: Option<PathBuf>,
+    /// The default input file name
+    file: Option<String>,
+    /// The default output format
+    format: Option<Format>,
+    /// The puzzle site session token
+    session: Option<String>,
+}
+
+impl Config {
+    /// Parse a TOML config file.
+    pub(crate) fn parse(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Load the config file at `path`, or from the default location when no path is given.
+    ///
+    /// A missing file is only an error when it was asked for by name.
+    pub(crate) fn load(path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match config_dir().map(|dir| dir.join(CONFIG_FILE)) {
+                Some(path) if path.is_file() => path,
+                _ => return Ok(Self::default()),
+            },
+        };
+        let text = fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    /// Read the `AOC_*` variables through `var`, so tests need not touch the real environment.
+    pub(crate) fn from_vars<F>(var: F) -> Result<Self>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let format = match var("AOC_FORMAT") {
+            Some(format) => Some(Format::from_str(&format, true).map_err(|_| anyhow!("AOC_FORMAT must be `text` or `json`, not `{format}`"))?),
+            None => None,
+        };
+        Ok(Self {
+            year: var("AOC_YEAR"),
+            data: var("AOC_DATA").map(PathBuf::from),
+            file: var("AOC_FILE"),
+            format,
+            session: var("AOC_SESSION"),
+        })
+    }
+
+    /// Read the `AOC_*` environment variables, ignoring empty ones.
+    pub(crate) fn from_env() -> Result<Self> {
+        Self::from_vars(|name| env::var(name).ok().filter(|value| !value.is_empty()))
+    }
+
+    /// Fill in anything unset here from `fallback`.
+    pub(crate) fn or(self, fallback: Self) -> Self {
+        Self {
+            year: self.year.or(fallback.year),
+            data: self.data.or(fallback.data),
+            file: self.file.or(fallback.file),
+            format: self.format.or(fallback.format),
+            session: self.session.or(fallback.session),
+        }
+    }
+}
+
+/// The defaults in effect, with nothing left unset except the session token.
+#[derive(Clone, Debug, Eq, Getters, PartialEq)]
+#[getset(get = "pub(crate)")]
+pub(crate) struct Settings {
+    /// The default year
+    year: String,
+    /// The data root
+    data: PathBuf,
+    /// The default input file name
+    file: String,
+    /// The default output format
+    format: Format,
+    /// The puzzle site session token, if one is configured
+    session: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::from(Config::default())
+    }
+}
+
+impl From<Config> for Settings {
+    fn from(config: Config) -> Self {
+        Self {
+            year: config.year.unwrap_or_else(|| DEFAULT_YEAR.to_string()),
+            data: config.data.unwrap_or_else(|| PathBuf::from(DEFAULT_DATA)),
+            file: config.file.unwrap_or_else(|| DEFAULT_FILE.to_string()),
+            format: config.format.unwrap_or_default(),
+            session: config.session.map(|session| session.trim().to_string()),
+        }
+    }
+}
+
+impl Settings {
+    /// Resolve the settings from the environment and the config file at `path`, or the default one.
+    pub(crate) fn resolve(path: Option<&Path>) -> Result<Self> {
+        Ok(Config::from_env()?.or(Config::load(path)?).into())
+    }
+}
+
+/// Make `settings` the ones in effect for the rest of the run.
+pub(crate) fn init(settings: Settings) -> Result<()> {
+    SETTINGS.set(settings).map_err(|_| anyhow!("Settings are already initialized"))
+}
+
+/// The settings in effect, which are the built-in defaults until [`init`] is called.
+pub(crate) fn settings() -> &'static Settings {
+    SETTINGS.get_or_init(Settings::default)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Config, Settings};
+    use crate::output::Format;
+    use anyhow::Result;
+    use std::{collections::HashMap, path::PathBuf};
+
+    #[test]
+    fn precedence() -> Result<()> {
+        let file = Config::parse("year = \"2016\"\ndata = \"/srv/inputs\"\nformat = \"json\"\nsession = \"abc\\n\"\n")?;
+        let vars = HashMap::from([("AOC_YEAR", "2017"), ("AOC_FILE", "input"), ("AOC_FORMAT", "Text")]);
+        let env = Config::from_vars(|name| vars.get(name).map(|value| value.to_string()))?;
+
+        let settings = Settings::from(env.or(file));
+        assert_eq!(settings.year(), "2017");
+        assert_eq!(settings.data(), &PathBuf::from("/srv/inputs"));
+        assert_eq!(settings.file(), "input");
+        assert_eq!(settings.format(), &Format::Text);
+        assert_eq!(settings.session().as_deref(), Some("abc"));
+        Ok(())
+    }
+
+    #[test]
+    fn defaults() -> Result<()> {
+        let settings = Settings::from(Config::from_vars(|_| None)?);
+        assert_eq!(settings, Settings::default());
+        assert_eq!(settings.year(), "2018");
+        assert_eq!(settings.data(), &PathBuf::from("data"));
+        assert_eq!(settings.file(), "data_file");
+        assert!(Config::parse("editor = \"vim\"").is_err());
+        assert!(Config::from_vars(|name| (name == "AOC_FORMAT").then(|| "yaml".to_string())).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn load() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("config.toml");
+        assert!(Config::load(Some(&path)).is_err());
+        std::fs::write(&path, "file = \"input\"\n")?;
+        assert_eq!(Config::load(Some(&path))?.file.as_deref(), Some("input"));
+        Ok(())
+    }
+}
@@ -1,8 +1,10 @@
 //! Constants
 
 use anyhow::{Error, Result, anyhow};
+use std::fmt;
 
 /// Advent of Code Year
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum AoCYear {
     /// Advent of Code 2015
     AOC2015,
@@ -14,7 +16,12 @@ pub enum AoCYear {
     AOC2018,
 }
 
-impl<'a> From<&'a AoCYear> for &'a str {
+impl AoCYear {
+    /// Every supported year, in order.
+    pub const ALL: [AoCYear; 4] = [AoCYear::AOC2015, AoCYear::AOC2016, AoCYear::AOC2017, AoCYear::AOC2018];
+}
+
+impl From<&AoCYear> for &'static str {
     fn from(year: &AoCYear) -> Self {
         match *year {
             AoCYear::AOC2015 => "2015",
@@ -25,6 +32,12 @@ impl<'a> From<&'a AoCYear> for &'a str {
     }
 }
 
+impl fmt::Display for AoCYear {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.into())
+    }
+}
+
 impl TryFrom<&str> for AoCYear {
     type Error = Error;
     fn try_from(year: &str) -> Result<Self> {
@@ -39,6 +52,7 @@ impl TryFrom<&str> for AoCYear {
 }
 
 /// Advent of Code Days
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum AoCDay {
     /// Day 1
     AOCD01,
@@ -92,7 +106,43 @@ pub enum AoCDay {
     AOCD25,
 }
 
-impl<'a> From<&'a AoCDay> for &'a str {
+impl AoCDay {
+    /// Every puzzle day, in order.
+    pub const ALL: [AoCDay; 25] = [
+        AoCDay::AOCD01,
+        AoCDay::AOCD02,
+        AoCDay::AOCD03,
+        AoCDay::AOCD04,
+        AoCDay::AOCD05,
+        AoCDay::AOCD06,
+        AoCDay::AOCD07,
+        AoCDay::AOCD08,
+        AoCDay::AOCD09,
+        AoCDay::AOCD10,
+        AoCDay::AOCD11,
+        AoCDay::AOCD12,
+        AoCDay::AOCD13,
+        AoCDay::AOCD14,
+        AoCDay::AOCD15,
+        AoCDay::AOCD16,
+        AoCDay::AOCD17,
+        AoCDay::AOCD18,
+        AoCDay::AOCD19,
+        AoCDay::AOCD20,
+        AoCDay::AOCD21,
+        AoCDay::AOCD22,
+        AoCDay::AOCD23,
+        AoCDay::AOCD24,
+        AoCDay::AOCD25,
+    ];
+
+    /// The day of the month, 1 through 25.
+    pub fn number(self) -> u8 {
+        self as u8 + 1
+    }
+}
+
+impl From<&AoCDay> for &'static str {
     fn from(year: &AoCDay) -> Self {
         match *year {
             AoCDay::AOCD01 => DAY_1,
@@ -124,6 +174,22 @@ impl<'a> From<&'a AoCDay> for &'a str {
     }
 }
 
+impl fmt::Display for AoCDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.into())
+    }
+}
+
+impl TryFrom<&str> for AoCDay {
+    type Error = Error;
+    fn try_from(day: &str) -> Result<Self> {
+        AoCDay::ALL
+            .into_iter()
+            .find(|candidate| <&str>::from(candidate) == day)
+            .ok_or_else(|| anyhow!("Unable to convert to day!"))
+    }
+}
+
 /// Day 1
 pub const DAY_1: &str = "day01";
 /// Day 2
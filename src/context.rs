@@ -0,0 +1,119 @@
+// Copyright (c) 2017 aoc developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Solver diagnostics
+//!
+//! Tracing and visualization are both off unless asked for, and always write to
+//! stderr so they never mix with answers on stdout.  The context also carries the
+//! run's resource limits, which solvers with long loops check as they go.
+use crate::limits::{self, LimitExceeded};
+use std::{
+    io::{self, Write},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Clears the terminal and moves the cursor home before each frame.
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// What a solver may write about itself while it runs, and how long and how much memory it may take.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Context {
+    /// Whether to trace the solver's steps
+    trace: bool,
+    /// The pause after each visualization frame, if visualizing
+    frame_delay: Option<Duration>,
+    /// How long a star may run, if limited
+    timeout: Option<Duration>,
+    /// How many bytes of heap a star may use, if limited
+    max_memory: Option<usize>,
+    /// When the running star's time is up, once it has started
+    deadline: Option<Instant>,
+}
+
+impl Context {
+    /// A context that traces if `trace` is set, and visualizes with `frame_delay` between frames if one is given.
+    pub fn new(trace: bool, frame_delay: Option<Duration>) -> Self {
+        Self {
+            trace,
+            frame_delay,
+            ..Self::default()
+        }
+    }
+
+    /// The same context, with each star limited to `timeout` and `max_memory` bytes of heap where given.
+    pub fn with_limits(self, timeout: Option<Duration>, max_memory: Option<usize>) -> Self {
+        Self { timeout, max_memory, ..self }
+    }
+
+    /// How long a star may run, if limited.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// How many bytes of heap a star may use, if limited.
+    pub fn max_memory(&self) -> Option<usize> {
+        self.max_memory
+    }
+
+    /// Whether stars run under a time or memory limit.
+    pub fn limited(&self) -> bool {
+        self.timeout.is_some() || self.max_memory.is_some()
+    }
+
+    /// The context for a star starting now, whose deadline is one timeout away.
+    pub(crate) fn started(&self) -> Self {
+        Self {
+            deadline: self.timeout.map(|timeout| Instant::now() + timeout),
+            ..*self
+        }
+    }
+
+    /// The time left before the running star's deadline, if it has one.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Fail once the running star is past its deadline, or the heap has outgrown the memory limit.
+    ///
+    /// Solvers call this from loops that can run for a long time, so they stop soon after a limit is hit.
+    pub fn check(&self) -> Result<(), LimitExceeded> {
+        if let (Some(timeout), Some(deadline)) = (self.timeout, self.deadline)
+            && Instant::now() >= deadline
+        {
+            return Err(LimitExceeded::TimedOut(timeout));
+        }
+        self.reserve(0)
+    }
+
+    /// Fail if allocating another `bytes` would take the heap past the memory limit.
+    pub fn reserve(&self, bytes: usize) -> Result<(), LimitExceeded> {
+        match self.max_memory {
+            Some(max) if limits::heap_in_use().saturating_add(bytes) > max => Err(LimitExceeded::OutOfMemory(max)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether solvers should trace their steps to stderr.
+    pub fn tracing(&self) -> bool {
+        self.trace
+    }
+
+    /// Whether solvers should draw their state to stderr as it changes.
+    pub fn visualizing(&self) -> bool {
+        self.frame_delay.is_some()
+    }
+
+    /// Draw a frame on a cleared terminal, then pause for the frame delay.  Does nothing unless visualizing.
+    pub fn frame(&self, frame: &str) {
+        if let Some(delay) = self.frame_delay {
+            let _ = write!(io::stderr(), "{CLEAR_SCREEN}{frame}");
+            thread::sleep(delay);
+        }
+    }
+}
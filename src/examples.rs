@@ -0,0 +1,207 @@
+// Copyright (c) 2017 aoc developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Named example inputs
+//!
+//! A day's examples live in `data/<year>/<day>/examples/`.  Each example is an
+//! input file named for the example, i.e. `3`, with its expected answers in a
+//! sibling answers file, i.e. `3.answers`, in the same format as the day's
+//! `answers` file.  A star with no expected answer is run but not checked.
+use crate::{
+    answers::Answers,
+    constants::{AoCDay, AoCYear},
+    context::Context,
+    run::input_path,
+    solver::{Solver, Star},
+};
+use anyhow::{Context as _, Result, anyhow};
+use getset::Getters;
+use std::{fmt, fs, io::Write, path::PathBuf};
+
+/// The directory holding a day's examples.
+const EXAMPLES_DIR: &str = "examples";
+/// The extension of an example's expected answers file.
+const ANSWERS_EXTENSION: &str = "answers";
+
+/// A named example input, with its expected answers.
+#[derive(Clone, Debug, Getters)]
+#[getset(get = "pub(crate)")]
+pub(crate) struct Example {
+    /// The example name, i.e. its file name
+    name: String,
+    /// The example input
+    input: String,
+    /// The expected answers
+    expected: Answers,
+}
+
+/// How a single star of an example fared.
+#[derive(Debug)]
+pub(crate) enum Check {
+    /// The answer matched the expected answer
+    Pass,
+    /// The answer did not match
+    Fail { expected: String, actual: String },
+    /// There was no expected answer to check against
+    Unchecked(String),
+    /// The solver failed
+    Error(String),
+}
+
+impl Check {
+    /// Whether this check should fail the run.
+    pub(crate) fn is_failure(&self) -> bool {
+        matches!(self, Check::Fail { .. } | Check::Error(_))
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Check::Pass => f.write_str("ok"),
+            Check::Fail { expected, actual } => write!(f, "FAILED, expected {expected} but got {actual}"),
+            Check::Unchecked(actual) => write!(f, "{actual} (unchecked)"),
+            Check::Error(e) => write!(f, "error: {e}"),
+        }
+    }
+}
+
+/// The directory holding a day's examples.
+pub(crate) fn examples_dir(year: AoCYear, day: AoCDay) -> PathBuf {
+    input_path(year, day, EXAMPLES_DIR)
+}
+
+/// Load a day's examples, ordered by name with numeric names in numeric order.
+pub(crate) fn load(year: AoCYear, day: AoCDay) -> Result<Vec<Example>> {
+    let dir = examples_dir(year, day);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut examples = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("reading {}", dir.display()))? {
+        let path = entry?.path();
+        if !path.is_file() || path.extension().is_some_and(|extension| extension == ANSWERS_EXTENSION) {
+            continue;
+        }
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| anyhow!("{} is not a valid example name", path.display()))?
+            .to_string();
+        let input = fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        let answers_path = path.with_extension(ANSWERS_EXTENSION);
+        let expected = if answers_path.is_file() {
+            Answers::parse(&fs::read_to_string(&answers_path)?).with_context(|| format!("parsing {}", answers_path.display()))?
+        } else {
+            Answers::default()
+        };
+        examples.push(Example { name, input, expected });
+    }
+
+    examples.sort_by_key(|example| (example.name.parse::<u64>().ok(), example.name.clone()));
+    Ok(examples)
+}
+
+/// The input of a day's named example, for a solver's own tests.
+#[cfg(test)]
+pub(crate) fn input(year: AoCYear, day: AoCDay, name: &str) -> Result<String> {
+    load(year, day)?
+        .into_iter()
+        .find(|example| example.name == name)
+        .map(|example| example.input)
+        .ok_or_else(|| anyhow!("No example named `{name}` in {}", examples_dir(year, day).display()))
+}
+
+impl Example {
+    /// Run one star of the example, and check it against the expected answer.
+    pub(crate) fn check(&self, solver: &dyn Solver, part: u8) -> (Star, Check) {
+        let star = Star::time(|| {
+            let reader = &mut self.input.as_bytes();
+            solver.star(part, reader, &Context::default())
+        });
+        let check = match (star.answer(), self.expected.part(part)) {
+            (Err(e), _) => Check::Error(e.to_string()),
+            (Ok(answer), None) => Check::Unchecked(answer.to_string()),
+            (Ok(answer), Some(expected)) if *expected == answer.to_string() => Check::Pass,
+            (Ok(answer), Some(expected)) => Check::Fail {
+                expected: expected.clone(),
+                actual: answer.to_string(),
+            },
+        };
+        (star, check)
+    }
+
+    /// The stars worth running: those with an expected answer, or both if there are none.
+    pub(crate) fn parts(&self) -> Vec<u8> {
+        let expected: Vec<u8> = [1, 2].into_iter().filter(|part| self.expected.part(*part).is_some()).collect();
+        if expected.is_empty() { vec![1, 2] } else { expected }
+    }
+}
+
+/// Run a day's examples, or just the named one, writing a line per star.
+///
+/// Returns the number of failed stars.
+pub(crate) fn run_examples<W: Write>(out: &mut W, solver: &dyn Solver, name: Option<&String>) -> Result<usize> {
+    let (year, day) = (solver.year(), solver.day());
+    let examples: Vec<Example> = load(year, day)?
+        .into_iter()
+        .filter(|example| name.is_none_or(|name| example.name == *name))
+        .collect();
+
+    if examples.is_empty() {
+        let wanted = name.map_or_else(|| "No examples".to_string(), |name| format!("No example named `{name}`"));
+        return Err(anyhow!("{wanted} in {}", examples_dir(year, day).display()));
+    }
+
+    let mut failures = 0;
+    for example in &examples {
+        for part in example.parts() {
+            let (star, check) = example.check(solver, part);
+            writeln!(out, "example {} part {part}: {check} ({:.3?})", example.name, star.elapsed())?;
+            if check.is_failure() {
+                failures += 1;
+            }
+        }
+    }
+    Ok(failures)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Check, load};
+    use crate::constants::{AoCDay, AoCYear};
+    use crate::solver;
+    use anyhow::Result;
+
+    /// Every stored example, for every registered solver, produces its expected answers.
+    #[test]
+    fn examples() -> Result<()> {
+        let mut failures = Vec::new();
+
+        for solver in solver::solvers() {
+            for example in load(solver.year(), solver.day())? {
+                for part in example.parts() {
+                    if let (_, check @ (Check::Fail { .. } | Check::Error(_))) = example.check(solver, part) {
+                        failures.push(format!("{} {} example {} part {part}: {check}", solver.year(), solver.day(), example.name));
+                    }
+                }
+            }
+        }
+
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn ordering() -> Result<()> {
+        let names: Vec<String> = load(AoCYear::AOC2017, AoCDay::AOCD01)?.into_iter().map(|example| example.name).collect();
+        assert_eq!(names, (1..=8).map(|name| name.to_string()).collect::<Vec<String>>());
+        Ok(())
+    }
+}
@@ -0,0 +1,415 @@
+// Copyright (c) 2017 aoc developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Two-dimensional grids
+//!
+//! Positions are `[x, y]`, with `x` counting columns to the right and `y` counting
+//! rows down from the top left, so "reading order" is row by row, left to right.
+//! The neighbor iterators yield positions in reading order too, except the wrapping ones:
+//! they keep the order of the offsets around `pos`, so a neighbor wrapped across an edge
+//! comes where it would have unwrapped, not where it lands.
+use anyhow::{Context as _, Result, anyhow};
+use std::{
+    fmt,
+    io::BufRead,
+    ops::{Index, IndexMut},
+};
+
+/// The offsets of the four orthogonal neighbors, in reading order.
+const NEIGHBORS4: [[isize; 2]; 4] = [[0, -1], [-1, 0], [1, 0], [0, 1]];
+/// The offsets of all eight neighbors, in reading order.
+const NEIGHBORS8: [[isize; 2]; 8] = [[-1, -1], [0, -1], [1, -1], [-1, 0], [1, 0], [-1, 1], [0, 1], [1, 1]];
+
+/// A fixed-size grid of cells, stored row by row.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Grid<T> {
+    /// The number of columns
+    width: usize,
+    /// The number of rows
+    height: usize,
+    /// The cells, in reading order
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A `width` by `height` grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Parse a grid of one cell per character, sized to fit the longest line.
+    ///
+    /// Lines shorter than the longest are padded with `T::default()`, and an error from `map`
+    /// is reported with the line and column of the character it rejected.
+    pub fn parse<R, F>(reader: R, mut map: F) -> Result<Self>
+    where
+        R: BufRead,
+        T: Clone + Default,
+        F: FnMut(char) -> Result<T>,
+    {
+        let mut rows = Vec::new();
+        for (y, line) in reader.lines().enumerate() {
+            let row = line?
+                .chars()
+                .enumerate()
+                .map(|(x, ch)| map(ch).with_context(|| format!("line {}, column {}", y + 1, x + 1)))
+                .collect::<Result<Vec<T>>>()?;
+            rows.push(row);
+        }
+
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let mut grid = Self::new(width, rows.len(), T::default());
+        for (y, row) in rows.into_iter().enumerate() {
+            for (x, cell) in row.into_iter().enumerate() {
+                grid[[x, y]] = cell;
+            }
+        }
+        Ok(grid)
+    }
+
+    /// A grid of the same size, with `f` applied to every cell.
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// The number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether `[x, y]` lies within the grid.
+    pub fn contains(&self, [x, y]: [usize; 2]) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// The cell at `pos`, if it lies within the grid.
+    pub fn get(&self, pos: [usize; 2]) -> Option<&T> {
+        self.contains(pos).then(|| &self.cells[pos[1] * self.width + pos[0]])
+    }
+
+    /// The cell at `pos` for writing, if it lies within the grid.
+    pub fn get_mut(&mut self, pos: [usize; 2]) -> Option<&mut T> {
+        if self.contains(pos) {
+            Some(&mut self.cells[pos[1] * self.width + pos[0]])
+        } else {
+            None
+        }
+    }
+
+    /// The cell at `[x, y]`, wrapping around each edge to the opposite one.
+    ///
+    /// # Panics
+    ///
+    /// If the grid is empty.
+    pub fn get_wrapping(&self, [x, y]: [isize; 2]) -> &T {
+        &self[wrap([x, y], self.width, self.height)]
+    }
+
+    /// Every position, in reading order.
+    pub fn positions(&self) -> impl Iterator<Item = [usize; 2]> + use<T> {
+        let (width, height) = (self.width, self.height);
+        (0..height).flat_map(move |y| (0..width).map(move |x| [x, y]))
+    }
+
+    /// Every cell, in reading order.
+    pub fn cells(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// Every cell with its position, in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = ([usize; 2], &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// Every row, from the top.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The positions above, left of, right of and below `pos` that lie within the grid.
+    pub fn neighbors4(&self, pos: [usize; 2]) -> impl Iterator<Item = [usize; 2]> + use<T> {
+        self.neighbors(pos, &NEIGHBORS4)
+    }
+
+    /// The positions of all eight cells around `pos` that lie within the grid.
+    pub fn neighbors8(&self, pos: [usize; 2]) -> impl Iterator<Item = [usize; 2]> + use<T> {
+        self.neighbors(pos, &NEIGHBORS8)
+    }
+
+    /// The four orthogonal neighbors of `pos`, wrapping around each edge to the opposite one.
+    ///
+    /// They come above, left, right then below, which is not reading order once one wraps.
+    pub fn wrapping_neighbors4(&self, pos: [usize; 2]) -> impl Iterator<Item = [usize; 2]> + use<T> {
+        self.wrapping_neighbors(pos, &NEIGHBORS4)
+    }
+
+    /// All eight neighbors of `pos`, wrapping around each edge to the opposite one.
+    ///
+    /// They come in the order they would have unwrapped, which is not reading order once one wraps.
+    pub fn wrapping_neighbors8(&self, pos: [usize; 2]) -> impl Iterator<Item = [usize; 2]> + use<T> {
+        self.wrapping_neighbors(pos, &NEIGHBORS8)
+    }
+
+    /// The positions at `offsets` from `pos` that lie within the grid.
+    fn neighbors(&self, [x, y]: [usize; 2], offsets: &'static [[isize; 2]]) -> impl Iterator<Item = [usize; 2]> + use<T> {
+        let (width, height) = (self.width, self.height);
+        offsets.iter().filter_map(move |[dx, dy]| {
+            let pos = [x.checked_add_signed(*dx)?, y.checked_add_signed(*dy)?];
+            (pos[0] < width && pos[1] < height).then_some(pos)
+        })
+    }
+
+    /// The positions at `offsets` from `pos`, wrapped onto the grid.
+    fn wrapping_neighbors(&self, [x, y]: [usize; 2], offsets: &'static [[isize; 2]]) -> impl Iterator<Item = [usize; 2]> + use<T> {
+        let (width, height) = (self.width, self.height);
+        offsets.iter().map(move |[dx, dy]| {
+            // Positions within the grid always fit in an `isize`, as the cells they index do.
+            wrap([x as isize + dx, y as isize + dy], width, height)
+        })
+    }
+}
+
+impl Grid<char> {
+    /// Parse a grid of characters, padding short lines with spaces.
+    pub fn parse_chars<R: BufRead>(reader: R) -> Result<Self> {
+        let grid = Grid::<Option<char>>::parse(reader, |ch| Ok(Some(ch)))?;
+        Ok(Self {
+            width: grid.width,
+            height: grid.height,
+            cells: grid.cells.into_iter().map(|ch| ch.unwrap_or(' ')).collect(),
+        })
+    }
+}
+
+/// Wrap `[x, y]` onto a `width` by `height` grid.
+fn wrap([x, y]: [isize; 2], width: usize, height: usize) -> [usize; 2] {
+    // The dimensions of any grid that fits in memory fit in an `isize`.
+    let (width, height) = (width as isize, height as isize);
+    [x.rem_euclid(width) as usize, y.rem_euclid(height) as usize]
+}
+
+impl<T> Index<[usize; 2]> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: [usize; 2]) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside a {}x{} grid", self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<[usize; 2]> for Grid<T> {
+    fn index_mut(&mut self, pos: [usize; 2]) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos).unwrap_or_else(|| panic!("{pos:?} is outside a {width}x{height} grid"))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows().take(self.height) {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> TryFrom<Vec<Vec<T>>> for Grid<T> {
+    type Error = anyhow::Error;
+
+    /// A grid from its rows, which must all be the same length.
+    fn try_from(rows: Vec<Vec<T>>) -> Result<Self> {
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return Err(anyhow!("every row of a grid must be {width} cells wide"));
+        }
+        Ok(Self {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+}
+
+/// A grid without edges, which grows to take in any position written to.
+///
+/// Positions are signed, and every cell not yet written holds the fill value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnboundedGrid<T> {
+    /// The position of the top left cell of `cells`
+    origin: [i64; 2],
+    /// The cells written so far, and any fill around them
+    cells: Grid<T>,
+    /// The value of every cell outside `cells`
+    fill: T,
+}
+
+impl<T: Clone> UnboundedGrid<T> {
+    /// An empty grid, where every cell holds `fill`.
+    pub fn new(fill: T) -> Self {
+        Self::from_grid(Grid::new(0, 0, fill.clone()), [0, 0], fill)
+    }
+
+    /// A grid holding `grid` with its top left cell at `origin`, and `fill` everywhere else.
+    pub fn from_grid(grid: Grid<T>, origin: [i64; 2], fill: T) -> Self {
+        Self { origin, cells: grid, fill }
+    }
+
+    /// The cell at `pos`.
+    pub fn get(&self, pos: [i64; 2]) -> &T {
+        self.local(pos).and_then(|pos| self.cells.get(pos)).unwrap_or(&self.fill)
+    }
+
+    /// The cell at `pos` for writing, growing the grid to take it in if needed.
+    pub fn get_mut(&mut self, pos: [i64; 2]) -> &mut T {
+        if self.local(pos).is_none_or(|local| !self.cells.contains(local)) {
+            self.grow(pos);
+        }
+        let local = self.local(pos).unwrap_or_default();
+        &mut self.cells[local]
+    }
+
+    /// The top left and bottom right positions of the cells held so far, if there are any.
+    pub fn bounds(&self) -> Option<([i64; 2], [i64; 2])> {
+        let [x, y] = self.origin;
+        let (width, height) = (i64::try_from(self.cells.width).ok()?, i64::try_from(self.cells.height).ok()?);
+        (width > 0 && height > 0).then_some(([x, y], [x + width - 1, y + height - 1]))
+    }
+
+    /// The four orthogonal neighbors of `pos`, in reading order.
+    pub fn neighbors4(&self, [x, y]: [i64; 2]) -> impl Iterator<Item = [i64; 2]> + use<T> {
+        NEIGHBORS4.iter().map(move |[dx, dy]| [x + *dx as i64, y + *dy as i64])
+    }
+
+    /// All eight neighbors of `pos`, in reading order.
+    pub fn neighbors8(&self, [x, y]: [i64; 2]) -> impl Iterator<Item = [i64; 2]> + use<T> {
+        NEIGHBORS8.iter().map(move |[dx, dy]| [x + *dx as i64, y + *dy as i64])
+    }
+
+    /// The position within `cells` of `pos`, if it is not above or left of them.
+    fn local(&self, [x, y]: [i64; 2]) -> Option<[usize; 2]> {
+        Some([usize::try_from(x - self.origin[0]).ok()?, usize::try_from(y - self.origin[1]).ok()?])
+    }
+
+    /// Grow the held cells to take in `pos`, at least doubling on each side that grows so that
+    /// walking off an edge stays cheap.
+    fn grow(&mut self, [x, y]: [i64; 2]) {
+        let ([left, top], [right, bottom]) = self.bounds().unwrap_or(([x, y], [x, y]));
+        let (width, height) = (right - left + 1, bottom - top + 1);
+        let new_left = if x < left { x.min(left - width) } else { left };
+        let new_top = if y < top { y.min(top - height) } else { top };
+        let new_right = if x > right { x.max(right + width) } else { right };
+        let new_bottom = if y > bottom { y.max(bottom + height) } else { bottom };
+
+        let size = |low: i64, high: i64| usize::try_from(high - low + 1).unwrap_or_default();
+        let mut cells = Grid::new(size(new_left, new_right), size(new_top, new_bottom), self.fill.clone());
+        let offset = size(new_left, left) - 1;
+        let row_offset = size(new_top, top) - 1;
+        for ([cx, cy], cell) in self.cells.iter() {
+            cells[[cx + offset, cy + row_offset]] = cell.clone();
+        }
+        self.origin = [new_left, new_top];
+        self.cells = cells;
+    }
+}
+
+impl<T: Clone + fmt::Display> fmt::Display for UnboundedGrid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.cells)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Grid, UnboundedGrid};
+    use anyhow::{Result, anyhow};
+    use std::io::Cursor;
+
+    #[test]
+    fn parse_and_render() -> Result<()> {
+        let grid = Grid::parse_chars(Cursor::new("#.#\n.\n##"))?;
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid[[2, 0]], '#');
+        assert_eq!(grid[[1, 1]], ' ');
+        assert_eq!(grid.to_string(), "#.#\n.  \n## \n");
+
+        let bits = Grid::parse(Cursor::new("01\n10"), |ch| ch.to_digit(2).ok_or(anyhow!("not a bit")))?;
+        assert_eq!(
+            bits.iter().filter(|(_, bit)| **bit == 1).map(|(pos, _)| pos).collect::<Vec<_>>(),
+            [[1, 0], [0, 1]]
+        );
+        let error = Grid::parse(Cursor::new("01\n12"), |ch| ch.to_digit(2).ok_or(anyhow!("not a bit"))).err();
+        assert_eq!(error.map(|e| format!("{e:#}")), Some("line 2, column 2: not a bit".to_string()));
+        assert!(Grid::try_from(vec![vec![1, 2], vec![3]]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn neighbors() -> Result<()> {
+        let grid = Grid::try_from(vec![vec![0; 3]; 3])?;
+        assert_eq!(grid.neighbors4([1, 1]).collect::<Vec<_>>(), [[1, 0], [0, 1], [2, 1], [1, 2]]);
+        assert_eq!(grid.neighbors4([0, 0]).collect::<Vec<_>>(), [[1, 0], [0, 1]]);
+        assert_eq!(grid.neighbors8([2, 2]).collect::<Vec<_>>(), [[1, 1], [2, 1], [1, 2]]);
+        assert_eq!(grid.neighbors8([1, 1]).count(), 8);
+        assert_eq!(grid.wrapping_neighbors4([0, 0]).collect::<Vec<_>>(), [[0, 2], [2, 0], [1, 0], [0, 1]]);
+        assert_eq!(
+            grid.wrapping_neighbors8([0, 0]).collect::<Vec<_>>(),
+            [[2, 2], [0, 2], [1, 2], [2, 0], [1, 0], [2, 1], [0, 1], [1, 1]]
+        );
+        assert_eq!(grid.positions().take(4).collect::<Vec<_>>(), [[0, 0], [1, 0], [2, 0], [0, 1]]);
+        Ok(())
+    }
+
+    #[test]
+    fn wrapping() -> Result<()> {
+        let grid = Grid::try_from(vec![vec!['a', 'b'], vec!['c', 'd']])?;
+        assert_eq!(*grid.get_wrapping([-1, 0]), 'b');
+        assert_eq!(*grid.get_wrapping([2, 3]), 'c');
+        assert_eq!(grid.get([2, 0]), None);
+        Ok(())
+    }
+
+    #[test]
+    fn unbounded() -> Result<()> {
+        let mut grid = UnboundedGrid::from_grid(Grid::parse_chars(Cursor::new("..#\n#..\n..."))?, [-1, -1], '.');
+        assert_eq!(*grid.get([1, -1]), '#');
+        assert_eq!(*grid.get([-40, 7]), '.');
+        *grid.get_mut([-3, 2]) = 'W';
+        *grid.get_mut([4, -5]) = 'F';
+        assert_eq!(*grid.get([-3, 2]), 'W');
+        assert_eq!(*grid.get([4, -5]), 'F');
+        assert_eq!(*grid.get([1, -1]), '#');
+        assert_eq!(*grid.get([-1, 0]), '#');
+        let ([left, top], [right, bottom]) = grid.bounds().ok_or(anyhow!("no bounds"))?;
+        assert!(left <= -3 && top <= -5 && right >= 4 && bottom >= 2);
+        assert_eq!(grid.neighbors4([0, 0]).collect::<Vec<_>>(), [[0, -1], [-1, 0], [1, 0], [0, 1]]);
+
+        let mut empty = UnboundedGrid::new(0);
+        *empty.get_mut([5, 5]) += 1;
+        assert_eq!((*empty.get([5, 5]), empty.bounds()), (1, Some(([5, 5], [5, 5]))));
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright (c) 2017 aoc developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Submitted guess history
+//!
+//! Every answer submitted for a day is kept in a `guesses` TOML file next to its
+//! input, along with the verdict it got, so known-bad guesses are never resent.
+use crate::{
+    constants::{AoCDay, AoCYear},
+    run::input_path,
+    site::Verdict,
+};
+use anyhow::{Context, Result, anyhow};
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The name of the guess history file kept next to each day's input.
+pub(crate) const GUESSES_FILE: &str = "guesses";
+
+/// A single submitted answer.
+#[derive(Clone, Debug, Deserialize, Eq, Getters, PartialEq, Serialize)]
+#[getset(get = "pub(crate)")]
+pub(crate) struct Guess {
+    /// The star, 1 or 2
+    part: u8,
+    /// The submitted answer
+    answer: String,
+    /// The site's verdict
+    verdict: Verdict,
+}
+
+/// Every answer submitted for a single day.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct History {
+    /// The guesses, oldest first
+    #[serde(default, rename = "guess")]
+    guesses: Vec<Guess>,
+}
+
+impl History {
+    /// The path of the guess history file for the given day.
+    pub(crate) fn path(year: AoCYear, day: AoCDay) -> PathBuf {
+        input_path(year, day, GUESSES_FILE)
+    }
+
+    /// Load the guess history at `path`, which is empty if the file does not exist yet.
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    /// Save the guess history to `path`.
+    pub(crate) fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, toml::to_string(self)?).with_context(|| format!("writing {}", path.display()))
+    }
+
+    /// Remember a submitted answer and its verdict.
+    pub(crate) fn record(&mut self, part: u8, answer: &str, verdict: Verdict) {
+        self.guesses.push(Guess {
+            part,
+            answer: answer.to_string(),
+            verdict,
+        });
+    }
+
+    /// Check that an answer is worth submitting, given what earlier guesses have taught us.
+    ///
+    /// Refuses a star that is already solved, an answer already judged wrong, and a
+    /// numeric answer at or beyond a known too-high or too-low bound.
+    pub(crate) fn check(&self, part: u8, answer: &str) -> Result<()> {
+        let guesses = || self.guesses.iter().filter(|guess| guess.part == part);
+
+        if let Some(correct) = guesses().find(|guess| guess.verdict == Verdict::Correct) {
+            return Err(anyhow!("Part {part} is already solved with {}", correct.answer));
+        }
+        if let Some(wrong) = guesses().find(|guess| guess.answer == answer && guess.verdict.is_wrong()) {
+            return Err(anyhow!("{answer} was already submitted for part {part}, and was {}", wrong.verdict));
+        }
+
+        if let Ok(value) = answer.parse::<i128>() {
+            let bound = |verdict| {
+                guesses()
+                    .filter(move |guess| guess.verdict == verdict)
+                    .filter_map(|guess| guess.answer.parse::<i128>().ok())
+            };
+
+            if let Some(high) = bound(Verdict::TooHigh).min().filter(|high| value >= *high) {
+                return Err(anyhow!("{answer} is not below {high}, which is already known to be too high"));
+            }
+            if let Some(low) = bound(Verdict::TooLow).max().filter(|low| value <= *low) {
+                return Err(anyhow!("{answer} is not above {low}, which is already known to be too low"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::History;
+    use crate::site::Verdict;
+    use anyhow::Result;
+
+    #[test]
+    fn refusals() {
+        let mut history = History::default();
+        history.record(2, "5000", Verdict::TooHigh);
+        history.record(2, "4000", Verdict::TooLow);
+        history.record(2, "4500", Verdict::Wrong);
+        history.record(2, "4600", Verdict::RateLimited(30));
+
+        assert!(history.check(2, "4988").is_ok());
+        assert!(history.check(2, "4600").is_ok());
+        assert!(history.check(2, "4500").is_err());
+        assert!(history.check(2, "5000").is_err());
+        assert!(history.check(2, "6000").is_err());
+        assert!(history.check(2, "3999").is_err());
+        assert!(history.check(1, "6000").is_ok());
+
+        history.record(1, "27730", Verdict::Correct);
+        assert!(history.check(1, "27731").is_err());
+    }
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("guesses");
+        let mut history = History::default();
+        history.record(1, "27730", Verdict::Correct);
+        history.record(2, "4000", Verdict::TooLow);
+        history.record(2, "4001", Verdict::RateLimited(37));
+        history.save(&path)?;
+        assert_eq!(History::load(&path)?, history);
+        assert_eq!(History::load(&dir.path().join("missing"))?, History::default());
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+// Copyright (c) 2017 aoc developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Advent of Code solvers
+//!
+//! Every registered day can be run on any reader or string, without going through
+//! the command line:
+//!
+//! ```
+//! use aoc::{Answer, AoCDay, AoCYear};
+//!
+//! let answer = aoc::solve_str(AoCYear::AOC2015, AoCDay::AOCD01, 1, "(()(()(")?;
+//! assert_eq!(answer, Answer::Signed(3));
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+mod answer;
+mod answers;
+mod batch;
+mod bench;
+mod cli;
+mod config;
+mod constants;
+mod context;
+mod examples;
+mod grid;
+mod history;
+mod limits;
+mod output;
+mod parse;
+mod run;
+mod scaffold;
+mod site;
+mod solver;
+mod status;
+mod utils;
+mod watch;
+mod year2015;
+mod year2016;
+mod year2017;
+mod year2018;
+
+pub use crate::{
+    answer::Answer,
+    constants::{AoCDay, AoCYear},
+    context::Context,
+    grid::{Grid, UnboundedGrid},
+    limits::{LimitExceeded, TrackingAllocator},
+    parse::ParseError,
+    run::run,
+    solver::{Panicked, Solver, Star, Unsolved, find, solve, solve_str, solvers},
+};
@@ -0,0 +1,319 @@
+// Copyright (c) 2017 aoc developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Time and memory limits for solvers
+//!
+//! A star run under a limit gets a worker thread of its own.  The timeout is
+//! enforced twice: solvers with long loops call [`Context::check`] and give up once
+//! their deadline passes, and the runner stops waiting shortly after that, leaving
+//! behind any solver that never checks.
+//!
+//! Memory is the heap in use by the whole process, as counted by
+//! [`TrackingAllocator`], so it is only measured when the binary installs it as
+//! the global allocator, and stars under a memory limit are never run alongside
+//! each other.
+use crate::{
+    answer::Answer,
+    context::Context,
+    solver::{self, Solver, Star},
+};
+use anyhow::{Result, anyhow};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt,
+    io::BufRead,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, RecvTimeoutError},
+    },
+    thread,
+    time::Duration,
+};
+use thiserror::Error;
+
+/// How long the runner keeps waiting past the deadline for a solver to notice it.
+const GRACE: Duration = Duration::from_millis(250);
+/// The size suffixes understood by [`parse_size`], largest first.
+const SIZE_UNITS: [(&str, usize); 3] = [("G", 1 << 30), ("M", 1 << 20), ("K", 1 << 10)];
+
+/// The bytes of heap currently allocated.
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+/// The most bytes of heap allocated at once since the last [`reset_peak`].
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// A star was stopped for going over a limit.
+#[derive(Clone, Copy, Debug, Eq, Error, PartialEq)]
+pub enum LimitExceeded {
+    /// The star ran past the timeout
+    #[error("timed out after {0:?}")]
+    TimedOut(Duration),
+    /// The heap grew past the memory limit, in bytes
+    #[error("memory limit of {} exceeded", Size(*.0))]
+    OutOfMemory(usize),
+}
+
+/// A byte count, written with the largest suffix that divides it exactly.
+struct Size(usize);
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match SIZE_UNITS.iter().find(|(_, unit)| self.0 > 0 && self.0.is_multiple_of(*unit)) {
+            Some((suffix, unit)) => write!(f, "{}{suffix}", self.0 / unit),
+            None => write!(f, "{} bytes", self.0),
+        }
+    }
+}
+
+/// The system allocator, counting the bytes in use so memory limits can be enforced.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TrackingAllocator;
+
+impl TrackingAllocator {
+    /// Count `size` more bytes in use.
+    fn grew(size: usize) {
+        let live = LIVE.fetch_add(size, Ordering::Relaxed) + size;
+        let _ = PEAK.fetch_max(live, Ordering::Relaxed);
+    }
+}
+
+// SAFETY: every call is passed straight to the system allocator, and only counted on success.
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // SAFETY: the caller upholds `GlobalAlloc::alloc`'s contract.
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            Self::grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        // SAFETY: the caller upholds `GlobalAlloc::alloc_zeroed`'s contract.
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            Self::grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: the caller upholds `GlobalAlloc::dealloc`'s contract.
+        unsafe { System.dealloc(ptr, layout) };
+        let _ = LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // SAFETY: the caller upholds `GlobalAlloc::realloc`'s contract.
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            let _ = LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+            Self::grew(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// The bytes of heap in use right now, or 0 if [`TrackingAllocator`] is not installed.
+pub(crate) fn heap_in_use() -> usize {
+    LIVE.load(Ordering::Relaxed)
+}
+
+/// Start measuring the peak heap afresh.
+fn reset_peak() {
+    PEAK.store(heap_in_use(), Ordering::Relaxed);
+}
+
+/// The most bytes of heap in use at once since the last [`reset_peak`].
+fn peak_heap() -> usize {
+    PEAK.load(Ordering::Relaxed)
+}
+
+/// Parse a timeout such as `90`, `90s`, `1.5m` or `500ms`, where a bare number is in seconds.
+pub(crate) fn parse_duration(text: &str) -> Result<Duration, String> {
+    let (number, scale) = if let Some(number) = text.strip_suffix("ms") {
+        (number, 0.001)
+    } else if let Some(number) = text.strip_suffix('s') {
+        (number, 1.0)
+    } else if let Some(number) = text.strip_suffix('m') {
+        (number, 60.0)
+    } else {
+        (text, 1.0)
+    };
+    number
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .and_then(|number| Duration::try_from_secs_f64(number * scale).ok())
+        .filter(|duration| !duration.is_zero())
+        .ok_or_else(|| format!("expected a duration like `30`, `30s`, `2m` or `500ms`, not `{text}`"))
+}
+
+/// Parse a memory size such as `1048576`, `512K`, `64M` or `2G`, where a bare number is in bytes.
+pub(crate) fn parse_size(text: &str) -> Result<usize, String> {
+    let upper = text.trim().to_ascii_uppercase();
+    let upper = upper.strip_suffix('B').unwrap_or(&upper);
+    let (number, unit) = SIZE_UNITS
+        .iter()
+        .find_map(|(suffix, unit)| upper.strip_suffix(suffix).map(|number| (number, *unit)))
+        .unwrap_or((upper, 1));
+    number
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .and_then(|number| number.checked_mul(unit))
+        .filter(|size| *size > 0)
+        .ok_or_else(|| format!("expected a size like `1048576`, `512K`, `64M` or `2G`, not `{text}`"))
+}
+
+/// Calculate one star under the limits in `ctx`, or directly when there are none.
+pub(crate) fn star(solver: &'static dyn Solver, part: u8, reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer> {
+    if !ctx.limited() {
+        return solver.star(part, reader, ctx);
+    }
+    let mut input = String::new();
+    let _ = reader.read_to_string(&mut input)?;
+
+    let ctx = ctx.started();
+    reset_peak();
+    let (sender, receiver) = mpsc::channel();
+    let _handle = thread::Builder::new()
+        .name(format!("{} {} part {part}", solver.year(), solver.day()))
+        .spawn(move || {
+            let _ = sender.send(solver::catch_panic(|| solver.star(part, &mut input.as_bytes(), &ctx)));
+        })?;
+
+    let answer = match ctx.timeout() {
+        Some(timeout) => match receiver.recv_timeout(timeout + GRACE) {
+            Ok(answer) => answer,
+            Err(RecvTimeoutError::Timeout) => return Err(LimitExceeded::TimedOut(timeout).into()),
+            Err(RecvTimeoutError::Disconnected) => return Err(anyhow!("the solver thread stopped without an answer")),
+        },
+        None => receiver.recv()?,
+    };
+    match ctx.max_memory() {
+        Some(max) if peak_heap() > max => Err(LimitExceeded::OutOfMemory(max).into()),
+        _ => answer,
+    }
+}
+
+/// Calculate both stars under the limits in `ctx`, or with [`Solver::both`] when there are none.
+///
+/// Limited stars are timed and stopped one at a time, so they cannot share work.
+pub(crate) fn both(solver: &'static dyn Solver, reader: &mut dyn BufRead, ctx: &Context) -> Result<(Star, Star)> {
+    if !ctx.limited() {
+        return solver.both(reader, ctx);
+    }
+    let mut input = String::new();
+    let _ = reader.read_to_string(&mut input)?;
+    let first = Star::time(|| star(solver, 1, &mut input.as_bytes(), ctx));
+    let second = Star::time(|| star(solver, 2, &mut input.as_bytes(), ctx));
+    Ok((first, second))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{LIVE, LimitExceeded, TrackingAllocator, heap_in_use, parse_duration, parse_size, star};
+    use crate::{
+        answer::Answer,
+        constants::{AoCDay, AoCYear},
+        context::Context,
+        solver::Solver,
+    };
+    use anyhow::Result;
+    use std::{
+        alloc::{GlobalAlloc, Layout},
+        io::BufRead,
+        sync::atomic::Ordering,
+        thread,
+        time::Duration,
+    };
+
+    /// Spins until told to stop, checking its limits as it goes unless told not to in its input.
+    struct Spin;
+
+    impl Solver for Spin {
+        fn year(&self) -> AoCYear {
+            AoCYear::AOC2016
+        }
+
+        fn day(&self) -> AoCDay {
+            AoCDay::AOCD01
+        }
+
+        fn title(&self) -> &'static str {
+            "Spin"
+        }
+
+        fn part1(&self, _reader: &mut dyn BufRead) -> Result<Answer> {
+            Ok(Answer::Unsigned(1))
+        }
+
+        fn part2(&self, _reader: &mut dyn BufRead) -> Result<Answer> {
+            panic!("dizzy")
+        }
+
+        fn star(&self, part: u8, reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer> {
+            let mut input = String::new();
+            let _ = reader.read_to_string(&mut input)?;
+            match input.as_str() {
+                "check" => loop {
+                    ctx.check()?;
+                },
+                "sleep" => {
+                    thread::sleep(Duration::from_secs(5));
+                    Ok(Answer::Unsigned(0))
+                }
+                _ if part == 1 => self.part1(reader),
+                _ => self.part2(reader),
+            }
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("1.5m"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("soon").is_err());
+        assert_eq!(parse_size("1048576"), Ok(1 << 20));
+        assert_eq!(parse_size("512k"), Ok(512 << 10));
+        assert_eq!(parse_size("64MB"), Ok(64 << 20));
+        assert!(parse_size("lots").is_err());
+        assert_eq!(LimitExceeded::OutOfMemory(64 << 20).to_string(), "memory limit of 64M exceeded");
+        assert_eq!(LimitExceeded::TimedOut(Duration::from_millis(50)).to_string(), "timed out after 50ms");
+    }
+
+    #[test]
+    fn timeouts() -> Result<()> {
+        let ctx = Context::default().with_limits(Some(Duration::from_millis(50)), None);
+        for input in ["check", "sleep"] {
+            let error = star(&Spin, 1, &mut input.as_bytes(), &ctx).err().map(|e| e.downcast::<LimitExceeded>().ok());
+            assert_eq!(error, Some(Some(LimitExceeded::TimedOut(Duration::from_millis(50)))));
+        }
+        assert_eq!(star(&Spin, 1, &mut "".as_bytes(), &ctx)?, Answer::Unsigned(1));
+        assert_eq!(star(&Spin, 2, &mut "".as_bytes(), &ctx).err().map(|e| e.to_string()), Some("dizzy".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn memory() {
+        let ctx = Context::default().with_limits(None, Some(1 << 10));
+        assert_eq!(ctx.reserve(1 << 20), Err(LimitExceeded::OutOfMemory(1 << 10)));
+
+        let before = heap_in_use();
+        let layout = Layout::new::<[u64; 16]>();
+        // SAFETY: the layout is non-zero in size, and the block is freed with the same layout.
+        unsafe {
+            let ptr = TrackingAllocator.alloc(layout);
+            assert_eq!(LIVE.load(Ordering::Relaxed), before + 128);
+            TrackingAllocator.dealloc(ptr, layout);
+        }
+        assert_eq!(heap_in_use(), before);
+    }
+}
@@ -7,24 +7,22 @@
 // modified, or distributed except according to those terms.
 
 //! `aoc` 0.1.0
-mod cli;
-mod constants;
-mod run;
-mod utils;
-mod year2015;
-mod year2016;
-mod year2017;
-mod year2018;
-
 use std::io::{self, Write};
 use std::process;
 
-/// CLI Entry Point
+/// Count heap use, so `--max-memory` can be enforced.
+#[global_allocator]
+static ALLOCATOR: aoc::TrackingAllocator = aoc::TrackingAllocator;
+
 fn main() {
-    match run::run() {
+    match aoc::run() {
         Ok(i) => process::exit(i),
         Err(e) => {
-            writeln!(io::stderr(), "{e}").expect("Unable to write to stderr!");
+            let message = match e.downcast_ref::<aoc::ParseError>() {
+                Some(parse_error) => parse_error.render(),
+                None => format!("{e}\n"),
+            };
+            write!(io::stderr(), "{message}").expect("Unable to write to stderr!");
             process::exit(1)
         }
     }
@@ -0,0 +1,97 @@
+// Copyright (c) 2017 aoc developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Machine-readable output
+use crate::{
+    answer::Answer,
+    constants::{AoCDay, AoCYear},
+};
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::{io::Write, time::Duration};
+
+/// How results are written to stdout.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Format {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// One JSON object per star, one per line
+    Json,
+}
+
+/// A single star, as written in JSON output.
+#[derive(Debug, Serialize)]
+pub(crate) struct Record {
+    /// The puzzle year
+    year: String,
+    /// The puzzle day
+    day: String,
+    /// The star, 1 or 2
+    part: u8,
+    /// The answer, if there was one
+    answer: Option<String>,
+    /// The kind of answer, i.e. `unsigned` or `text`
+    #[serde(rename = "type")]
+    kind: Option<&'static str>,
+    /// How long the star took, in seconds
+    duration: f64,
+    /// Why there is no answer, if there isn't one
+    error: Option<String>,
+}
+
+impl Record {
+    /// Describe a star by its answer, or the error that prevented one.
+    pub(crate) fn new(year: AoCYear, day: AoCDay, part: u8, answer: Result<&Answer, String>, elapsed: Duration) -> Self {
+        let (answer, kind, error) = match answer {
+            Ok(answer) => (Some(answer.to_string()), Some(answer.kind()), None),
+            Err(e) => (None, None, Some(e)),
+        };
+        Self {
+            year: year.to_string(),
+            day: day.to_string(),
+            part,
+            answer,
+            kind,
+            duration: elapsed.as_secs_f64(),
+            error,
+        }
+    }
+
+    /// Write the record as a single line of JSON.
+    pub(crate) fn write<W: Write>(&self, out: &mut W) -> Result<()> {
+        serde_json::to_writer(&mut *out, self)?;
+        writeln!(out)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Record;
+    use crate::answer::Answer;
+    use crate::constants::{AoCDay, AoCYear};
+    use anyhow::Result;
+    use std::time::Duration;
+
+    #[test]
+    fn records() -> Result<()> {
+        let mut out = Vec::new();
+        let answer = Answer::Art("#..#\n####\n".to_string());
+        Record::new(AoCYear::AOC2018, AoCDay::AOCD10, 1, Ok(&answer), Duration::from_millis(1500)).write(&mut out)?;
+        Record::new(AoCYear::AOC2018, AoCDay::AOCD10, 2, Err("no input".to_string()), Duration::ZERO).write(&mut out)?;
+        assert_eq!(
+            String::from_utf8(out)?,
+            "{\"year\":\"2018\",\"day\":\"day10\",\"part\":1,\"answer\":\"#..#\\n####\",\"type\":\"art\",\"duration\":1.5,\"error\":null}\n\
+             {\"year\":\"2018\",\"day\":\"day10\",\"part\":2,\"answer\":null,\"type\":null,\"duration\":0.0,\"error\":\"no input\"}\n"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright (c) 2017 aoc developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Puzzle input errors
+use crate::constants::{AoCDay, AoCYear};
+use getset::Getters;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// A line of puzzle input that a solver could not make sense of.
+#[derive(Clone, Debug, Eq, Error, Getters, PartialEq)]
+#[getset(get = "pub")]
+pub struct ParseError {
+    /// The puzzle year
+    year: AoCYear,
+    /// The puzzle day
+    day: AoCDay,
+    /// The line number, counting from 1
+    line: usize,
+    /// The column of the offending token, in characters counting from 1
+    column: usize,
+    /// The whole offending line
+    text: String,
+    /// The offending token, empty if the line ended too soon
+    found: String,
+    /// What the solver expected instead
+    expected: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} line {}, column {}: expected {}, found ",
+            self.year, self.day, self.line, self.column, self.expected
+        )?;
+        if self.found.is_empty() {
+            f.write_str("the end of the line")
+        } else {
+            write!(f, "`{}`", self.found)
+        }
+    }
+}
+
+impl ParseError {
+    /// An error at `token` within `text`, the line at index `idx` of the input.
+    ///
+    /// `token` is located by address when it was sliced out of `text`, and by search otherwise.
+    pub(crate) fn new(year: AoCYear, day: AoCDay, idx: usize, text: &str, token: &str, expected: impl Into<String>) -> Self {
+        let (start, at) = (text.as_ptr() as usize, token.as_ptr() as usize);
+        let offset = if (start..=start + text.len()).contains(&at) {
+            at - start
+        } else {
+            text.find(token).unwrap_or(0)
+        };
+
+        Self {
+            year,
+            day,
+            line: idx + 1,
+            column: text[..offset].chars().count() + 1,
+            text: text.to_string(),
+            found: token.to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    /// An error blaming the whole of `text`, the line at index `idx` of the input.
+    pub(crate) fn whole_line(year: AoCYear, day: AoCDay, idx: usize, text: &str, expected: impl Into<String>) -> Self {
+        Self::new(year, day, idx, text, text, expected)
+    }
+
+    /// The error followed by the offending line, with a caret under the offending token.
+    pub fn render(&self) -> String {
+        let gutter = " ".repeat(self.line.to_string().len());
+        format!(
+            "{self}\n{gutter} |\n{} | {}\n{gutter} | {}{}\n",
+            self.line,
+            self.text,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.found.chars().count().max(1))
+        )
+    }
+}
+
+/// Parse `token`, found within `text`, the line at index `idx` of the input, as a number.
+pub(crate) fn number<T: FromStr>(year: AoCYear, day: AoCDay, idx: usize, text: &str, token: &str) -> Result<T, ParseError> {
+    token.parse().map_err(|_| ParseError::new(year, day, idx, text, token, "a number"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ParseError, number};
+    use crate::constants::{AoCDay, AoCYear};
+
+    #[test]
+    fn located_by_slice() {
+        let text = "../.# -> ##./#../...";
+        let token = text.split(' ').nth(1).unwrap_or_default();
+        let error = ParseError::new(AoCYear::AOC2017, AoCDay::AOCD21, 2, text, token, "`=>`");
+        assert_eq!((*error.line(), *error.column()), (3, 7));
+        assert_eq!(error.to_string(), "2017 day21 line 3, column 7: expected `=>`, found `->`");
+        assert_eq!(
+            error.render(),
+            "2017 day21 line 3, column 7: expected `=>`, found `->`\n  |\n3 | ../.# -> ##./#../...\n  |       ^^\n"
+        );
+    }
+
+    #[test]
+    fn located_by_search() {
+        let error = ParseError::new(AoCYear::AOC2018, AoCDay::AOCD16, 11, "addx 1 2 3", "addx", "an opcode");
+        assert_eq!(*error.column(), 1);
+        assert!(error.render().ends_with("12 | addx 1 2 3\n   | ^^^^\n"));
+
+        let text = "set a";
+        let error = ParseError::new(AoCYear::AOC2017, AoCDay::AOCD18, 0, text, &text[5..], "a value");
+        assert_eq!(*error.column(), 6);
+        assert_eq!(error.to_string(), "2017 day18 line 1, column 6: expected a value, found the end of the line");
+        assert!(error.render().ends_with("1 | set a\n  |      ^\n"));
+    }
+
+    #[test]
+    fn numbers() {
+        let text = "+7, -x";
+        assert_eq!(number::<i32>(AoCYear::AOC2018, AoCDay::AOCD01, 0, text, &text[..2]), Ok(7));
+        let error = number::<i32>(AoCYear::AOC2018, AoCDay::AOCD01, 0, text, &text[4..]).err();
+        assert_eq!(
+            error.map(|e| e.to_string()),
+            Some("2018 day01 line 1, column 5: expected a number, found `-x`".to_string())
+        );
+    }
+}
@@ -9,73 +9,470 @@
 //! `aoc` runtime
 
 use crate::{
-    cli::{AoC2Subcommand, Args, Command},
+    answer::Answer,
+    answers::Answers,
+    batch,
+    bench::{self, Baseline},
+    cli::{
+        AoC2Subcommand, Args, BenchSubcommand, Command, ExampleSubcommand, FetchSubcommand, NewSubcommand, RunSubcommand, StatusSubcommand, SubmitSubcommand,
+    },
+    config::{self, Settings},
     constants::{AoCDay, AoCYear},
-    year2015, year2016, year2017, year2018,
+    context::Context,
+    examples,
+    history::History,
+    limits,
+    output::{Format, Record},
+    parse::ParseError,
+    scaffold::Scaffold,
+    site::{self, Client, Fetched, Verdict},
+    solver::{self, Solver, Star},
+    status,
+    watch::Watcher,
 };
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use clap::Parser;
 use std::{
     fs::File,
-    io::{self, BufReader, Write},
-    path::PathBuf,
+    io::{self, BufRead, BufReader, Cursor, Read, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
 };
 
-/// Find the solution.
-pub fn find_solution(matches: &AoC2Subcommand, year: &AoCYear, day: &AoCDay) -> Result<u32> {
-    let year_str: &str = year.into();
-    let day_str: &str = day.into();
-    let mut filepath = PathBuf::from("data");
+/// The path of the named input file for the given day.
+pub(crate) fn input_path(year: AoCYear, day: AoCDay, file: &str) -> PathBuf {
+    let year_str: &str = (&year).into();
+    let day_str: &str = (&day).into();
+    let mut filepath = config::settings().data().clone();
     filepath.push(year_str);
     filepath.push(day_str);
-    filepath.push(matches.file().as_str());
+    filepath.push(file);
+    filepath
+}
+
+/// Where a day's puzzle input is read from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Input {
+    /// A file under `<data>/<year>/<day>/`
+    Data(PathBuf),
+    /// A file at a path given on the command line
+    Path(PathBuf),
+    /// Standard input
+    Stdin,
+    /// Text given on the command line
+    Text(String),
+}
+
+impl Input {
+    /// Resolve a `--file` argument, and an `--input-string` that takes precedence over it.
+    ///
+    /// `-` is stdin, a bare file name is looked up under `<data>/<year>/<day>/`, and
+    /// anything with a directory in it is used as given.
+    pub(crate) fn resolve(year: AoCYear, day: AoCDay, file: &str, input_string: Option<&String>) -> Self {
+        let path = Path::new(file);
+        match input_string {
+            Some(text) => Input::Text(text.clone()),
+            None if file == "-" => Input::Stdin,
+            None if path.is_absolute() || path.components().count() > 1 => Input::Path(path.to_path_buf()),
+            None => Input::Data(input_path(year, day, file)),
+        }
+    }
+
+    /// Open the input for reading.
+    pub(crate) fn open(&self) -> Result<Box<dyn BufRead>> {
+        let open = |path: &Path| File::open(path).map_err(|e| anyhow!("Unable to open {}: {e}", path.display()));
+        Ok(match self {
+            Input::Data(path) => Box::new(BufReader::new(open(path).map_err(|e| anyhow!("{e}, try `aoc fetch`"))?)),
+            Input::Path(path) => Box::new(BufReader::new(open(path)?)),
+            Input::Stdin => Box::new(io::stdin().lock()),
+            Input::Text(text) => Box::new(Cursor::new(text.clone().into_bytes())),
+        })
+    }
+
+    /// Read the whole input.
+    pub(crate) fn read_to_string(&self) -> Result<String> {
+        let mut input = String::new();
+        let _ = self.open()?.read_to_string(&mut input)?;
+        Ok(input)
+    }
+}
+
+/// Find the registered solver for the given day, and open its input.
+fn solver_and_input(matches: &AoC2Subcommand, year: AoCYear, day: AoCDay) -> Result<(&'static dyn Solver, Box<dyn BufRead>)> {
+    let solver = solver::find(year, day).ok_or_else(|| anyhow!("No solution is registered for {year} {day}"))?;
+    let input = Input::resolve(year, day, matches.file(), matches.input_string().as_ref());
+
+    Ok((solver, input.open()?))
+}
+
+/// Find the solution.
+pub fn find_solution(matches: &AoC2Subcommand, year: AoCYear, day: AoCDay, ctx: &Context) -> Result<Answer> {
+    let (solver, mut reader) = solver_and_input(matches, year, day)?;
+    limits::star(solver, if *matches.second() { 2 } else { 1 }, &mut reader, ctx)
+}
+
+/// Find the solutions for both stars.
+pub fn find_solutions(matches: &AoC2Subcommand, year: AoCYear, day: AoCDay, ctx: &Context) -> Result<(Star, Star)> {
+    let (solver, mut reader) = solver_and_input(matches, year, day)?;
+    limits::both(solver, &mut reader, ctx)
+}
+
+/// Write a labeled star answer along with the time it took.
+fn write_star<W: Write>(out: &mut W, part: u8, star: &Star) -> Result<()> {
+    let elapsed = star.elapsed();
+    match star.answer() {
+        Ok(answer @ Answer::Art(_)) => writeln!(out, "Part {part} ({elapsed:.3?}):\n{answer}")?,
+        Ok(answer) => writeln!(out, "Part {part}: {answer} ({elapsed:.3?})")?,
+        Err(e) => match e.downcast_ref::<ParseError>() {
+            Some(parse_error) => write!(out, "Part {part} ({elapsed:.3?}): error: {}", parse_error.render())?,
+            None => writeln!(out, "Part {part}: error: {e} ({elapsed:.3?})")?,
+        },
+    }
+    Ok(())
+}
+
+/// Compare the answers computed for a day against the expected answers, writing a diff for each mismatch.
+///
+/// Returns the number of mismatched stars.
+fn verify<W: Write>(out: &mut W, expected: &Answers, year: AoCYear, day: AoCDay, answers: [Option<&Answer>; 2]) -> Result<usize> {
+    let mut mismatches = 0;
+    for (part, answer) in (1..).zip(answers) {
+        if let Some(mismatch) = answer.and_then(|answer| expected.check(year, day, part, answer)) {
+            write!(out, "{mismatch}")?;
+            mismatches += 1;
+        }
+    }
+    Ok(mismatches)
+}
+
+/// Record the answers for a day, provided both stars produced one.
+fn record<W: Write>(out: &mut W, year: AoCYear, day: AoCDay, answers: [Option<&Answer>; 2]) -> Result<()> {
+    if let [Some(first), Some(second)] = answers {
+        let path = Answers::from_answers(first, second).save(year, day)?;
+        writeln!(out, "Recorded answers to {}", path.display())?;
+    } else {
+        writeln!(out, "Not recording answers for {year} {day}: both stars must succeed")?;
+    }
+    Ok(())
+}
 
-    let reader = BufReader::new(File::open(filepath)?);
-    let is_second_star = *matches.second();
+/// Write a star as a JSON record.
+fn write_record<W: Write>(out: &mut W, year: AoCYear, day: AoCDay, part: u8, star: &Star) -> Result<()> {
+    Record::new(year, day, part, star.answer().as_ref().map_err(|e| e.to_string()), *star.elapsed()).write(out)
+}
 
-    match *year {
-        AoCYear::AOC2018 => Ok(year2018::find_solution(reader, day, is_second_star)?),
-        AoCYear::AOC2017 => Ok(year2017::find_solution(reader, day, is_second_star)?),
-        AoCYear::AOC2016 => Ok(year2016::find_solution(reader, day, is_second_star)?),
-        AoCYear::AOC2015 => Ok(year2015::find_solution(reader, day, is_second_star)?),
+/// Where messages that are not results go: stdout for text, and stderr to keep JSON output clean.
+fn messages(format: Format) -> Box<dyn Write> {
+    match format {
+        Format::Text => Box::new(io::stdout()),
+        Format::Json => Box::new(io::stderr()),
     }
 }
 
+/// Run one or both stars of a single day, writing the results to `out`.
+fn run_day<W: Write>(out: &mut W, args: &AoC2Subcommand, year: AoCYear, day: AoCDay, format: Format, ctx: &Context) -> Result<i32> {
+    if !(*args.both() || *args.verify() || *args.record()) {
+        if format == Format::Text {
+            writeln!(out, "{}", find_solution(args, year, day, ctx)?)?;
+            return Ok(0);
+        }
+        let star = Star::time(|| find_solution(args, year, day, ctx));
+        write_record(out, year, day, if *args.second() { 2 } else { 1 }, &star)?;
+        return Ok(if star.answer().is_ok() { 0 } else { 1 });
+    }
+
+    let (first, second) = match find_solutions(args, year, day, ctx) {
+        Ok(stars) => stars,
+        Err(e) if format == Format::Json => {
+            let message = e.to_string();
+            (Star::time(|| Err(anyhow!("{message}"))), Star::time(|| Err(anyhow!("{message}"))))
+        }
+        Err(e) => return Err(e),
+    };
+    match format {
+        Format::Text => {
+            write_star(out, 1, &first)?;
+            write_star(out, 2, &second)?;
+        }
+        Format::Json => {
+            write_record(out, year, day, 1, &first)?;
+            write_record(out, year, day, 2, &second)?;
+        }
+    }
+    let answers = [first.answer().as_ref().ok(), second.answer().as_ref().ok()];
+    let mut code = if answers.contains(&None) { 1 } else { 0 };
+
+    // Checks and recordings go with the results as text, and to stderr to keep JSON output clean.
+    let mut stderr = io::stderr();
+    let mut messages: &mut dyn Write = match format {
+        Format::Text => out,
+        Format::Json => &mut stderr,
+    };
+    if *args.verify() {
+        let expected = Answers::load(year, day)?.ok_or_else(|| anyhow!("No answers are recorded at {}, try --record", Answers::path(year, day).display()))?;
+        if verify(&mut messages, &expected, year, day, answers)? > 0 {
+            code = 1;
+        }
+    }
+    if *args.record() {
+        record(&mut messages, year, day, answers)?;
+    }
+    Ok(code)
+}
+
+/// Re-run a day's selected stars whenever its input, or with `--verify` its answers file, changes.
+fn watch(args: &AoC2Subcommand, year: AoCYear, day: AoCDay, format: Format, ctx: &Context) -> Result<i32> {
+    let mut paths = match Input::resolve(year, day, args.file(), args.input_string().as_ref()) {
+        Input::Data(path) | Input::Path(path) => vec![path],
+        Input::Stdin | Input::Text(_) => return Err(anyhow!("--watch needs an input file to watch")),
+    };
+    if *args.verify() {
+        paths.push(Answers::path(year, day));
+    }
+
+    Watcher::new(paths).watch(&mut io::stdout(), &mut messages(format), |out| {
+        if *args.both() || *args.verify() || *args.record() {
+            let _ = run_day(out, args, year, day, format, ctx)?;
+        } else {
+            let part = if *args.second() { 2 } else { 1 };
+            let star = Star::time(|| find_solution(args, year, day, ctx));
+            match format {
+                Format::Text => write_star(out, part, &star)?,
+                Format::Json => write_record(out, year, day, part, &star)?,
+            }
+        }
+        Ok(())
+    })?;
+    Ok(0)
+}
+
 /// CLI Runtime
 pub fn run() -> Result<i32> {
     // Parse the command line
     let matches = Args::try_parse()?;
+    config::init(Settings::resolve(matches.config().as_deref())?)?;
+    let ctx = Context::new(*matches.trace(), matches.visualize().map(Duration::from_millis)).with_limits(*matches.timeout(), *matches.max_memory());
 
-    let year = AoCYear::try_from(&matches.year()[..])?;
-
-    let match_tuple = match matches.command() {
-        Command::Day01(command) => (command, AoCDay::AOCD01),
-        Command::Day02(command) => (command, AoCDay::AOCD02),
-        Command::Day03(command) => (command, AoCDay::AOCD03),
-        Command::Day04(command) => (command, AoCDay::AOCD04),
-        Command::Day05(command) => (command, AoCDay::AOCD05),
-        Command::Day06(command) => (command, AoCDay::AOCD06),
-        Command::Day07(command) => (command, AoCDay::AOCD07),
-        Command::Day08(command) => (command, AoCDay::AOCD08),
-        Command::Day09(command) => (command, AoCDay::AOCD09),
-        Command::Day10(command) => (command, AoCDay::AOCD10),
-        Command::Day11(command) => (command, AoCDay::AOCD11),
-        Command::Day12(command) => (command, AoCDay::AOCD12),
-        Command::Day13(command) => (command, AoCDay::AOCD13),
-        Command::Day14(command) => (command, AoCDay::AOCD14),
-        Command::Day15(command) => (command, AoCDay::AOCD15),
-        Command::Day16(command) => (command, AoCDay::AOCD16),
-        Command::Day17(command) => (command, AoCDay::AOCD17),
-        Command::Day18(command) => (command, AoCDay::AOCD18),
-        Command::Day19(command) => (command, AoCDay::AOCD19),
-        Command::Day20(command) => (command, AoCDay::AOCD20),
-        Command::Day21(command) => (command, AoCDay::AOCD21),
-        Command::Day22(command) => (command, AoCDay::AOCD22),
-        Command::Day23(command) => (command, AoCDay::AOCD23),
-        Command::Day24(command) => (command, AoCDay::AOCD24),
-        Command::Day25(command) => (command, AoCDay::AOCD25),
+    match matches.command() {
+        Command::Day(command) => {
+            let year = AoCYear::try_from(matches.year())?;
+            if *command.args().watch() {
+                return watch(command.args(), year, *command.day(), matches.format(), &ctx);
+            }
+            return run_day(&mut io::stdout(), command.args(), year, *command.day(), matches.format(), &ctx);
+        }
+        Command::List => {
+            for solver in solver::solvers() {
+                writeln!(io::stdout(), "{} {} {}", solver.year(), solver.day(), solver.title())?;
+            }
+        }
+        Command::Run(command) => return run_batch(command, matches.format(), &ctx),
+        Command::Bench(command) => return run_bench(command, matches.year()),
+        Command::Fetch(command) => fetch(command, matches.year())?,
+        Command::Submit(command) => return submit(command, matches.year()),
+        Command::New(command) => scaffold(command)?,
+        Command::Example(command) => return run_examples(command, matches.year()),
+        Command::Status(command) => status(command)?,
+    }
+    Ok(0)
+}
+
+/// Run every day of a year, or of every year, and print a summary table.
+fn run_batch(command: &RunSubcommand, format: Format, ctx: &Context) -> Result<i32> {
+    let years = match command.year() {
+        Some(year) => vec![AoCYear::try_from(&year[..])?],
+        None => AoCYear::ALL.to_vec(),
     };
+    let days = batch::days_with_input(&years, command.file());
+
+    let start = Instant::now();
+    let reports = batch::run_days(&days, command.file(), *command.jobs(), ctx);
+    match format {
+        Format::Text => batch::write_summary(&mut io::stdout(), &reports, start.elapsed())?,
+        Format::Json => {
+            for report in &reports {
+                Record::new(*report.year(), *report.day(), *report.part(), report.status().as_result(), *report.elapsed()).write(&mut io::stdout())?;
+            }
+        }
+    }
+    let stdout = &mut messages(format);
+    let mut code = if reports.iter().any(|report| report.status().is_failure()) { 1 } else { 0 };
+
+    for pair in reports.chunks(2) {
+        let (year, day) = (*pair[0].year(), *pair[0].day());
+        let answers = [pair[0].status().answer(), pair[1].status().answer()];
 
-    writeln!(io::stdout(), "{}", find_solution(match_tuple.0, &year, &match_tuple.1)?)?;
+        if *command.verify()
+            && let Some(expected) = Answers::load(year, day)?
+            && verify(stdout, &expected, year, day, answers)? > 0
+        {
+            code = 1;
+        }
+        if *command.record() && solver::find(year, day).is_some() {
+            record(stdout, year, day, answers)?;
+        }
+    }
+    Ok(code)
+}
+
+/// Benchmark a single day, optionally saving the results or comparing them against a baseline.
+fn run_bench(command: &BenchSubcommand, default_year: &str) -> Result<i32> {
+    let year = AoCYear::try_from(command.year().as_deref().unwrap_or(default_year))?;
+    let day = *command.day();
+    let solver = solver::find(year, day).ok_or_else(|| anyhow!("No solution is registered for {year} {day}"))?;
+    let input = Input::resolve(year, day, command.file(), None).read_to_string()?;
+    let parts = command.part().map_or(vec![1, 2], |part| vec![part]);
+
+    let results = bench::bench(solver, &input, &parts, *command.warmup(), *command.iterations())?;
+    let stdout = &mut io::stdout();
+    bench::write_stats(stdout, &results)?;
+
+    if let Some(path) = command.save() {
+        results.save(path)?;
+        writeln!(stdout, "Saved baseline to {}", path.display())?;
+    }
+    if let Some(path) = command.baseline() {
+        let regressions = results.regressions(&Baseline::load(path)?, *command.threshold())?;
+        for regression in &regressions {
+            writeln!(stdout, "regression: {regression}")?;
+        }
+        if !regressions.is_empty() {
+            return Ok(1);
+        }
+    }
     Ok(0)
 }
+
+/// Download a day's input to its data path, unless it is already there.
+fn fetch(command: &FetchSubcommand, default_year: &str) -> Result<()> {
+    let year = AoCYear::try_from(command.year().as_deref().unwrap_or(default_year))?;
+    let day = *command.day();
+    let path = input_path(year, day, command.file());
+
+    let base_url = command.base_url().clone().unwrap_or_else(site::base_url);
+    let session = if path.exists() { String::new() } else { site::session_token()? };
+    let client = Client::new(&base_url, &session, site::MIN_INTERVAL, config::settings().data());
+
+    match client.fetch_input(year, day, &path)? {
+        Fetched::Cached => writeln!(io::stdout(), "{} is already cached", path.display())?,
+        Fetched::Downloaded => writeln!(io::stdout(), "Saved {}", path.display())?,
+    }
+    Ok(())
+}
+
+/// Calculate a star and submit it, unless the guess history shows it cannot be right.
+fn submit(command: &SubmitSubcommand, default_year: &str) -> Result<i32> {
+    let year = AoCYear::try_from(command.year().as_deref().unwrap_or(default_year))?;
+    let day = *command.day();
+    let part = *command.part();
+    let solver = solver::find(year, day).ok_or_else(|| anyhow!("No solution is registered for {year} {day}"))?;
+    let reader = &mut Input::resolve(year, day, command.file(), None).open()?;
+
+    let answer = match solver.star(part, reader, &Context::default())? {
+        Answer::Art(art) => return Err(anyhow!("ASCII art answers must be read and submitted by hand:\n{art}")),
+        answer => answer.to_string(),
+    };
+
+    let history_path = History::path(year, day);
+    let mut history = History::load(&history_path)?;
+    history.check(part, &answer)?;
+
+    let base_url = command.base_url().clone().unwrap_or_else(site::base_url);
+    let client = Client::new(&base_url, &site::session_token()?, site::MIN_INTERVAL, config::settings().data());
+    let verdict = client.submit_answer(year, day, part, &answer)?;
+    history.record(part, &answer, verdict);
+    history.save(&history_path)?;
+
+    writeln!(io::stdout(), "{year} {day} part {part}: {answer} is {verdict}")?;
+    Ok(match verdict {
+        Verdict::Correct | Verdict::AlreadySolved => 0,
+        _ => 1,
+    })
+}
+
+/// Run a day's examples, failing if any star does not match its expected answer.
+fn run_examples(command: &ExampleSubcommand, default_year: &str) -> Result<i32> {
+    let year = AoCYear::try_from(command.year().as_deref().unwrap_or(default_year))?;
+    let day = *command.day();
+    let solver = solver::find(year, day).ok_or_else(|| anyhow!("No solution is registered for {year} {day}"))?;
+
+    if examples::run_examples(&mut io::stdout(), solver, command.name().as_ref())? > 0 {
+        Ok(1)
+    } else {
+        Ok(0)
+    }
+}
+
+/// Write the star calendar for one year, or every year.
+fn status(command: &StatusSubcommand) -> Result<()> {
+    let years = match command.year() {
+        Some(year) => vec![AoCYear::try_from(&year[..])?],
+        None => AoCYear::ALL.to_vec(),
+    };
+    let stdout = &mut io::stdout();
+
+    for (idx, year) in years.into_iter().enumerate() {
+        if idx > 0 {
+            writeln!(stdout)?;
+        }
+        let days = status::year_status(year, config::settings().file())?;
+        if *command.markdown() {
+            status::write_markdown(stdout, year, &days)?;
+        } else {
+            status::write_calendar(stdout, year, &days)?;
+        }
+    }
+    if !*command.markdown() {
+        writeln!(stdout)?;
+        status::write_legend(stdout)?;
+    }
+    Ok(())
+}
+
+/// Generate and register the solvers for a new year, or a single new day.
+fn scaffold(command: &NewSubcommand) -> Result<()> {
+    let scaffold = Scaffold::new(&PathBuf::from("."))?;
+    let year = *command.year();
+    let stdout = &mut io::stdout();
+    let _ = scaffold.add_year(stdout, year)?;
+
+    match command.day() {
+        Some(day) => {
+            let _ = scaffold.add_day(stdout, year, AoCDay::ALL[usize::from(*day) - 1], command.title())?;
+        }
+        None => {
+            for day in AoCDay::ALL {
+                if let Err(e) = scaffold.add_day(stdout, year, day, command.title()) {
+                    writeln!(stdout, "Skipping {day}: {e}")?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::Input;
+    use crate::constants::{AoCDay, AoCYear};
+    use anyhow::Result;
+    use std::path::PathBuf;
+
+    #[test]
+    fn resolve() {
+        let resolve = |file: &str, text: Option<&String>| Input::resolve(AoCYear::AOC2018, AoCDay::AOCD11, file, text);
+        assert_eq!(resolve("data_file", None), Input::Data(PathBuf::from("data/2018/day11/data_file")));
+        assert_eq!(resolve("-", None), Input::Stdin);
+        assert_eq!(resolve("./scratch", None), Input::Path(PathBuf::from("./scratch")));
+        assert_eq!(resolve("/tmp/input", None), Input::Path(PathBuf::from("/tmp/input")));
+        assert_eq!(resolve("data_file", Some(&"7347".to_string())), Input::Text("7347".to_string()));
+    }
+
+    #[test]
+    fn open() -> Result<()> {
+        assert_eq!(Input::Text("7347".to_string()).read_to_string()?, "7347");
+        let missing = Input::Path(PathBuf::from("no/such/input")).read_to_string();
+        assert!(missing.is_err_and(|e| e.to_string().starts_with("Unable to open no/such/input: ")));
+        Ok(())
+    }
+}
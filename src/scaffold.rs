@@ -0,0 +1,485 @@
+// Copyright (c) 2017 aoc developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Scaffolding for new years and days
+//!
+//! Adding a year touches `constants.rs` (the `AoCYear` variant, `ALL`, and both
+//! conversions), `lib.rs` and `solver.rs` (the module and its registry), and
+//! creates `src/yearYYYY/mod.rs`.  Adding a day writes a solver from the template
+//! below and registers it in the year's `mod.rs`.  A day file is only replaced while
+//! it is still exactly the generated stub.
+use crate::{config, constants::AoCDay};
+use anyhow::{Context, Result, anyhow};
+use regex::Regex;
+use std::{
+    collections::BTreeSet,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// The earliest Advent of Code year.
+const FIRST_YEAR: u16 = 2015;
+/// The width rustfmt wraps at in this repository.
+const MAX_WIDTH: usize = 160;
+
+/// The template for a new day, filled in by [`render_day`].
+const DAY_TEMPLATE: &str = r#"//! Advent of Code - Day {n} "{title}" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::solver::{Solver, Unsolved};
+use anyhow::Result;
+use std::io::BufRead;
+
+/// Day {n} "{title}"
+pub(crate) struct Day{nn};
+
+impl Solver for Day{nn} {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC{year}
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD{nn}
+    }
+
+    fn title(&self) -> &'static str {
+        "{title}"
+    }
+
+    fn stars(&self) -> u8 {
+        0
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
+/// Find the solution
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
+    let lines = reader.lines().collect::<Result<Vec<String>, _>>()?;
+
+    if second_star { star_two(&lines) } else { star_one(&lines) }
+}
+
+fn star_one(_lines: &[String]) -> Result<Answer> {
+    Err(Unsolved.into())
+}
+
+fn star_two(_lines: &[String]) -> Result<Answer> {
+    Err(Unsolved.into())
+}
+
+#[cfg(test)]
+mod one_star {
+    use super::find_solution;
+    use crate::answer::Answer;
+    use anyhow::Result;
+    use std::io::Cursor;
+
+    const TEST_INPUT: &str = r"";
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn solution() -> Result<()> {
+        assert_eq!(find_solution(Cursor::new(TEST_INPUT), false)?, Answer::Unsigned(0));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod two_star {
+    use super::find_solution;
+    use crate::answer::Answer;
+    use anyhow::Result;
+    use std::io::Cursor;
+
+    const TEST_INPUT: &str = r"";
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn solution() -> Result<()> {
+        assert_eq!(find_solution(Cursor::new(TEST_INPUT), true)?, Answer::Unsigned(0));
+        Ok(())
+    }
+}
+"#;
+
+/// Fill in the day template.
+fn render_day(year: u16, day: AoCDay, title: &str) -> String {
+    DAY_TEMPLATE
+        .replace("{year}", &year.to_string())
+        .replace("{nn}", &format!("{:02}", day.number()))
+        .replace("{n}", &day.number().to_string())
+        .replace("{title}", title)
+}
+
+/// Whether a day file is still exactly the stub the template generates.
+fn is_stub(text: &str, year: u16, day: AoCDay) -> bool {
+    Regex::new(r#"fn title\(&self\) -> &'static str \{\s*"([^"]*)""#)
+        .ok()
+        .and_then(|title_re| title_re.captures(text).map(|caps| caps[1].to_string()))
+        .is_some_and(|title| text == render_day(year, day, &title))
+}
+
+/// Render `items` as a bracketed list, on one line if it fits and one item per line otherwise.
+fn render_list(prefix: &str, items: &[String], suffix: &str) -> String {
+    let line = format!("{prefix}[{}]{suffix}", items.join(", "));
+    if line.len() <= MAX_WIDTH {
+        line
+    } else {
+        let indent = prefix.len() - prefix.trim_start().len();
+        let pad = " ".repeat(indent + 4);
+        let body: String = items.iter().map(|item| format!("{pad}{item},\n")).collect();
+        format!("{prefix}[\n{body}{}]{suffix}", " ".repeat(indent))
+    }
+}
+
+/// Render an empty year's `mod.rs`.
+fn render_year_mod(year: u16) -> String {
+    format!(
+        "//! Advent of Code {year} Days\n\nuse crate::solver::Solver;\n\n/// The solvers registered for this year, ordered by day.\n{}\n",
+        render_list("pub(crate) const SOLVERS: &[&dyn Solver] = &", &[], ";")
+    )
+}
+
+/// The days registered in a year's `mod.rs`.
+fn registered_days(year_mod: &str) -> Result<BTreeSet<u8>> {
+    let mod_re = Regex::new(r"(?m)^mod day(\d{2});$")?;
+    Ok(mod_re.captures_iter(year_mod).filter_map(|caps| caps[1].parse().ok()).collect())
+}
+
+/// Declare a day's module in a year's `mod.rs` and add its solver to the registry, leaving every other line,
+/// helper modules included, as it is.
+fn add_day_to_year_mod(year_mod: &str, day: u8) -> Result<String> {
+    if registered_days(year_mod)?.contains(&day) {
+        return Ok(year_mod.to_string());
+    }
+
+    // Module declarations are kept in rustfmt's alphabetical order, in a block of their own.
+    let declaration = format!("mod day{day:02};");
+    let mut lines: Vec<&str> = year_mod.lines().collect();
+    let block: Vec<usize> = (0..lines.len()).filter(|idx| lines[*idx].starts_with("mod ")).collect();
+    let later = block.iter().copied().find(|idx| lines[*idx] > declaration.as_str());
+    match (later, block.last()) {
+        (Some(idx), _) => lines.insert(idx, &declaration),
+        (None, Some(last)) => lines.insert(last + 1, &declaration),
+        (None, None) => {
+            let docs = lines
+                .iter()
+                .position(|line| line.starts_with("/// The solvers registered"))
+                .unwrap_or(lines.len());
+            lines.splice(docs..docs, [declaration.as_str(), ""]);
+        }
+    }
+    let text = lines.join("\n") + if year_mod.ends_with('\n') { "\n" } else { "" };
+
+    let registry_re = Regex::new(r"(?s)(pub\(crate\) const SOLVERS: &\[&dyn Solver\] = &)\[([^\]]*)\];")?;
+    let caps = registry_re.captures(&text).ok_or_else(|| anyhow!("Unable to find the year's `SOLVERS`"))?;
+    let mut solvers: Vec<String> = caps[2].split(',').map(str::trim).filter(|item| !item.is_empty()).map(str::to_string).collect();
+    let solver = format!("&day{day:02}::Day{day:02}");
+    let at = solvers.iter().position(|item| *item > solver).unwrap_or(solvers.len());
+    solvers.insert(at, solver);
+    let registry = render_list(&caps[1], &solvers, ";");
+    Ok(registry_re.replace(&text, regex::NoExpand(&registry)).to_string())
+}
+
+/// Insert `line` after the last line matching `pattern`, reusing its indentation.
+fn insert_after_last(text: &str, pattern: &str, line: &str) -> Result<String> {
+    let line_re = Regex::new(&format!(r"(?m)^( *){pattern}$"))?;
+    let last = line_re.captures_iter(text).last().ok_or_else(|| anyhow!("Unable to find `{pattern}`"))?;
+    let (end, indent) = (last.get(0).map_or(0, |m| m.end()), &last[1]);
+    Ok(format!("{}\n{indent}{line}{}", &text[..end], &text[end..]))
+}
+
+/// The years already known to `constants.rs`.
+fn known_years(constants: &str) -> Result<BTreeSet<u16>> {
+    let variant_re = Regex::new(r"(?m)^    AOC(\d{4}),$")?;
+    Ok(variant_re.captures_iter(constants).filter_map(|caps| caps[1].parse().ok()).collect())
+}
+
+/// Add an `AoCYear` variant, and extend `ALL` and both string conversions with it.
+fn add_year_to_constants(constants: &str, year: u16) -> Result<String> {
+    let years = known_years(constants)?;
+    if years.last().is_some_and(|last| year < *last) {
+        return Err(anyhow!("Only years after those already supported can be added"));
+    }
+
+    let text = insert_after_last(constants, r"AOC\d{4},", &format!("/// Advent of Code {year}"))?;
+    let text = insert_after_last(&text, &format!("/// Advent of Code {year}"), &format!("AOC{year},"))?;
+
+    let all_re = Regex::new(r"(?m)^( *pub const ALL: )\[AoCYear; \d+\] = \[[^\]]*\];$")?;
+    let declaration = all_re.captures(&text).ok_or_else(|| anyhow!("Unable to find `AoCYear::ALL`"))?[1].to_string();
+    let all: Vec<String> = years.iter().chain([year].iter()).map(|year| format!("AoCYear::AOC{year}")).collect();
+    let text = all_re
+        .replace(&text, render_list(&format!("{declaration}[AoCYear; {}] = ", all.len()), &all, ";"))
+        .to_string();
+
+    let text = insert_after_last(&text, r#"AoCYear::AOC\d{4} => "\d{4}","#, &format!(r#"AoCYear::AOC{year} => "{year}","#))?;
+    insert_after_last(
+        &text,
+        r#""\d{4}" => Ok\(AoCYear::AOC\d{4}\),"#,
+        &format!(r#""{year}" => Ok(AoCYear::AOC{year}),"#),
+    )
+}
+
+/// Add the year's module to `lib.rs`.
+fn add_year_to_lib(lib: &str, year: u16) -> Result<String> {
+    insert_after_last(lib, r"mod year\d{4};", &format!("mod year{year};"))
+}
+
+/// Add the year's registry to `solver.rs`.
+fn add_year_to_solver(solver: &str, year: u16) -> Result<String> {
+    let use_re = Regex::new(r"(?m)^( +(?:year\d{4}, )*year\d{4}),$")?;
+    let text = use_re.replace(solver, format!("${{1}}, year{year},")).to_string();
+
+    let registry_re = Regex::new(r"\[(?:year\d{4}::SOLVERS, )*year\d{4}::SOLVERS\]")?;
+    let registry = registry_re
+        .find(&text)
+        .ok_or_else(|| anyhow!("Unable to find the solver registry"))?
+        .as_str()
+        .to_string();
+    Ok(text.replacen(&registry, &format!("{}, year{year}::SOLVERS]", registry.trim_end_matches(']')), 1))
+}
+
+/// What happened to a day file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Generated {
+    /// A new file was written
+    Created,
+    /// The file was still the generated stub, and was regenerated
+    Replaced,
+}
+
+/// Generates years and days within a repository checkout.
+pub(crate) struct Scaffold {
+    /// The repository root, holding `src/` and `data/`
+    root: PathBuf,
+}
+
+impl Scaffold {
+    /// Scaffold within the repository at `root`.
+    pub(crate) fn new(root: &Path) -> Result<Self> {
+        if !root.join("src").join("constants.rs").is_file() {
+            return Err(anyhow!("{} is not the root of the aoc repository", root.display()));
+        }
+        Ok(Self { root: root.to_path_buf() })
+    }
+
+    /// Apply `edit` to the source file at `path`, relative to `src/`.
+    fn edit_source(&self, path: &str, edit: impl FnOnce(&str) -> Result<String>) -> Result<()> {
+        let path = self.root.join("src").join(path);
+        let text = fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        fs::write(&path, edit(&text)?).with_context(|| format!("writing {}", path.display()))
+    }
+
+    /// The directory holding a year's solvers.
+    fn year_dir(&self, year: u16) -> PathBuf {
+        self.root.join("src").join(format!("year{year}"))
+    }
+
+    /// Register a year, unless it already is.  Returns whether anything changed.
+    pub(crate) fn add_year<W: Write>(&self, out: &mut W, year: u16) -> Result<bool> {
+        if year < FIRST_YEAR {
+            return Err(anyhow!("Advent of Code started in {FIRST_YEAR}"));
+        }
+
+        let constants = fs::read_to_string(self.root.join("src").join("constants.rs"))?;
+        if known_years(&constants)?.contains(&year) {
+            return Ok(false);
+        }
+
+        self.edit_source("constants.rs", |text| add_year_to_constants(text, year))?;
+        self.edit_source("lib.rs", |text| add_year_to_lib(text, year))?;
+        self.edit_source("solver.rs", |text| add_year_to_solver(text, year))?;
+        fs::create_dir_all(self.year_dir(year))?;
+        fs::write(self.year_dir(year).join("mod.rs"), render_year_mod(year))?;
+        fs::create_dir_all(self.root.join(config::settings().data()).join(year.to_string()))?;
+        writeln!(out, "Registered {year} in constants.rs, lib.rs and solver.rs")?;
+        Ok(true)
+    }
+
+    /// Generate a day's solver and data directory, and register the solver.
+    ///
+    /// Refuses to touch a day file that is no longer the generated stub, and titles that cannot be pasted into
+    /// the stub's doc comments and string literals as they are.
+    pub(crate) fn add_day<W: Write>(&self, out: &mut W, year: u16, day: AoCDay, title: &str) -> Result<Generated> {
+        if let Some(bad) = title.chars().find(|ch| matches!(ch, '"' | '\\') || ch.is_control()) {
+            return Err(anyhow!("the title {title:?} contains {bad:?}, which a generated day cannot hold"));
+        }
+        let day_path = self.year_dir(year).join(format!("{day}.rs"));
+        let generated = if day_path.exists() {
+            let existing = fs::read_to_string(&day_path)?;
+            if !is_stub(&existing, year, day) {
+                return Err(anyhow!("{} already has a solution, refusing to overwrite it", day_path.display()));
+            }
+            Generated::Replaced
+        } else {
+            Generated::Created
+        };
+        fs::write(&day_path, render_day(year, day, title)).with_context(|| format!("writing {}", day_path.display()))?;
+
+        let mod_path = self.year_dir(year).join("mod.rs");
+        let year_mod = fs::read_to_string(&mod_path).with_context(|| format!("reading {}", mod_path.display()))?;
+        fs::write(&mod_path, add_day_to_year_mod(&year_mod, day.number())?).with_context(|| format!("writing {}", mod_path.display()))?;
+
+        fs::create_dir_all(self.root.join(config::settings().data()).join(year.to_string()).join(day.to_string()))?;
+        writeln!(
+            out,
+            "{} {}",
+            if generated == Generated::Created { "Created" } else { "Regenerated" },
+            day_path.display()
+        )?;
+        Ok(generated)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        Generated, Scaffold, add_day_to_year_mod, add_year_to_constants, add_year_to_lib, add_year_to_solver, is_stub, registered_days, render_day,
+        render_year_mod,
+    };
+    use crate::constants::AoCDay;
+    use anyhow::Result;
+    use std::fs;
+
+    const CONSTANTS: &str = r#"pub enum AoCYear {
+    /// Advent of Code 2017
+    AOC2017,
+    /// Advent of Code 2018
+    AOC2018,
+}
+
+impl AoCYear {
+    pub const ALL: [AoCYear; 2] = [AoCYear::AOC2017, AoCYear::AOC2018];
+}
+
+        match *year {
+            AoCYear::AOC2017 => "2017",
+            AoCYear::AOC2018 => "2018",
+        }
+
+        match year {
+            "2017" => Ok(AoCYear::AOC2017),
+            "2018" => Ok(AoCYear::AOC2018),
+            _ => Err(anyhow!("Unable to convert to year!")),
+        }
+"#;
+
+    #[test]
+    fn constants() -> Result<()> {
+        let text = add_year_to_constants(CONSTANTS, 2019)?;
+        assert!(text.contains("    AOC2018,\n    /// Advent of Code 2019\n    AOC2019,\n}"));
+        assert!(text.contains("pub const ALL: [AoCYear; 3] = [AoCYear::AOC2017, AoCYear::AOC2018, AoCYear::AOC2019];"));
+        assert!(text.contains("AoCYear::AOC2018 => \"2018\",\n            AoCYear::AOC2019 => \"2019\","));
+        assert!(text.contains("\"2019\" => Ok(AoCYear::AOC2019),\n            _ =>"));
+        assert!(add_year_to_constants(CONSTANTS, 2016).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn registries() -> Result<()> {
+        let lib = add_year_to_lib("mod utils;\nmod year2017;\nmod year2018;\n\nuse std::process;\n", 2019)?;
+        assert!(lib.contains("mod year2018;\nmod year2019;\n\n"));
+
+        let solver = "use crate::{\n    year2017, year2018,\n};\n    [year2017::SOLVERS, year2018::SOLVERS]\n";
+        let solver = add_year_to_solver(solver, 2019)?;
+        assert!(solver.contains("    year2017, year2018, year2019,\n"));
+        assert!(solver.contains("[year2017::SOLVERS, year2018::SOLVERS, year2019::SOLVERS]"));
+        Ok(())
+    }
+
+    #[test]
+    fn year_mod() -> Result<()> {
+        let empty = render_year_mod(2019);
+        assert_eq!(
+            empty,
+            "//! Advent of Code 2019 Days\n\nuse crate::solver::Solver;\n\n/// The solvers registered for this year, ordered by day.\npub(crate) const SOLVERS: &[&dyn Solver] = &[];\n"
+        );
+
+        let some = add_day_to_year_mod(&add_day_to_year_mod(&empty, 5)?, 1)?;
+        assert!(some.contains("use crate::solver::Solver;\n\nmod day01;\nmod day05;\n\n/// The solvers"));
+        assert!(some.contains("= &[&day01::Day01, &day05::Day05];"));
+        assert_eq!(registered_days(&some)?, [1, 5].into_iter().collect());
+        assert_eq!(add_day_to_year_mod(&some, 5)?, some);
+
+        let all = (1..=25).try_fold(empty, |text, day| add_day_to_year_mod(&text, day))?;
+        assert!(all.contains("= &[\n    &day01::Day01,\n"));
+        assert!(all.ends_with("    &day25::Day25,\n];\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn year_mod_helpers() -> Result<()> {
+        let year_mod = "//! Advent of Code 2018 Days\n\nuse crate::solver::Solver;\n\nmod accelerate;\nmod day01;\nmod day21;\nmod elfcode;\n\n/// The solvers registered for this year, ordered by day.\npub(crate) const SOLVERS: &[&dyn Solver] = &[&day01::Day01, &day21::Day21];\n";
+        let text = add_day_to_year_mod(year_mod, 20)?;
+        assert_eq!(
+            text,
+            year_mod
+                .replace("mod day21;", "mod day20;\nmod day21;")
+                .replace("&day01::Day01, &day21", "&day01::Day01, &day20::Day20, &day21")
+        );
+        let text = add_day_to_year_mod(&text, 25)?;
+        assert!(text.contains("mod day21;\nmod day25;\nmod elfcode;\n"));
+        assert!(text.contains("&day21::Day21, &day25::Day25]"));
+        Ok(())
+    }
+
+    #[test]
+    fn stubs() {
+        let stub = render_day(2019, AoCDay::AOCD05, "Sunny with a Chance of Asteroids");
+        assert!(stub.contains("pub(crate) struct Day05;"));
+        assert!(stub.contains("AoCYear::AOC2019"));
+        assert!(stub.contains("//! Advent of Code - Day 5 \"Sunny with a Chance of Asteroids\" Solution"));
+        assert!(is_stub(&stub, 2019, AoCDay::AOCD05));
+        assert!(!is_stub(
+            &stub.replace(
+                "_lines: &[String]) -> Result<Answer> {\n    Err",
+                "lines: &[String]) -> Result<Answer> {\n    Err"
+            ),
+            2019,
+            AoCDay::AOCD05
+        ));
+        assert!(!is_stub(&stub, 2019, AoCDay::AOCD06));
+    }
+
+    #[test]
+    fn scaffold() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let src = root.path().join("src");
+        fs::create_dir_all(&src)?;
+        fs::write(src.join("constants.rs"), CONSTANTS)?;
+        fs::write(src.join("lib.rs"), "mod year2017;\nmod year2018;\n")?;
+        fs::write(src.join("solver.rs"), "    year2017, year2018,\n    [year2017::SOLVERS, year2018::SOLVERS]\n")?;
+        let scaffold = Scaffold::new(root.path())?;
+        let out = &mut Vec::new();
+
+        assert!(scaffold.add_year(out, 2019)?);
+        assert!(!scaffold.add_year(out, 2019)?);
+        assert_eq!(scaffold.add_day(out, 2019, AoCDay::AOCD05, "Untitled")?, Generated::Created);
+        assert_eq!(scaffold.add_day(out, 2019, AoCDay::AOCD05, "Sunny")?, Generated::Replaced);
+
+        let day05 = src.join("year2019").join("day05.rs");
+        fs::write(&day05, fs::read_to_string(&day05)?.replace("Err(Unsolved.into())", "Ok(0_u64.into())"))?;
+        assert!(scaffold.add_day(out, 2019, AoCDay::AOCD05, "Sunny").is_err());
+        for title in ["Say \"Hi\"", "Back\\slash", "Two\nlines"] {
+            assert!(scaffold.add_day(out, 2019, AoCDay::AOCD06, title).is_err());
+        }
+        assert!(!src.join("year2019").join("day06.rs").exists());
+
+        assert!(fs::read_to_string(src.join("year2019").join("mod.rs"))?.contains("&[&day05::Day05]"));
+        assert!(root.path().join("data").join("2019").join("day05").is_dir());
+        Ok(())
+    }
+}
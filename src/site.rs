@@ -0,0 +1,383 @@
+// Copyright (c) 2017 aoc developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Puzzle site client
+use crate::{
+    config,
+    constants::{AoCDay, AoCYear},
+};
+use anyhow::{Context, Result, anyhow};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    env, fmt, fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use ureq::Agent;
+
+/// The puzzle site, used when no other base URL is configured.
+pub(crate) const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+/// The least time allowed between two requests to the puzzle site.
+pub(crate) const MIN_INTERVAL: Duration = Duration::from_secs(5);
+/// The file under `data/` that remembers when the site was last contacted.
+const LAST_REQUEST_FILE: &str = ".last_request";
+/// Sent with every request, so the site operators know who is calling.
+const USER_AGENT: &str = concat!("aoc/", env!("CARGO_PKG_VERSION"), " (personal puzzle runner)");
+
+/// The base URL of the puzzle site, from `AOC_BASE_URL` or the default.
+pub(crate) fn base_url() -> String {
+    env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string())
+}
+
+/// The session token, from `AOC_SESSION`, the config file, or the `aoc/session` file in the user's config directory.
+pub(crate) fn session_token() -> Result<String> {
+    if let Some(session) = config::settings().session() {
+        return Ok(session.clone());
+    }
+    let path = config::config_dir()
+        .ok_or_else(|| anyhow!("No session token: set AOC_SESSION or `session` in the config file"))?
+        .join("session");
+    let session = fs::read_to_string(&path).with_context(|| format!("No session token: set AOC_SESSION or write it to {}", path.display()))?;
+    Ok(session.trim().to_string())
+}
+
+/// The result of asking for a day's input.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Fetched {
+    /// The input was already on disk, and the site was not contacted
+    Cached,
+    /// The input was downloaded and saved
+    Downloaded,
+}
+
+/// The site's response to a submitted answer.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Verdict {
+    /// The answer was right
+    Correct,
+    /// The answer was wrong, with no hint given
+    Wrong,
+    /// The answer was wrong, and too high
+    TooHigh,
+    /// The answer was wrong, and too low
+    TooLow,
+    /// The answer was not checked, and the site asked for this many seconds of patience
+    RateLimited(u64),
+    /// The star has already been earned
+    AlreadySolved,
+}
+
+impl Verdict {
+    /// Read the verdict from a response page.
+    pub(crate) fn parse(page: &str) -> Option<Self> {
+        if page.contains("That's the right answer") {
+            Some(Verdict::Correct)
+        } else if page.contains("That's not the right answer") {
+            if page.contains("your answer is too high") {
+                Some(Verdict::TooHigh)
+            } else if page.contains("your answer is too low") {
+                Some(Verdict::TooLow)
+            } else {
+                Some(Verdict::Wrong)
+            }
+        } else if page.contains("You gave an answer too recently") {
+            let wait_re = Regex::new(r"You have (?:(\d+)m )?(\d+)s left to wait").ok()?;
+            let wait = wait_re.captures(page).map_or(60, |caps| {
+                let minutes = caps.get(1).and_then(|minutes| minutes.as_str().parse::<u64>().ok()).unwrap_or(0);
+                let seconds = caps.get(2).and_then(|seconds| seconds.as_str().parse::<u64>().ok()).unwrap_or(0);
+                minutes * 60 + seconds
+            });
+            Some(Verdict::RateLimited(wait))
+        } else if page.contains("You don't seem to be solving the right level") {
+            Some(Verdict::AlreadySolved)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the site judged the answer to be wrong.
+    pub(crate) fn is_wrong(self) -> bool {
+        matches!(self, Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => f.write_str("correct"),
+            Verdict::Wrong => f.write_str("wrong"),
+            Verdict::TooHigh => f.write_str("wrong, too high"),
+            Verdict::TooLow => f.write_str("wrong, too low"),
+            Verdict::RateLimited(wait) => write!(f, "rate limited, wait {wait}s before trying again"),
+            Verdict::AlreadySolved => f.write_str("already solved"),
+        }
+    }
+}
+
+/// A polite client for the puzzle site.
+pub(crate) struct Client {
+    /// The HTTP agent
+    agent: Agent,
+    /// The site base URL, without a trailing slash
+    base_url: String,
+    /// The session token sent as a cookie
+    session: String,
+    /// The least time allowed between two requests
+    min_interval: Duration,
+    /// Where the time of the last request is kept, shared between runs
+    last_request: PathBuf,
+}
+
+impl Client {
+    /// Build a client that remembers its last request under `data_root`.
+    pub(crate) fn new(base_url: &str, session: &str, min_interval: Duration, data_root: &Path) -> Self {
+        let agent = Agent::config_builder()
+            .http_status_as_error(false)
+            .user_agent(USER_AGENT)
+            .timeout_global(Some(Duration::from_secs(30)))
+            .build()
+            .into();
+        Self {
+            agent,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            min_interval,
+            last_request: data_root.join(LAST_REQUEST_FILE),
+        }
+    }
+
+    /// The URL of a puzzle page, or of something below it.
+    pub(crate) fn day_url(&self, year: AoCYear, day: AoCDay, suffix: &str) -> String {
+        format!("{}/{year}/day/{}{suffix}", self.base_url, day.number())
+    }
+
+    /// Sleep until at least `min_interval` has passed since the last request, then note the time.
+    fn wait_politely(&self) -> Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        let last = fs::read_to_string(&self.last_request)
+            .ok()
+            .and_then(|millis| millis.trim().parse::<u64>().ok())
+            .map(Duration::from_millis);
+
+        if let Some(wait) = last.and_then(|last| (last + self.min_interval).checked_sub(now)) {
+            thread::sleep(wait);
+        }
+
+        if let Some(parent) = self.last_request.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        fs::write(&self.last_request, now.as_millis().to_string())?;
+        Ok(())
+    }
+
+    /// Send a request with the session cookie, posting `form` if given, and return the status code and body.
+    fn send(&self, url: &str, form: Option<&[(&str, &str)]>) -> Result<(u16, String)> {
+        self.wait_politely()?;
+        let cookie = format!("session={}", self.session);
+        let mut response = match form {
+            Some(form) => self.agent.post(url).header("Cookie", &cookie).send_form(form.iter().copied()),
+            None => self.agent.get(url).header("Cookie", &cookie).call(),
+        }
+        .with_context(|| format!("requesting {url}"))?;
+        let status = response.status().as_u16();
+        let body = response.body_mut().read_to_string()?;
+        Ok((status, body))
+    }
+
+    /// Download a day's input to `path`, unless it is already there.
+    pub(crate) fn fetch_input(&self, year: AoCYear, day: AoCDay, path: &Path) -> Result<Fetched> {
+        if path.exists() {
+            return Ok(Fetched::Cached);
+        }
+
+        let url = self.day_url(year, day, "/input");
+        match self.send(&url, None)? {
+            (200, input) => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(path, input).with_context(|| format!("writing {}", path.display()))?;
+                Ok(Fetched::Downloaded)
+            }
+            (404, _) => Err(anyhow!("The input for {year} {day} is not available yet ({url})")),
+            (400 | 500, _) => Err(anyhow!("The session token was rejected fetching {url}")),
+            (status, _) => Err(anyhow!("Unexpected status {status} fetching {url}")),
+        }
+    }
+
+    /// Submit an answer for one star, and read the verdict from the response page.
+    pub(crate) fn submit_answer(&self, year: AoCYear, day: AoCDay, part: u8, answer: &str) -> Result<Verdict> {
+        let url = self.day_url(year, day, "/answer");
+        let level = part.to_string();
+        match self.send(&url, Some(&[("level", &level), ("answer", answer)]))? {
+            (200, page) => Verdict::parse(&page).ok_or_else(|| anyhow!("Unable to find a verdict in the response from {url}")),
+            (400 | 500, _) => Err(anyhow!("The session token was rejected submitting to {url}")),
+            (status, _) => Err(anyhow!("Unexpected status {status} submitting to {url}")),
+        }
+    }
+}
+
+/// A stand-in for the puzzle site, serving canned responses over plain HTTP.
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    /// Serve each `(status, body)` response to one connection, in order.
+    ///
+    /// Returns the base URL to point a client at, and a handle yielding the raw requests received.
+    pub(crate) fn serve(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind a local port");
+        let base_url = format!("http://{}", listener.local_addr().expect("a local address"));
+
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().expect("a connection");
+                let mut reader = BufReader::new(stream);
+                let mut request = String::new();
+                let mut content_length = 0;
+
+                loop {
+                    let mut line = String::new();
+                    let _ = reader.read_line(&mut line).expect("a request line");
+                    if let Some(length) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        content_length = length.trim().parse().expect("a content length");
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                let mut content = vec![0; content_length];
+                reader.read_exact(&mut content).expect("the request body");
+                request.push_str(&String::from_utf8_lossy(&content));
+                requests.push(request);
+
+                let mut stream = reader.into_inner();
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} Canned\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .expect("a response");
+            }
+            requests
+        });
+
+        (base_url, handle)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Client, Fetched, Verdict, mock};
+    use crate::constants::{AoCDay, AoCYear};
+    use anyhow::{Result, anyhow};
+    use std::{
+        fs,
+        time::{Duration, Instant},
+    };
+
+    #[test]
+    fn fetch_and_cache() -> Result<()> {
+        let data = tempfile::tempdir()?;
+        let path = data.path().join("2018").join("day20").join("data_file");
+        let (base_url, server) = mock::serve(vec![(200, "^WNE$\n")]);
+        let client = Client::new(&base_url, "abc123", Duration::ZERO, data.path());
+
+        assert_eq!(client.fetch_input(AoCYear::AOC2018, AoCDay::AOCD20, &path)?, Fetched::Downloaded);
+        assert_eq!(fs::read_to_string(&path)?, "^WNE$\n");
+        assert_eq!(client.fetch_input(AoCYear::AOC2018, AoCDay::AOCD20, &path)?, Fetched::Cached);
+
+        let requests = server.join().map_err(|_| anyhow!("the mock server panicked"))?;
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /2018/day/20/input "));
+        assert!(requests[0].contains("session=abc123"));
+        Ok(())
+    }
+
+    #[test]
+    fn errors_are_not_cached() -> Result<()> {
+        let data = tempfile::tempdir()?;
+        let path = data.path().join("data_file");
+        let (base_url, server) = mock::serve(vec![(404, "Not found"), (400, "Puzzle inputs differ by user.")]);
+        let client = Client::new(&base_url, "abc123", Duration::ZERO, data.path());
+
+        assert!(client.fetch_input(AoCYear::AOC2018, AoCDay::AOCD25, &path).is_err());
+        assert!(client.fetch_input(AoCYear::AOC2018, AoCDay::AOCD25, &path).is_err());
+        assert!(!path.exists());
+        let _ = server.join().map_err(|_| anyhow!("the mock server panicked"))?;
+        Ok(())
+    }
+
+    #[test]
+    fn rate_limit() -> Result<()> {
+        let data = tempfile::tempdir()?;
+        let (base_url, server) = mock::serve(vec![(200, "1"), (200, "2")]);
+        let client = Client::new(&base_url, "abc123", Duration::from_millis(300), data.path());
+
+        let start = Instant::now();
+        let _ = client.fetch_input(AoCYear::AOC2017, AoCDay::AOCD01, &data.path().join("one"))?;
+        let _ = client.fetch_input(AoCYear::AOC2017, AoCDay::AOCD02, &data.path().join("two"))?;
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        let _ = server.join().map_err(|_| anyhow!("the mock server panicked"))?;
+        Ok(())
+    }
+
+    #[test]
+    fn verdicts() {
+        let page = |text: &str| format!("<main><article><p>{text}</p></article></main>");
+        assert_eq!(
+            Verdict::parse(&page("That's the right answer!  You are one gold star closer.")),
+            Some(Verdict::Correct)
+        );
+        assert_eq!(
+            Verdict::parse(&page("That's not the right answer; your answer is too high.  If you're stuck...")),
+            Some(Verdict::TooHigh)
+        );
+        assert_eq!(
+            Verdict::parse(&page("That's not the right answer; your answer is too low.")),
+            Some(Verdict::TooLow)
+        );
+        assert_eq!(Verdict::parse(&page("That's not the right answer.  If you're stuck...")), Some(Verdict::Wrong));
+        assert_eq!(
+            Verdict::parse(&page("You gave an answer too recently; you have to wait.  You have 4m 59s left to wait.")),
+            Some(Verdict::RateLimited(299))
+        );
+        assert_eq!(
+            Verdict::parse(&page("You gave an answer too recently.  You have 37s left to wait.")),
+            Some(Verdict::RateLimited(37))
+        );
+        assert_eq!(
+            Verdict::parse(&page("You don't seem to be solving the right level.  Did you already complete it?")),
+            Some(Verdict::AlreadySolved)
+        );
+        assert_eq!(Verdict::parse(&page("Something else entirely")), None);
+    }
+
+    #[test]
+    fn submit() -> Result<()> {
+        let data = tempfile::tempdir()?;
+        let (base_url, server) = mock::serve(vec![(200, "<p>That's not the right answer; your answer is too low.</p>")]);
+        let client = Client::new(&base_url, "abc123", Duration::ZERO, data.path());
+
+        assert_eq!(client.submit_answer(AoCYear::AOC2018, AoCDay::AOCD15, 2, "4988")?, Verdict::TooLow);
+        let requests = server.join().map_err(|_| anyhow!("the mock server panicked"))?;
+        assert!(requests[0].starts_with("POST /2018/day/15/answer "));
+        assert!(requests[0].ends_with("level=2&answer=4988"));
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+// Copyright (c) 2017 aoc developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Solver trait and registry
+use crate::{
+    answer::Answer,
+    constants::{AoCDay, AoCYear},
+    context::Context,
+    year2015, year2016, year2017, year2018,
+};
+use anyhow::{Result, anyhow};
+use getset::Getters;
+use std::{
+    any::Any,
+    io::BufRead,
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+use thiserror::Error;
+
+/// A solver panicked rather than returning an answer.
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct Panicked(String);
+
+impl Panicked {
+    /// Capture the message carried by a panic payload.
+    pub(crate) fn from_payload(payload: &(dyn Any + Send)) -> Self {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            (*message).to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown panic".to_string()
+        };
+        Self(message)
+    }
+}
+
+/// A star that has not been solved yet, as returned by freshly generated solvers.
+#[derive(Debug, Error)]
+#[error("not solved yet")]
+pub struct Unsolved;
+
+/// The answer and running time for a single star.
+#[derive(Debug, Getters)]
+#[getset(get = "pub")]
+pub struct Star {
+    /// The answer, or the error that prevented it from being calculated
+    answer: Result<Answer>,
+    /// How long the star took to calculate
+    elapsed: Duration,
+}
+
+impl Star {
+    /// Run `f`, recording its answer and how long it took.
+    ///
+    /// A panic in `f` is caught and recorded as a [`Panicked`] error, so one bad star
+    /// cannot take down the other.
+    pub fn time<F>(f: F) -> Self
+    where
+        F: FnOnce() -> Result<Answer>,
+    {
+        let start = Instant::now();
+        let answer = catch_panic(f);
+        Self {
+            answer,
+            elapsed: start.elapsed(),
+        }
+    }
+}
+
+/// Run `f`, turning a panic into a [`Panicked`] error.
+pub(crate) fn catch_panic<F>(f: F) -> Result<Answer>
+where
+    F: FnOnce() -> Result<Answer>,
+{
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| Err(Panicked::from_payload(&*payload).into()))
+}
+
+/// An Advent of Code solution for a single day.
+pub trait Solver: Sync {
+    /// The year of the puzzle.
+    fn year(&self) -> AoCYear;
+    /// The day of the puzzle.
+    fn day(&self) -> AoCDay;
+    /// The puzzle title.
+    fn title(&self) -> &'static str;
+    /// How many of the day's stars are implemented, counting from the first.
+    ///
+    /// Generated stubs report 0 until their stars are filled in.
+    fn stars(&self) -> u8 {
+        2
+    }
+    /// Calculate the value for the first star.
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer>;
+    /// Calculate the value for the second star.
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer>;
+
+    /// Calculate the value for the given star, 1 or 2, tracing and visualizing as `ctx` asks.
+    ///
+    /// Only days with diagnostics to show override this; the rest ignore `ctx`.
+    fn star(&self, part: u8, reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer> {
+        let _ = ctx;
+        if part == 1 { self.part1(reader) } else { self.part2(reader) }
+    }
+
+    /// Parse the input into the form both stars are calculated from, for days that keep parsing
+    /// apart from solving.
+    ///
+    /// Most days parse inside each star and return `None`, the default.
+    fn parse(&self, reader: &mut dyn BufRead) -> Result<Option<Box<dyn Any>>> {
+        let _ = reader;
+        Ok(None)
+    }
+
+    /// Calculate the value for the given star, 1 or 2, from the input returned by [`Solver::parse`].
+    fn solve_parsed(&self, part: u8, input: &dyn Any, ctx: &Context) -> Result<Answer> {
+        let _ = (part, input, ctx);
+        Err(anyhow!("{} {} does not parse its input ahead of solving", self.year(), self.day()))
+    }
+
+    /// Calculate the values for both stars from a single read of the input.
+    ///
+    /// The input is read once and each star parses its own copy.  Days whose second
+    /// star can build on the work done for the first override this to share that state.
+    fn both(&self, reader: &mut dyn BufRead, ctx: &Context) -> Result<(Star, Star)> {
+        let mut input = String::new();
+        let _ = reader.read_to_string(&mut input)?;
+        let first = Star::time(|| self.star(1, &mut input.as_bytes(), ctx));
+        let second = Star::time(|| self.star(2, &mut input.as_bytes(), ctx));
+        Ok((first, second))
+    }
+}
+
+/// The input returned by [`Solver::parse`], as the type the day parsed it into.
+pub(crate) fn parsed<T: 'static>(input: &dyn Any) -> Result<&T> {
+    input.downcast_ref().ok_or_else(|| anyhow!("the input was not parsed by this day"))
+}
+
+/// Every registered solver, ordered by year and day.
+pub fn solvers() -> impl Iterator<Item = &'static dyn Solver> {
+    [year2015::SOLVERS, year2016::SOLVERS, year2017::SOLVERS, year2018::SOLVERS]
+        .into_iter()
+        .flatten()
+        .copied()
+}
+
+/// Find the solver registered for the given year and day.
+pub fn find(year: AoCYear, day: AoCDay) -> Option<&'static dyn Solver> {
+    solvers().find(|solver| solver.year() == year && solver.day() == day)
+}
+
+/// Calculate the given star, 1 or 2, of a day from its input.
+pub fn solve(year: AoCYear, day: AoCDay, part: u8, reader: &mut dyn BufRead) -> Result<Answer> {
+    let solver = find(year, day).ok_or_else(|| anyhow!("No solution is registered for {year} {day}"))?;
+    match part {
+        1 | 2 => solver.star(part, reader, &Context::default()),
+        _ => Err(anyhow!("Valid parts are 1 and 2, not {part}")),
+    }
+}
+
+/// Calculate the given star, 1 or 2, of a day from input already in memory.
+pub fn solve_str(year: AoCYear, day: AoCDay, part: u8, input: &str) -> Result<Answer> {
+    solve(year, day, part, &mut input.as_bytes())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{find, solve, solve_str, solvers};
+    use crate::answer::Answer;
+    use crate::constants::{AoCDay, AoCYear};
+    use crate::context::Context;
+    use anyhow::{Result, anyhow};
+    use itertools::Itertools;
+    use std::io::Cursor;
+
+    #[test]
+    fn registry_is_ordered_and_unique() {
+        let keys: Vec<(AoCYear, AoCDay)> = solvers().map(|solver| (solver.year(), solver.day())).collect();
+        assert!(keys.iter().tuple_windows().all(|(a, b)| a < b));
+    }
+
+    #[test]
+    fn both_shares_one_read() -> Result<()> {
+        let solver = find(AoCYear::AOC2017, AoCDay::AOCD01).ok_or(anyhow!("2017 day01 is not registered"))?;
+        let (first, second) = solver.both(&mut Cursor::new("1212"), &Context::default())?;
+        assert_eq!(first.answer().as_ref().ok(), Some(&Answer::Unsigned(0)));
+        assert_eq!(second.answer().as_ref().ok(), Some(&Answer::Unsigned(6)));
+        Ok(())
+    }
+
+    #[test]
+    fn find_registered() {
+        assert!(find(AoCYear::AOC2018, AoCDay::AOCD01).is_some());
+        assert!(find(AoCYear::AOC2016, AoCDay::AOCD01).is_none());
+    }
+
+    #[test]
+    fn solve_from_reader_or_str() -> Result<()> {
+        assert_eq!(solve(AoCYear::AOC2017, AoCDay::AOCD01, 2, &mut Cursor::new("123425"))?, Answer::Unsigned(4));
+        assert_eq!(solve_str(AoCYear::AOC2017, AoCDay::AOCD01, 1, "91212129")?, Answer::Unsigned(9));
+        assert!(solve_str(AoCYear::AOC2017, AoCDay::AOCD01, 3, "1122").is_err());
+        assert!(solve_str(AoCYear::AOC2016, AoCDay::AOCD01, 1, "R2, L3").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright (c) 2017 aoc developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Star calendar
+//!
+//! Implemented stars come from each solver's [`Solver::stars`](crate::solver::Solver::stars),
+//! never from running it, so a stub that happens to return an answer is not counted.
+use crate::{
+    answers::Answers,
+    constants::{AoCDay, AoCYear},
+    run::input_path,
+    solver,
+};
+use anyhow::Result;
+use getset::Getters;
+use std::io::Write;
+
+/// The number of days on each row of the calendar.
+const WEEK: usize = 5;
+
+/// What is known about a single day.
+#[derive(Clone, Copy, Debug, Eq, Getters, PartialEq)]
+#[getset(get = "pub(crate)")]
+pub(crate) struct DayStatus {
+    /// The puzzle day
+    day: AoCDay,
+    /// The puzzle title, if a solver is registered
+    title: Option<&'static str>,
+    /// How many stars the solver implements
+    stars: u8,
+    /// Whether the input file exists
+    input: bool,
+    /// How many stars have a stored answer
+    answers: u8,
+}
+
+impl DayStatus {
+    /// Look up a day's solver, input file named `file` and answers file.
+    pub(crate) fn load(year: AoCYear, day: AoCDay, file: &str) -> Result<Self> {
+        let solver = solver::find(year, day);
+        let answers = Answers::load(year, day)?.map_or(0, |answers| u8::from(answers.part1().is_some()) + u8::from(answers.part2().is_some()));
+
+        Ok(Self {
+            day,
+            title: solver.map(|solver| solver.title()),
+            stars: solver.map_or(0, |solver| solver.stars().min(2)),
+            input: input_path(year, day, file).is_file(),
+            answers,
+        })
+    }
+
+    /// The day's calendar cell, i.e. `07 ** i vv`.
+    fn cell(&self) -> String {
+        let marks = |count: u8, mark: &str| format!("{}{}", mark.repeat(count.into()), "-".repeat((2 - count).into()));
+        format!(
+            "{:02} {} {} {}",
+            self.day.number(),
+            marks(self.stars, "*"),
+            if self.input { "i" } else { "-" },
+            marks(self.answers, "v")
+        )
+    }
+}
+
+/// The status of every day of a year.
+pub(crate) fn year_status(year: AoCYear, file: &str) -> Result<Vec<DayStatus>> {
+    AoCDay::ALL.into_iter().map(|day| DayStatus::load(year, day, file)).collect()
+}
+
+/// The year's totals, i.e. `12/50 stars, 6/25 inputs, 10/50 answers`.
+fn totals(days: &[DayStatus]) -> String {
+    let sum = |count: fn(&DayStatus) -> usize| days.iter().map(count).sum::<usize>();
+    format!(
+        "{}/{} stars, {}/{} inputs, {}/{} answers",
+        sum(|day| day.stars.into()),
+        days.len() * 2,
+        sum(|day| day.input.into()),
+        days.len(),
+        sum(|day| day.answers.into()),
+        days.len() * 2
+    )
+}
+
+/// Write a year as a calendar grid.
+pub(crate) fn write_calendar<W: Write>(out: &mut W, year: AoCYear, days: &[DayStatus]) -> Result<()> {
+    writeln!(out, "{year}  {}", totals(days))?;
+    for week in days.chunks(WEEK) {
+        writeln!(out, "  {}", week.iter().map(DayStatus::cell).collect::<Vec<String>>().join("   "))?;
+    }
+    Ok(())
+}
+
+/// Explain the marks used by [`write_calendar`].
+pub(crate) fn write_legend<W: Write>(out: &mut W) -> Result<()> {
+    writeln!(out, "* implemented star   i input present   v stored answer")?;
+    Ok(())
+}
+
+/// Write a year as a Markdown table, for a README.
+pub(crate) fn write_markdown<W: Write>(out: &mut W, year: AoCYear, days: &[DayStatus]) -> Result<()> {
+    writeln!(out, "## {year}\n\n{}\n", totals(days))?;
+    writeln!(out, "| Day | Title | Stars | Input | Answers |")?;
+    writeln!(out, "| --: | --- | :-: | :-: | :-: |")?;
+    for day in days {
+        writeln!(
+            out,
+            "| {} | {} | {} | {} | {} |",
+            day.day.number(),
+            day.title.unwrap_or(""),
+            "★".repeat(day.stars.into()),
+            if day.input { "✓" } else { "" },
+            "✓".repeat(day.answers.into())
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DayStatus, write_calendar, write_markdown, year_status};
+    use crate::constants::{AoCDay, AoCYear};
+    use anyhow::Result;
+
+    #[test]
+    fn stars_come_from_solvers() -> Result<()> {
+        let day = DayStatus::load(AoCYear::AOC2017, AoCDay::AOCD01, "data_file")?;
+        assert_eq!((*day.stars(), *day.input(), *day.title()), (2, true, Some("Inverse Captcha")));
+        let missing = DayStatus::load(AoCYear::AOC2016, AoCDay::AOCD01, "no_such_file")?;
+        assert_eq!((*missing.stars(), *missing.input(), *missing.answers(), *missing.title()), (0, false, 0, None));
+        Ok(())
+    }
+
+    #[test]
+    fn calendar() -> Result<()> {
+        let mut days = year_status(AoCYear::AOC2016, "no_such_file")?;
+        days[0] = DayStatus {
+            title: Some("No Time for a Taxicab"),
+            stars: 2,
+            input: true,
+            answers: 1,
+            ..days[0]
+        };
+
+        let mut out = Vec::new();
+        write_calendar(&mut out, AoCYear::AOC2016, &days)?;
+        let out = String::from_utf8(out)?;
+        assert!(out.starts_with("2016  2/50 stars, 1/25 inputs, 1/50 answers\n  01 ** i v-   02 -- - --   03 -- - --"));
+        assert_eq!(out.lines().count(), 6);
+        assert!(out.ends_with("25 -- - --\n"));
+
+        let mut out = Vec::new();
+        write_markdown(&mut out, AoCYear::AOC2016, &days)?;
+        let out = String::from_utf8(out)?;
+        assert!(out.starts_with("## 2016\n\n2/50 stars, 1/25 inputs, 1/50 answers\n\n| Day |"));
+        assert!(out.contains("| 1 | No Time for a Taxicab | ★★ | ✓ | ✓ |\n| 2 |  |  |  |  |\n"));
+        Ok(())
+    }
+}
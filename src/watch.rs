@@ -0,0 +1,153 @@
+// Copyright (c) 2017 aoc developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Re-running a day whenever its files change
+//!
+//! Files are polled rather than watched with OS notifications, and compared by
+//! content, so editors that replace a file instead of writing it in place are
+//! still noticed.
+use anyhow::Result;
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::{Hash, Hasher},
+    io::Write,
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+/// How often the watched files are checked.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Clears the terminal and moves the cursor home.
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// A fingerprint of a file's contents, or `None` if it cannot be read.
+fn fingerprint(path: &Path) -> Option<u64> {
+    let contents = fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// Polls a set of files for changes.
+#[derive(Debug)]
+pub(crate) struct Watcher {
+    /// The watched files
+    paths: Vec<PathBuf>,
+    /// The fingerprints seen at the last poll, or `None` before the first
+    seen: Option<Vec<Option<u64>>>,
+}
+
+impl Watcher {
+    /// Watch `paths`, which need not exist yet.
+    pub(crate) fn new(paths: Vec<PathBuf>) -> Self {
+        Self { paths, seen: None }
+    }
+
+    /// Whether any file was created, changed or removed since the last poll.  The first poll always counts as a change.
+    pub(crate) fn changed(&mut self) -> bool {
+        let current: Vec<Option<u64>> = self.paths.iter().map(|path| fingerprint(path)).collect();
+        let changed = self.seen.as_ref() != Some(&current);
+        self.seen = Some(current);
+        changed
+    }
+
+    /// Call `run` with a cleared screen if anything changed, writing any error it returns.  Returns whether it ran.
+    ///
+    /// `run` writes its results to `out`, while the screen clear, errors and the banner go to `messages`, so
+    /// they can be kept out of a stream of records.
+    pub(crate) fn poll<W, M, F>(&mut self, out: &mut W, messages: &mut M, run: &mut F) -> Result<bool>
+    where
+        W: Write,
+        M: Write,
+        F: FnMut(&mut W) -> Result<()>,
+    {
+        if !self.changed() {
+            return Ok(false);
+        }
+        write!(messages, "{CLEAR_SCREEN}")?;
+        messages.flush()?;
+        let result = run(out);
+        out.flush()?;
+        if let Err(e) = result {
+            writeln!(messages, "error: {e}")?;
+        }
+        let paths: Vec<String> = self.paths.iter().map(|path| path.display().to_string()).collect();
+        writeln!(messages, "\nWatching {} for changes, Ctrl-C to stop", paths.join(", "))?;
+        messages.flush()?;
+        Ok(true)
+    }
+
+    /// Poll forever, calling `run` after every change.
+    pub(crate) fn watch<W, M, F>(&mut self, out: &mut W, messages: &mut M, mut run: F) -> Result<()>
+    where
+        W: Write,
+        M: Write,
+        F: FnMut(&mut W) -> Result<()>,
+    {
+        loop {
+            let _ = self.poll(out, messages, &mut run)?;
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CLEAR_SCREEN, Watcher};
+    use anyhow::{Result, anyhow};
+    use std::{fs, io::Write};
+
+    #[test]
+    fn changes() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (input, answers) = (dir.path().join("data_file"), dir.path().join("answers"));
+        fs::write(&input, "1122")?;
+        let mut watcher = Watcher::new(vec![input.clone(), answers.clone()]);
+
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+        fs::write(&input, "1111")?;
+        assert!(watcher.changed());
+        fs::write(&input, "1111")?;
+        assert!(!watcher.changed());
+        fs::write(&answers, "part1=4")?;
+        assert!(watcher.changed());
+        fs::remove_file(&input)?;
+        assert!(watcher.changed());
+        Ok(())
+    }
+
+    #[test]
+    fn poll() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let input = dir.path().join("data_file");
+        fs::write(&input, "1122")?;
+        let mut watcher = Watcher::new(vec![input.clone()]);
+        let mut runs = 0;
+        let mut run = |out: &mut Vec<u8>| {
+            runs += 1;
+            writeln!(out, "Part 1: {}", fs::read_to_string(&input)?)?;
+            if runs > 1 { Err(anyhow!("bad input")) } else { Ok(()) }
+        };
+
+        let (mut out, mut messages) = (Vec::new(), Vec::new());
+        assert!(watcher.poll(&mut out, &mut messages, &mut run)?);
+        assert!(!watcher.poll(&mut out, &mut messages, &mut run)?);
+        assert_eq!(String::from_utf8(out)?, "Part 1: 1122\n");
+        assert!(String::from_utf8(messages)?.starts_with(&format!("{CLEAR_SCREEN}\nWatching ")));
+
+        fs::write(dir.path().join("data_file"), "91212129")?;
+        let (mut out, mut messages) = (Vec::new(), Vec::new());
+        assert!(watcher.poll(&mut out, &mut messages, &mut run)?);
+        assert_eq!(String::from_utf8(out)?, "Part 1: 91212129\n");
+        assert!(String::from_utf8(messages)?.starts_with(&format!("{CLEAR_SCREEN}error: bad input\n")));
+        Ok(())
+    }
+}
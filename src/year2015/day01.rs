@@ -1,29 +1,61 @@
 //! Advent of Code - Day 1 "Not Quite Lisp" Solution
-use anyhow::{Result, anyhow};
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::ParseError;
+use crate::solver::Solver;
+use anyhow::Result;
 use std::io::BufRead;
 
+/// Day 1 "Not Quite Lisp"
+pub(crate) struct Day01;
+
+impl Solver for Day01 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2015
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD01
+    }
+
+    fn title(&self) -> &'static str {
+        "Not Quite Lisp"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Find the solution
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     if second_star {
-        let found_at = find_basement(reader)?;
-        println!("{found_at}");
+        Ok(find_basement(reader)?.into())
     } else {
-        let floor = find_floor(reader)?;
-        println!("{floor}");
+        Ok(find_floor(reader)?.into())
+    }
+}
+
+/// The change of floor for the instruction `ch`, at byte `col` of the line at index `idx`.
+fn step(idx: usize, line: &str, col: usize, ch: char) -> Result<isize> {
+    match ch {
+        '(' => Ok(1),
+        ')' => Ok(-1),
+        _ => Err(ParseError::new(AoCYear::AOC2015, AoCDay::AOCD01, idx, line, &line[col..col + ch.len_utf8()], "`(` or `)`").into()),
     }
-    Ok(0)
 }
 
 fn find_floor<T: BufRead>(reader: T) -> Result<isize> {
     let mut floor: isize = 0;
 
-    for line in reader.lines().map_while(Result::ok) {
-        for ch in line.chars() {
-            match ch {
-                '(' => floor += 1,
-                ')' => floor -= 1,
-                _ => return Err(anyhow!("invalid floor")),
-            }
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        for (col, ch) in line.char_indices() {
+            floor += step(idx, &line, col, ch)?;
         }
     }
 
@@ -34,13 +66,10 @@ fn find_basement<T: BufRead>(reader: T) -> Result<u32> {
     let mut floor = 0;
     let mut idx = 1;
 
-    'outer: for line in reader.lines().map_while(Result::ok) {
-        for ch in line.chars() {
-            match ch {
-                '(' => floor += 1,
-                ')' => floor -= 1,
-                _ => return Err(anyhow!("invalid floor")),
-            }
+    'outer: for (line_idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        for (col, ch) in line.char_indices() {
+            floor += step(line_idx, &line, col, ch)?;
             if floor == -1 {
                 break 'outer;
             }
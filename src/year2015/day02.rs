@@ -1,23 +1,54 @@
 //! Advent of Code - Day 2 Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::ParseError;
+use crate::solver::Solver;
 use anyhow::Result;
 use regex::Regex;
 use std::cmp::min;
 use std::io::BufRead;
 
+/// Day 2 "I Was Told There Would Be No Math"
+pub(crate) struct Day02;
+
+impl Solver for Day02 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2015
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD02
+    }
+
+    fn title(&self) -> &'static str {
+        "I Was Told There Would Be No Math"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Find the solution
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    let line_re = Regex::new(r"(\d+)x(\d+)x(\d+)")?;
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
+    let line_re = Regex::new(r"^(\d+)x(\d+)x(\d+)$")?;
     let mut answer = 0;
 
-    for line in reader.lines().map_while(Result::ok) {
-        let mut length = 0;
-        let mut width = 0;
-        let mut height = 0;
-        for cap in line_re.captures_iter(&line) {
-            length = (cap[1]).parse::<usize>()?;
-            width = (cap[2]).parse::<usize>()?;
-            height = (cap[3]).parse::<usize>()?;
-        }
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let cap = line_re
+            .captures(&line)
+            .ok_or_else(|| ParseError::whole_line(AoCYear::AOC2015, AoCDay::AOCD02, idx, &line, "dimensions like `2x3x4`"))?;
+        let number = |token: &str| {
+            token
+                .parse::<usize>()
+                .map_err(|_| ParseError::new(AoCYear::AOC2015, AoCDay::AOCD02, idx, &line, token, "a number that fits in a usize"))
+        };
+        let (length, width, height) = (number(&cap[1])?, number(&cap[2])?, number(&cap[3])?);
 
         if second_star {
             let mut tmp = [length, width, height];
@@ -33,12 +64,13 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
         }
     }
 
-    Ok(answer as u32)
+    Ok(answer.into())
 }
 
 #[cfg(test)]
 mod one_star {
     use super::find_solution;
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -47,8 +79,8 @@ mod one_star {
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), false)?, 58);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_1), false)?, 43);
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), false)?, Answer::Unsigned(58));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_1), false)?, Answer::Unsigned(43));
         Ok(())
     }
 }
@@ -56,6 +88,7 @@ mod one_star {
 #[cfg(test)]
 mod two_star {
     use super::find_solution;
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -64,8 +97,8 @@ mod two_star {
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), true)?, 34);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_1), true)?, 14);
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), true)?, Answer::Unsigned(34));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_1), true)?, Answer::Unsigned(14));
         Ok(())
     }
 }
@@ -1,13 +1,54 @@
 //! Advent of Code - Day 3 Solution
-use anyhow::{Result, anyhow};
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::ParseError;
+use crate::solver::Solver;
+use anyhow::Result;
 use std::collections::HashMap;
 use std::io::BufRead;
 
+/// Day 3 "Perfectly Spherical Houses in a Vacuum"
+pub(crate) struct Day03;
+
+impl Solver for Day03 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2015
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD03
+    }
+
+    fn title(&self) -> &'static str {
+        "Perfectly Spherical Houses in a Vacuum"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Find the solution
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let result = if second_star { count_houses_2(reader)? } else { count_houses(reader)? };
 
-    Ok(result as u32)
+    Ok(result.into())
+}
+
+/// The error for the character `ch` at byte `col` of the line at index `idx`, which is not a direction.
+fn bad_direction(idx: usize, line: &str, col: usize, ch: char) -> ParseError {
+    ParseError::new(
+        AoCYear::AOC2015,
+        AoCDay::AOCD03,
+        idx,
+        line,
+        &line[col..col + ch.len_utf8()],
+        "`^`, `>`, `v` or `<`",
+    )
 }
 
 fn count_houses<T: BufRead>(reader: T) -> Result<usize> {
@@ -17,21 +58,22 @@ fn count_houses<T: BufRead>(reader: T) -> Result<usize> {
 
     house_map.entry((x, y)).or_insert(1_usize);
 
-    for line in reader.lines().map_while(Result::ok) {
-        for ch in line.chars() {
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        for (col, ch) in line.char_indices() {
             match ch {
                 '^' => y += 1,
                 '>' => x += 1,
                 'v' => y -= 1,
                 '<' => x -= 1,
-                _ => return Err(anyhow!("invalid floor")),
+                _ => return Err(bad_direction(idx, &line, col, ch).into()),
             }
 
             *house_map.entry((x, y)).or_insert(0) += 1;
         }
     }
 
-    Ok(house_map.iter().filter_map(|(_, y)| if *y > 0 { Some(*y) } else { None }).count())
+    Ok(house_map.values().filter_map(|y| if *y > 0 { Some(*y) } else { None }).count())
 }
 
 fn count_houses_2<T: BufRead>(reader: T) -> Result<usize> {
@@ -45,8 +87,9 @@ fn count_houses_2<T: BufRead>(reader: T) -> Result<usize> {
     house_map.entry((s_x, s_y)).or_insert(1_usize);
     *house_map.entry((r_x, r_y)).or_insert(1_usize) += 1;
 
-    for line in reader.lines().map_while(Result::ok) {
-        for ch in line.chars() {
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        for (col, ch) in line.char_indices() {
             match ch {
                 '^' => {
                     if is_santa {
@@ -76,7 +119,7 @@ fn count_houses_2<T: BufRead>(reader: T) -> Result<usize> {
                         r_x -= 1
                     }
                 }
-                _ => return Err(anyhow!("invalid floor")),
+                _ => return Err(bad_direction(idx, &line, col, ch).into()),
             }
 
             if is_santa {
@@ -89,12 +132,13 @@ fn count_houses_2<T: BufRead>(reader: T) -> Result<usize> {
         }
     }
 
-    Ok(house_map.iter().filter_map(|(_, y)| if *y > 0 { Some(*y) } else { None }).count())
+    Ok(house_map.values().filter_map(|y| if *y > 0 { Some(*y) } else { None }).count())
 }
 
 #[cfg(test)]
 mod one_star {
     use super::find_solution;
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -104,9 +148,9 @@ mod one_star {
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), false)?, 2);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_1), false)?, 4);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_2), false)?, 2);
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), false)?, Answer::Unsigned(2));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_1), false)?, Answer::Unsigned(4));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_2), false)?, Answer::Unsigned(2));
         Ok(())
     }
 }
@@ -114,6 +158,7 @@ mod one_star {
 #[cfg(test)]
 mod two_star {
     use super::find_solution;
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -123,9 +168,9 @@ mod two_star {
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), true)?, 3);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_1), true)?, 3);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_2), true)?, 11);
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), true)?, Answer::Unsigned(3));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_1), true)?, Answer::Unsigned(3));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_2), true)?, Answer::Unsigned(11));
         Ok(())
     }
 }
@@ -1,20 +1,49 @@
 //! Advent of Code - Day 4 Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::solver::Solver;
 use anyhow::Result;
 use std::io::BufRead;
 
+/// Day 4 "The Ideal Stocking Stuffer"
+pub(crate) struct Day04;
+
+impl Solver for Day04 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2015
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD04
+    }
+
+    fn title(&self) -> &'static str {
+        "The Ideal Stocking Stuffer"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Find the solution
-pub fn find_solution<T: BufRead>(reader: T, _second_star: bool) -> Result<u32> {
+pub fn find_solution<T: BufRead>(reader: T, _second_star: bool) -> Result<Answer> {
     let result = if _second_star {
         find_lowest(reader, "000000")?
     } else {
         find_lowest(reader, "00000")?
     };
-    Ok(result)
+    Ok(result.into())
 }
 
 fn find_lowest<T: BufRead>(reader: T, start_str: &str) -> Result<u32> {
     let mut count = 1;
-    for line in reader.lines().map_while(Result::ok) {
+    for line in reader.lines() {
+        let line = line?;
         loop {
             let check = format!("{line}{count}");
             let digest = md5::compute(check.as_bytes());
@@ -33,6 +62,7 @@ fn find_lowest<T: BufRead>(reader: T, start_str: &str) -> Result<u32> {
 #[cfg(test)]
 mod one_star {
     use super::find_solution;
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -41,8 +71,8 @@ mod one_star {
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), false)?, 609_043);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_1), false)?, 1_048_970);
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), false)?, Answer::Unsigned(609_043));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_1), false)?, Answer::Unsigned(1_048_970));
         Ok(())
     }
 }
@@ -1,11 +1,39 @@
 //! Advent of Code - Day 5 Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::solver::Solver;
 use anyhow::Result;
 use std::collections::HashMap;
 use std::io::BufRead;
 
+/// Day 5 "Doesn't He Have Intern-Elves For This?"
+pub(crate) struct Day05;
+
+impl Solver for Day05 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2015
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD05
+    }
+
+    fn title(&self) -> &'static str {
+        "Doesn't He Have Intern-Elves For This?"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Find the solution
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    find_nice(reader, second_star)
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
+    Ok(find_nice(reader, second_star)?.into())
 }
 
 fn is_nice(line: &[char]) -> bool {
@@ -91,7 +119,8 @@ fn is_nice2(line: &[char]) -> bool {
 
 fn find_nice<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
     let mut nice = 0;
-    for line in reader.lines().map_while(Result::ok) {
+    for line in reader.lines() {
+        let line = line?;
         let ch: Vec<char> = line.chars().collect();
 
         if (second_star && is_nice2(&ch)) || (!second_star && is_nice(&ch)) {
@@ -104,6 +133,7 @@ fn find_nice<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
 #[cfg(test)]
 mod one_star {
     use super::find_solution;
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -115,11 +145,11 @@ mod one_star {
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), false)?, 1);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_1), false)?, 1);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_2), false)?, 0);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_3), false)?, 0);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_4), false)?, 0);
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), false)?, Answer::Unsigned(1));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_1), false)?, Answer::Unsigned(1));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_2), false)?, Answer::Unsigned(0));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_3), false)?, Answer::Unsigned(0));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_4), false)?, Answer::Unsigned(0));
         Ok(())
     }
 }
@@ -127,6 +157,7 @@ mod one_star {
 #[cfg(test)]
 mod two_star {
     use super::find_solution;
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -137,10 +168,10 @@ mod two_star {
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), true)?, 1);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_1), true)?, 1);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_2), true)?, 0);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_3), false)?, 0);
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), true)?, Answer::Unsigned(1));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_1), true)?, Answer::Unsigned(1));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_2), true)?, Answer::Unsigned(0));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_3), false)?, Answer::Unsigned(0));
         Ok(())
     }
 }
@@ -1,50 +1,98 @@
 //! Advent of Code - Day 6 Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
-use ndarray::Array2;
 use regex::Regex;
 use std::io::BufRead;
 
+/// The width and height of the grid of lights.
+const SIZE: usize = 1000;
+
+/// Day 6 "Probably a Fire Hazard"
+pub(crate) struct Day06;
+
+impl Solver for Day06 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2015
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD06
+    }
+
+    fn title(&self) -> &'static str {
+        "Probably a Fire Hazard"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false, &Context::default())
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true, &Context::default())
+    }
+
+    fn star(&self, part: u8, reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer> {
+        find_solution(reader, part == 2, ctx)
+    }
+}
+
 /// Find the solution
-pub fn find_solution<T: BufRead>(reader: T, _second_star: bool) -> Result<u32> {
-    let result = if _second_star { brighten(reader)? } else { decorate(reader)? };
-    Ok(result as u32)
+pub fn find_solution<T: BufRead>(reader: T, _second_star: bool, ctx: &Context) -> Result<Answer> {
+    let result = if _second_star { brighten(reader, ctx)? } else { decorate(reader, ctx)? };
+    Ok(result.into())
+}
+
+/// Parse an instruction like `turn on 0,0 through 999,999` into its action and corners.
+fn parse_instruction<'a>(line_re: &Regex, idx: usize, line: &'a str) -> Result<(&'a str, [usize; 4])> {
+    let cap = line_re
+        .captures(line)
+        .ok_or_else(|| ParseError::whole_line(AoCYear::AOC2015, AoCDay::AOCD06, idx, line, "an instruction like `turn on 0,0 through 999,999`"))?;
+    let mut corners = [0; 4];
+    for (corner, group) in corners.iter_mut().zip(2..) {
+        let token = cap.get(group).map_or("", |m| m.as_str());
+        *corner = token
+            .parse::<usize>()
+            .ok()
+            .filter(|coordinate| *coordinate < SIZE)
+            .ok_or_else(|| ParseError::new(AoCYear::AOC2015, AoCDay::AOCD06, idx, line, token, "a coordinate from 0 to 999"))?;
+    }
+    Ok((cap.get(1).map_or("", |m| m.as_str()), corners))
 }
 
-fn decorate<T: BufRead>(reader: T) -> Result<usize> {
-    let mut lights: Array2<bool> = Array2::default((1000, 1000));
-    let line_re = Regex::new(r"(turn on|turn off|toggle) (\d+),(\d+) through (\d+),(\d+)")?;
-
-    for line in reader.lines().map_while(Result::ok) {
-        for cap in line_re.captures_iter(&line) {
-            let action = &cap[1];
-            let x1 = (cap[2]).parse::<usize>()?;
-            let y1 = (cap[3]).parse::<usize>()?;
-            let x2 = (cap[4]).parse::<usize>()?;
-            let y2 = (cap[5]).parse::<usize>()?;
-
-            // println!("Action: {}", action);
-            match action {
-                "turn on" => {
-                    // println!( "Turning on  {},{} through {},{}", x1, y1, x2, y2);
-                    turn_on(&mut lights, x1, y1, x2, y2);
-                }
-                "turn off" => {
-                    // println!("Turning off {},{} through {},{}", x1, y1, x2, y2);
-                    turn_off(&mut lights, x1, y1, x2, y2);
-                }
-                "toggle" => {
-                    // println!("Toggling    {},{} through {},{}", x1, y1, x2, y2);
-                    toggle(&mut lights, x1, y1, x2, y2);
-                }
-                _ => return Err(anyhow!("invalid command")),
+fn decorate<T: BufRead>(reader: T, ctx: &Context) -> Result<usize> {
+    let mut lights = Grid::new(SIZE, SIZE, false);
+    let line_re = Regex::new(r"^(turn on|turn off|toggle) (\d+),(\d+) through (\d+),(\d+)$")?;
+
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let (action, [x1, y1, x2, y2]) = parse_instruction(&line_re, idx, &line)?;
+
+        if ctx.tracing() {
+            eprintln!("{action} {x1},{y1} through {x2},{y2}");
+        }
+        match action {
+            "turn on" => {
+                turn_on(&mut lights, x1, y1, x2, y2);
             }
+            "turn off" => {
+                turn_off(&mut lights, x1, y1, x2, y2);
+            }
+            "toggle" => {
+                toggle(&mut lights, x1, y1, x2, y2);
+            }
+            _ => return Err(anyhow!("invalid command")),
         }
     }
 
-    Ok(lights.iter().filter(|x| **x).count())
+    Ok(lights.cells().filter(|x| **x).count())
 }
 
-fn turn_on(lights: &mut Array2<bool>, x1: usize, y1: usize, x2: usize, y2: usize) {
+fn turn_on(lights: &mut Grid<bool>, x1: usize, y1: usize, x2: usize, y2: usize) {
     for x in x1..=x2 {
         for y in y1..=y2 {
             lights[[x, y]] = true;
@@ -52,7 +100,7 @@ fn turn_on(lights: &mut Array2<bool>, x1: usize, y1: usize, x2: usize, y2: usize
     }
 }
 
-fn toggle(lights: &mut Array2<bool>, x1: usize, y1: usize, x2: usize, y2: usize) {
+fn toggle(lights: &mut Grid<bool>, x1: usize, y1: usize, x2: usize, y2: usize) {
     for x in x1..=x2 {
         for y in y1..=y2 {
             lights[[x, y]] = !lights[[x, y]];
@@ -60,7 +108,7 @@ fn toggle(lights: &mut Array2<bool>, x1: usize, y1: usize, x2: usize, y2: usize)
     }
 }
 
-fn turn_off(lights: &mut Array2<bool>, x1: usize, y1: usize, x2: usize, y2: usize) {
+fn turn_off(lights: &mut Grid<bool>, x1: usize, y1: usize, x2: usize, y2: usize) {
     for x in x1..=x2 {
         for y in y1..=y2 {
             lights[[x, y]] = false;
@@ -68,41 +116,35 @@ fn turn_off(lights: &mut Array2<bool>, x1: usize, y1: usize, x2: usize, y2: usiz
     }
 }
 
-fn brighten<T: BufRead>(reader: T) -> Result<usize> {
-    let mut lights: Array2<usize> = Array2::zeros((1000, 1000));
-    let line_re = Regex::new(r"(turn on|turn off|toggle) (\d+),(\d+) through (\d+),(\d+)")?;
-
-    for line in reader.lines().map_while(Result::ok) {
-        for cap in line_re.captures_iter(&line) {
-            let action = &cap[1];
-            let x1 = (cap[2]).parse::<usize>()?;
-            let y1 = (cap[3]).parse::<usize>()?;
-            let x2 = (cap[4]).parse::<usize>()?;
-            let y2 = (cap[5]).parse::<usize>()?;
-
-            // println!("Action: {}", action);
-            match action {
-                "turn on" => {
-                    // println!( "Turning on  {},{} through {},{}", x1, y1, x2, y2);
-                    increase_brightness(&mut lights, x1, y1, x2, y2);
-                }
-                "turn off" => {
-                    // println!("Turning off {},{} through {},{}", x1, y1, x2, y2);
-                    decrease_brightness(&mut lights, x1, y1, x2, y2);
-                }
-                "toggle" => {
-                    // println!("Toggling    {},{} through {},{}", x1, y1, x2, y2);
-                    really_brighten(&mut lights, x1, y1, x2, y2);
-                }
-                _ => return Err(anyhow!("invalid command")),
+fn brighten<T: BufRead>(reader: T, ctx: &Context) -> Result<usize> {
+    let mut lights = Grid::new(SIZE, SIZE, 0);
+    let line_re = Regex::new(r"^(turn on|turn off|toggle) (\d+),(\d+) through (\d+),(\d+)$")?;
+
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let (action, [x1, y1, x2, y2]) = parse_instruction(&line_re, idx, &line)?;
+
+        if ctx.tracing() {
+            eprintln!("{action} {x1},{y1} through {x2},{y2}");
+        }
+        match action {
+            "turn on" => {
+                increase_brightness(&mut lights, x1, y1, x2, y2);
+            }
+            "turn off" => {
+                decrease_brightness(&mut lights, x1, y1, x2, y2);
+            }
+            "toggle" => {
+                really_brighten(&mut lights, x1, y1, x2, y2);
             }
+            _ => return Err(anyhow!("invalid command")),
         }
     }
 
-    Ok(lights.iter().sum())
+    Ok(lights.cells().sum())
 }
 
-fn increase_brightness(lights: &mut Array2<usize>, x1: usize, y1: usize, x2: usize, y2: usize) {
+fn increase_brightness(lights: &mut Grid<usize>, x1: usize, y1: usize, x2: usize, y2: usize) {
     for x in x1..=x2 {
         for y in y1..=y2 {
             lights[[x, y]] += 1;
@@ -110,7 +152,7 @@ fn increase_brightness(lights: &mut Array2<usize>, x1: usize, y1: usize, x2: usi
     }
 }
 
-fn decrease_brightness(lights: &mut Array2<usize>, x1: usize, y1: usize, x2: usize, y2: usize) {
+fn decrease_brightness(lights: &mut Grid<usize>, x1: usize, y1: usize, x2: usize, y2: usize) {
     for x in x1..=x2 {
         for y in y1..=y2 {
             if lights[[x, y]] >= 1 {
@@ -120,7 +162,7 @@ fn decrease_brightness(lights: &mut Array2<usize>, x1: usize, y1: usize, x2: usi
     }
 }
 
-fn really_brighten(lights: &mut Array2<usize>, x1: usize, y1: usize, x2: usize, y2: usize) {
+fn really_brighten(lights: &mut Grid<usize>, x1: usize, y1: usize, x2: usize, y2: usize) {
     for x in x1..=x2 {
         for y in y1..=y2 {
             lights[[x, y]] += 2;
@@ -131,6 +173,7 @@ fn really_brighten(lights: &mut Array2<usize>, x1: usize, y1: usize, x2: usize,
 #[cfg(test)]
 mod one_star {
     use super::decorate;
+    use crate::context::Context;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -141,9 +184,9 @@ turn off 499,499 through 500,500";
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(decorate(Cursor::new(TEST_CHAIN))?, 1_000_000);
-        assert_eq!(decorate(Cursor::new(TEST_CHAIN_1))?, 1_000);
-        assert_eq!(decorate(Cursor::new(TEST_CHAIN_2))?, 999_996);
+        assert_eq!(decorate(Cursor::new(TEST_CHAIN), &Context::default())?, 1_000_000);
+        assert_eq!(decorate(Cursor::new(TEST_CHAIN_1), &Context::default())?, 1_000);
+        assert_eq!(decorate(Cursor::new(TEST_CHAIN_2), &Context::default())?, 999_996);
         Ok(())
     }
 }
@@ -151,6 +194,7 @@ turn off 499,499 through 500,500";
 #[cfg(test)]
 mod two_star {
     use super::brighten;
+    use crate::context::Context;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -159,8 +203,8 @@ mod two_star {
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(brighten(Cursor::new(TEST_CHAIN))?, 1);
-        assert_eq!(brighten(Cursor::new(TEST_CHAIN_1))?, 2_000_000);
+        assert_eq!(brighten(Cursor::new(TEST_CHAIN), &Context::default())?, 1);
+        assert_eq!(brighten(Cursor::new(TEST_CHAIN_1), &Context::default())?, 2_000_000);
         Ok(())
     }
 }
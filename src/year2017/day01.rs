@@ -1,15 +1,47 @@
 //! Advent of Code - Day 1 "Inverse Captcha" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::ParseError;
+use crate::solver::Solver;
 use anyhow::Result;
 use std::io::BufRead;
 
+/// Day 1 "Inverse Captcha"
+pub(crate) struct Day01;
+
+impl Solver for Day01 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD01
+    }
+
+    fn title(&self) -> &'static str {
+        "Inverse Captcha"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Parse the input file and calculate the captcha.
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let mut result = 0;
-    for line_result in reader.lines() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
+    for (idx, line_result) in reader.lines().enumerate() {
+        let line = &line_result?;
+        if let Some((col, ch)) = line.char_indices().find(|(_, ch)| !ch.is_ascii_digit()) {
+            return Err(ParseError::new(AoCYear::AOC2017, AoCDay::AOCD01, idx, line, &line[col..col + ch.len_utf8()], "a digit").into());
+        }
         result = val(line, second_star)?;
     }
-    Ok(result)
+    Ok(result.into())
 }
 
 /// Calculate the 'inverse captcha' value for a byte array.
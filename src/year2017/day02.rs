@@ -1,32 +1,75 @@
 //! Advent of Code - Day 2 "Corruption Checksum" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::ParseError;
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
 use std::cmp;
 use std::io::BufRead;
 
+/// Day 2 "Corruption Checksum"
+pub(crate) struct Day02;
+
+impl Solver for Day02 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD02
+    }
+
+    fn title(&self) -> &'static str {
+        "Corruption Checksum"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Parse the file at `filename` and generate the checksum.
-pub fn find_solution<T: BufRead>(reader: T, use_div: bool) -> Result<u32> {
+pub fn find_solution<T: BufRead>(reader: T, use_div: bool) -> Result<Answer> {
     let mut checksum = 0;
 
-    for line_result in reader.lines() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
+    for (idx, line_result) in reader.lines().enumerate() {
+        let row = parse_row(idx, &line_result?)?;
         if use_div {
-            checksum += row_evenly_divisible_value(line)?;
+            checksum += row_evenly_divisible_value(&row)?;
         } else {
-            checksum += row_min_max_diff(line)?;
+            checksum += row_min_max_diff(&row)?;
         }
     }
 
-    Ok(checksum)
+    Ok(checksum.into())
 }
 
-/// Find the difference between the max and min of a whitespace separated string
-fn row_min_max_diff(line: &str) -> Result<u32> {
-    let vals_iter = line.split_whitespace();
+/// Parse a row of whitespace separated values, the line at index `idx` of the input.
+fn parse_row(idx: usize, line: &str) -> Result<Vec<u32>> {
+    let row = line
+        .split_whitespace()
+        .map(|token| {
+            token
+                .parse::<u32>()
+                .map_err(|_| ParseError::new(AoCYear::AOC2017, AoCDay::AOCD02, idx, line, token, "a number"))
+        })
+        .collect::<Result<Vec<u32>, _>>()?;
+    if row.is_empty() {
+        return Err(ParseError::whole_line(AoCYear::AOC2017, AoCDay::AOCD02, idx, line, "a row of numbers").into());
+    }
+    Ok(row)
+}
+
+/// Find the difference between the max and min of a row
+fn row_min_max_diff(row: &[u32]) -> Result<u32> {
     let mut min = u32::MAX;
     let mut max = 0;
 
-    for val_str in vals_iter {
-        let val = val_str.parse::<u32>()?;
+    for &val in row {
         if val < min {
             min = val;
         }
@@ -39,14 +82,9 @@ fn row_min_max_diff(line: &str) -> Result<u32> {
     Ok(max - min)
 }
 
-/// Find the only two evenly divisible values in a whitespace separated string
-fn row_evenly_divisible_value(line: &str) -> Result<u32> {
-    let vals_iter = line.split_whitespace();
-    let mut vals_vec = Vec::new();
-    for val_str in vals_iter {
-        vals_vec.push(val_str.parse::<u32>()?);
-    }
-    let inner_vals_vec = vals_vec.clone();
+/// Find the only two evenly divisible values in a row
+fn row_evenly_divisible_value(vals_vec: &[u32]) -> Result<u32> {
+    let inner_vals_vec = vals_vec;
 
     for (idx, val) in vals_vec.iter().enumerate() {
         for (next_idx, next_val) in inner_vals_vec.iter().enumerate() {
@@ -57,7 +95,7 @@ fn row_evenly_divisible_value(line: &str) -> Result<u32> {
             let numerator = cmp::max(*val, *next_val);
             let denominator = cmp::min(*val, *next_val);
 
-            if numerator % denominator == 0 {
+            if denominator != 0 && numerator % denominator == 0 {
                 return Ok(numerator / denominator);
             }
         }
@@ -69,9 +107,9 @@ fn row_evenly_divisible_value(line: &str) -> Result<u32> {
 mod one_star {
     #[test]
     fn solution() {
-        assert_eq!(super::row_min_max_diff("5 1 9 5").unwrap_or(0), 8);
-        assert_eq!(super::row_min_max_diff("7 5 3").unwrap_or(0), 4);
-        assert_eq!(super::row_min_max_diff("2 4 6 8").unwrap_or(0), 6);
+        assert_eq!(super::row_min_max_diff(&[5, 1, 9, 5]).unwrap_or(0), 8);
+        assert_eq!(super::row_min_max_diff(&[7, 5, 3]).unwrap_or(0), 4);
+        assert_eq!(super::row_min_max_diff(&[2, 4, 6, 8]).unwrap_or(0), 6);
     }
 }
 
@@ -79,8 +117,8 @@ mod one_star {
 mod two_star {
     #[test]
     fn solution() {
-        assert_eq!(super::row_evenly_divisible_value("5 9 2 8").unwrap_or(0), 4);
-        assert_eq!(super::row_evenly_divisible_value("9 4 7 3").unwrap_or(0), 3);
-        assert_eq!(super::row_evenly_divisible_value("3 8 6 5").unwrap_or(0), 2);
+        assert_eq!(super::row_evenly_divisible_value(&[5, 9, 2, 8]).unwrap_or(0), 4);
+        assert_eq!(super::row_evenly_divisible_value(&[9, 4, 7, 3]).unwrap_or(0), 3);
+        assert_eq!(super::row_evenly_divisible_value(&[3, 8, 6, 5]).unwrap_or(0), 2);
     }
 }
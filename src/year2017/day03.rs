@@ -1,15 +1,44 @@
 //! Advent of Code - Day 3 "Spiral Memory" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse;
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
 use std::collections::HashMap;
 use std::io::BufRead;
 
+/// Day 3 "Spiral Memory"
+pub(crate) struct Day03;
+
+impl Solver for Day03 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD03
+    }
+
+    fn title(&self) -> &'static str {
+        "Spiral Memory"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Parse the file at `filename` and generate the checksum.
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let mut checksum = 0;
 
-    for line_result in reader.lines() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
-        let value = line.parse::<u32>()?;
+    for (idx, line_result) in reader.lines().enumerate() {
+        let line = &line_result?;
+        let value = parse::number(AoCYear::AOC2017, AoCDay::AOCD03, idx, line, line)?;
         if second_star {
             checksum += next_biggest(value)?;
         } else {
@@ -17,7 +46,7 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
         }
     }
 
-    Ok(checksum)
+    Ok(checksum.into())
 }
 
 /// Calculate the number of steps it will take to drain the given
@@ -138,10 +167,11 @@ fn calculate_tuple_val(tuple: (i32, i32), tuple_map: &HashMap<(i32, i32), u32>)
         tuple_map.get(&(x - 1, y - 1)),
     ];
 
-    Ok(results
+    results
         .iter()
-        .filter(|x| x.is_some())
-        .fold(0, |sum, i| sum + i.expect("Invalid tuple_map value")))
+        .flatten()
+        .try_fold(0_u32, |sum, value| sum.checked_add(**value))
+        .ok_or_else(|| anyhow!("the values around {tuple:?} add up to more than a u32 holds"))
 }
 
 #[cfg(test)]
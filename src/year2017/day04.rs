@@ -1,14 +1,42 @@
 //! Advent of Code - Day 4 "High Entropy Passphrases" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::solver::Solver;
 use anyhow::Result;
 use std::collections::HashSet;
 use std::io::BufRead;
 
+/// Day 4 "High Entropy Passphrases"
+pub(crate) struct Day04;
+
+impl Solver for Day04 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD04
+    }
+
+    fn title(&self) -> &'static str {
+        "High Entropy Passphrases"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Parse the file at `filename` and generate the checksum.
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let mut count = 0;
 
     for line_result in reader.lines() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
+        let line = &line_result?;
         if second_star {
             count += u32::from(check_for_anagrams(line)?);
         } else {
@@ -16,7 +44,7 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
         }
     }
 
-    Ok(count)
+    Ok(count.into())
 }
 
 /// Check each passphrase for the same word an toss out any that violate.
@@ -1,17 +1,46 @@
 //! Advent of Code - Day 5 "A Maze of Twisty Trampolines All Alike" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse;
+use crate::solver::Solver;
 use anyhow::Result;
 use std::io::BufRead;
 
+/// Day 5 "A Maze of Twisty Trampolines, All Alike"
+pub(crate) struct Day05;
+
+impl Solver for Day05 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD05
+    }
+
+    fn title(&self) -> &'static str {
+        "A Maze of Twisty Trampolines, All Alike"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Parse the file at `filename` and generate the checksum.
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let mut jump_vec = Vec::new();
 
-    for line_result in reader.lines() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
-        jump_vec.push(line.parse::<i32>()?);
+    for (idx, line_result) in reader.lines().enumerate() {
+        let line = &line_result?;
+        jump_vec.push(parse::number(AoCYear::AOC2017, AoCDay::AOCD05, idx, line, line)?);
     }
 
-    jump_away(&mut jump_vec, second_star)
+    Ok(jump_away(&mut jump_vec, second_star)?.into())
 }
 
 /// Find an exit
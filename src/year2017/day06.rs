@@ -1,28 +1,54 @@
 //! Advent of Code - Day 6 "Memory Reallocation" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse;
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
 use std::collections::HashSet;
 use std::io::BufRead;
 
+/// Day 6 "Memory Reallocation"
+pub(crate) struct Day06;
+
+impl Solver for Day06 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD06
+    }
+
+    fn title(&self) -> &'static str {
+        "Memory Reallocation"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Parse the file at `filename` and generate the checksum.
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let mut reallocations = 0;
-    for line_result in reader.lines() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
-        reallocations = reallocate_memory(line, second_star)?;
+    for (idx, line_result) in reader.lines().enumerate() {
+        let line = &line_result?;
+        let banks = line
+            .split_whitespace()
+            .map(|token| parse::number(AoCYear::AOC2017, AoCDay::AOCD06, idx, line, token))
+            .collect::<Result<Vec<u32>, _>>()?;
+        reallocations = reallocate_memory(banks, second_star)?;
     }
 
-    Ok(reallocations)
+    Ok(reallocations.into())
 }
 
 /// Reallocate some memory blocks
-fn reallocate_memory(line: &str, find_again: bool) -> Result<u32> {
-    // Convert the line to a vector of u32.
-    let vals_iter = line.split_whitespace();
-    let mut vals_vec = Vec::new();
-    for val_str in vals_iter {
-        vals_vec.push(val_str.parse::<u32>()?);
-    }
-
+fn reallocate_memory(mut vals_vec: Vec<u32>, find_again: bool) -> Result<u32> {
     // Setup some state.
     let len = vals_vec.len();
     let mut once_more = find_again;
@@ -72,7 +98,7 @@ fn reallocate_memory(line: &str, find_again: bool) -> Result<u32> {
 mod one_star {
     #[test]
     fn solution() {
-        assert_eq!(super::reallocate_memory("0 2 7 0", false).unwrap_or(0), 5);
+        assert_eq!(super::reallocate_memory(vec![0, 2, 7, 0], false).unwrap_or(0), 5);
     }
 }
 
@@ -80,6 +106,6 @@ mod one_star {
 mod two_star {
     #[test]
     fn solution() {
-        assert_eq!(super::reallocate_memory("0 2 7 0", true).unwrap_or(0), 4);
+        assert_eq!(super::reallocate_memory(vec![0, 2, 7, 0], true).unwrap_or(0), 4);
     }
 }
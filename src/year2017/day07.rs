@@ -1,8 +1,50 @@
 //! Advent of Code - Day 7 "Recursive Circus" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::parse::ParseError;
+use crate::solver::{Solver, Star};
 use anyhow::{Result, anyhow};
 use std::collections::HashMap;
 use std::io::BufRead;
 
+/// Day 7 "Recursive Circus"
+pub(crate) struct Day07;
+
+impl Solver for Day07 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD07
+    }
+
+    fn title(&self) -> &'static str {
+        "Recursive Circus"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+
+    fn both(&self, reader: &mut dyn BufRead, _ctx: &Context) -> Result<(Star, Star)> {
+        let mut tower = None;
+        let first = Star::time(|| {
+            let built = build_tower(reader)?;
+            let name = root_name(&built)?;
+            tower = Some(built);
+            Ok(name)
+        });
+        let second = Star::time(|| corrected_weight(&mut tower.ok_or(anyhow!("Unable to build the tower"))?));
+        Ok((first, second))
+    }
+}
+
 /// Day 7 Node
 #[derive(Clone)]
 struct Node {
@@ -20,59 +62,86 @@ struct Node {
     children: Option<Vec<usize>>,
 }
 
+/// The parsed tower of programs, shared by both stars.
+struct Tower {
+    /// The programs in the tower
+    nodes: Vec<Node>,
+    /// The child names of each program, by program id
+    children: HashMap<usize, Vec<String>>,
+    /// The id of the bottom program
+    root: usize,
+}
+
 /// Parse the file at `filename` and generate the checksum.
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    use std::io::{self, Write};
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
+    let mut tower = build_tower(reader)?;
+
+    if second_star { corrected_weight(&mut tower) } else { root_name(&tower) }
+}
+
+/// Parse the program descriptions and find the bottom of the tower.
+fn build_tower<T: BufRead>(reader: T) -> Result<Tower> {
     let mut nodes: Vec<Node> = Vec::new();
     let mut children: HashMap<usize, Vec<String>> = HashMap::new();
     for (id, line_result) in reader.lines().enumerate() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
+        let line = &line_result?;
         parse_line(line, id, &mut nodes, &mut children)?;
     }
 
     assign_parents(&mut nodes, &mut children)?;
+    let root = find_root(&nodes)?;
+    Ok(Tower { nodes, children, root })
+}
 
-    if second_star {
-        let idx = find_root(&nodes)?;
-        assign_children(&mut nodes, &children)?;
-        let root = nodes.get(idx).ok_or(anyhow!("Invalid root node"))?;
-        let mut curr_weights = children_weight(&nodes, root).expect("");
-        let mut curr_tuple = are_my_children_balanced(&curr_weights, 0).expect("");
-        let mut is_balanced = curr_tuple.2;
+/// The name of the program at the bottom of the tower.
+fn root_name(tower: &Tower) -> Result<Answer> {
+    let node = tower.nodes.get(tower.root).ok_or(anyhow!("Not a good index"))?;
+    Ok(node.name.clone().into())
+}
 
-        while !is_balanced {
-            let node = nodes.get(curr_tuple.0).ok_or("").expect("");
-            curr_weights = children_weight(&nodes, node).expect("");
-            curr_tuple = are_my_children_balanced(&curr_weights, curr_tuple.1).expect("");
-            is_balanced = curr_tuple.2
-        }
-        Ok(curr_tuple.1)
-    } else {
-        let idx = find_root(&nodes)?;
-        let node = nodes.get(idx).ok_or(anyhow!("Not a good index"))?;
-        writeln!(io::stdout(), "Node {}: {}", node.id, node.name)?;
-        Ok(0)
+/// The weight the single unbalanced program would need to balance the tower.
+fn corrected_weight(tower: &mut Tower) -> Result<Answer> {
+    assign_children(&mut tower.nodes, &tower.children)?;
+    let nodes = &tower.nodes;
+    let root = nodes.get(tower.root).ok_or(anyhow!("Invalid root node"))?;
+    let mut curr_weights = children_weight(nodes, root)?;
+    let mut curr_tuple = are_my_children_balanced(&curr_weights, 0)?;
+    let mut is_balanced = curr_tuple.2;
+
+    while !is_balanced {
+        let node = nodes.get(curr_tuple.0).ok_or(anyhow!("Cannot find unbalanced node {}", curr_tuple.0))?;
+        curr_weights = children_weight(nodes, node)?;
+        curr_tuple = are_my_children_balanced(&curr_weights, curr_tuple.1)?;
+        is_balanced = curr_tuple.2
     }
+    Ok(curr_tuple.1.into())
 }
 
 /// Parse a node description line, and add the nodes and children to the appropriate structures.
 fn parse_line(line: &str, id: usize, nodes: &mut Vec<Node>, children: &mut HashMap<usize, Vec<String>>) -> Result<()> {
-    let node_def: Vec<&str> = line.split(" -> ").collect();
-
-    let node_desc = node_def.first().ok_or(anyhow!("Unable to get node description"))?;
-    let desc: Vec<&str> = node_desc.split(' ').collect();
-    let name = desc.first().ok_or(anyhow!("Unable to deternmine node name"))?;
-    let weight_str = desc.get(1).ok_or(anyhow!("Unable to determine node weight"))?;
-    let weight = weight_str.trim_matches(|c| c == '(' || c == ')').parse::<u32>()?;
-
-    if let Some(children_desc) = node_def.get(1) {
+    let error = |token: &str, expected: &str| ParseError::new(AoCYear::AOC2017, AoCDay::AOCD07, id, line, token, expected);
+    let (node_desc, children_desc) = match line.split_once(" -> ") {
+        Some((node_desc, children_desc)) => (node_desc, Some(children_desc)),
+        None => (line, None),
+    };
+
+    let mut desc = node_desc.split(' ');
+    let name = desc.next().filter(|name| !name.is_empty()).ok_or_else(|| error(node_desc, "a program name"))?;
+    let weight_str = desc.next().unwrap_or(&node_desc[node_desc.len()..]);
+    let weight = weight_str
+        .strip_prefix('(')
+        .and_then(|weight| weight.strip_suffix(')'))
+        .and_then(|weight| weight.parse::<u32>().ok())
+        .ok_or_else(|| error(weight_str, "a weight like `(42)`"))?;
+
+    if let Some(children_desc) = children_desc {
         let children_vec: Vec<String> = children_desc.split(", ").map(String::from).collect();
         children.insert(id, children_vec);
     }
 
     nodes.push(Node {
         id,
-        name: String::from(*name),
+        name: String::from(name),
         weight,
         parent: None,
         children: None,
@@ -164,7 +233,7 @@ fn children_weight(nodes: &[Node], start_node: &Node) -> Result<Vec<(usize, u32)
 
 /// Check weights
 fn are_my_children_balanced(outer: &[(usize, u32)], diff: u32) -> Result<(usize, u32, bool)> {
-    let len = outer.len() - 1;
+    let len = outer.len().checked_sub(1).ok_or(anyhow!("No weights to balance"))?;
     let mut inner = vec![(0, 0); outer.len()];
     inner.copy_from_slice(outer);
 
@@ -185,24 +254,19 @@ fn are_my_children_balanced(outer: &[(usize, u32)], diff: u32) -> Result<(usize,
             }
         }
     }
-    Ok((outer[len].0, outer[len].1 - diff, true))
+    let (id, weight) = outer[len];
+    let corrected = weight.checked_sub(diff).ok_or(anyhow!(
+        "Program {id} weighs {weight}, too little to shed the {diff} that would balance the tower"
+    ))?;
+    Ok((id, corrected, true))
 }
 
 #[cfg(test)]
 fn setup_tree(nodes: &mut Vec<Node>, children: &mut HashMap<usize, Vec<String>>) -> Result<()> {
-    parse_line("pbga (66)", 0, nodes, children)?;
-    parse_line("xhth (57)", 1, nodes, children)?;
-    parse_line("ebii (61)", 2, nodes, children)?;
-    parse_line("havc (66)", 3, nodes, children)?;
-    parse_line("ktlj (57)", 4, nodes, children)?;
-    parse_line("fwft (72) -> ktlj, cntj, xhth", 5, nodes, children)?;
-    parse_line("qoyq (66)", 6, nodes, children)?;
-    parse_line("padx (45) -> pbga, havc, qoyq", 7, nodes, children)?;
-    parse_line("tknk (41) -> ugml, padx, fwft", 8, nodes, children)?;
-    parse_line("jptl (61)", 9, nodes, children)?;
-    parse_line("ugml (68) -> gyxo, ebii, jptl", 10, nodes, children)?;
-    parse_line("gyxo (61)", 11, nodes, children)?;
-    parse_line("cntj (57)", 12, nodes, children)?;
+    let input = crate::examples::input(AoCYear::AOC2017, AoCDay::AOCD07, "1")?;
+    for (id, line) in input.lines().enumerate() {
+        parse_line(line, id, nodes, children)?;
+    }
     assign_parents(nodes, children)?;
     Ok(())
 }
@@ -254,4 +318,20 @@ mod two_star {
 
         assert_eq!(curr_tuple.1, 60);
     }
+
+    #[test]
+    fn both() -> anyhow::Result<()> {
+        use crate::answer::Answer;
+        use crate::constants::{AoCDay, AoCYear};
+        use crate::context::Context;
+        use crate::examples;
+        use crate::solver::Solver;
+        use std::io::Cursor;
+
+        let input = examples::input(AoCYear::AOC2017, AoCDay::AOCD07, "1")?;
+        let (first, second) = super::Day07.both(&mut Cursor::new(input), &Context::default())?;
+        assert_eq!(first.answer().as_ref().ok(), Some(&Answer::from("tknk")));
+        assert_eq!(second.answer().as_ref().ok(), Some(&Answer::Unsigned(60)));
+        Ok(())
+    }
 }
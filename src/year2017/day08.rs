@@ -1,8 +1,37 @@
 //! Advent of Code - Day 8 "I Heard You Like Registers" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::{self, ParseError};
+use crate::solver::Solver;
 use anyhow::{Error, Result, anyhow};
 use std::collections::HashMap;
 use std::io::BufRead;
 
+/// Day 8 "I Heard You Like Registers"
+pub(crate) struct Day08;
+
+impl Solver for Day08 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD08
+    }
+
+    fn title(&self) -> &'static str {
+        "I Heard You Like Registers"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Register commands
 enum Command {
     /// Increment
@@ -76,30 +105,30 @@ struct RegisterCommand {
 }
 
 /// Calculate the largest value in a register.
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let mut register_map = HashMap::new();
     let mut commands = Vec::new();
 
-    for line_result in reader.lines() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
-        generate_register_map_entry_and_command(line, &mut register_map, &mut commands)?;
+    for (idx, line_result) in reader.lines().enumerate() {
+        let line = &line_result?;
+        generate_register_map_entry_and_command(idx, line, &mut register_map, &mut commands)?;
     }
 
     if second_star {
         let mut maximum_attained = i32::MIN;
 
         for command in &commands {
-            if check_condition(&register_map, &command.condition).expect("") {
-                execute_command(&mut register_map, command).expect("");
+            if check_condition(&register_map, &command.condition)? {
+                execute_command(&mut register_map, command)?;
             }
-            let max = register_map.values().max().ok_or("No max found").expect("");
+            let max = register_map.values().max().ok_or(anyhow!("No max found"))?;
 
             if *max > maximum_attained {
                 maximum_attained = *max;
             }
         }
 
-        Ok(TryFrom::try_from(maximum_attained)?)
+        Ok(maximum_attained.into())
     } else {
         for command in &commands {
             if check_condition(&register_map, &command.condition)? {
@@ -108,31 +137,47 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
         }
 
         let max = register_map.values().max().ok_or(anyhow!("No max found"))?;
-        Ok(TryFrom::try_from(*max)?)
+        Ok((*max).into())
     }
 }
 
 /// Generate a register map entry and the associated command
-fn generate_register_map_entry_and_command(line: &str, register_map: &mut HashMap<String, i32>, commands: &mut Vec<RegisterCommand>) -> Result<()> {
-    let line_desc: Vec<&str> = line.split_whitespace().collect();
-    let name_str = line_desc.first().ok_or(anyhow!("Invalid register name!"))?;
-    let name = String::from(*name_str);
-    let command_str = line_desc.get(1).ok_or(anyhow!("Invalid command!"))?;
-    let value = line_desc.get(2).ok_or(anyhow!("Invalid command value"))?.parse::<i32>()?;
-    let command_register_str = line_desc.get(4).ok_or(anyhow!("Invalid command register!"))?;
-    let command_register = String::from(*command_register_str);
-    let operator_str = line_desc.get(5).ok_or(anyhow!("Invalid operator!"))?;
-    let condition_value = line_desc.get(6).ok_or(anyhow!("Invalid condition value!"))?.parse::<i32>()?;
+fn generate_register_map_entry_and_command(idx: usize, line: &str, register_map: &mut HashMap<String, i32>, commands: &mut Vec<RegisterCommand>) -> Result<()> {
+    let error = |token: &str, expected: &str| ParseError::new(AoCYear::AOC2017, AoCDay::AOCD08, idx, line, token, expected);
+    let number = |token: &str| parse::number::<i32>(AoCYear::AOC2017, AoCDay::AOCD08, idx, line, token);
+    let register = |token: &str| {
+        if token.is_empty() {
+            Err(error(token, "a register name"))
+        } else {
+            Ok(String::from(token))
+        }
+    };
+    let mut tokens = line.split_whitespace();
+    let mut next = || tokens.next().unwrap_or(&line[line.len()..]);
+
+    let name = register(next())?;
+    let command_str = next();
+    let command = Command::try_from(command_str).map_err(|_| error(command_str, "`inc` or `dec`"))?;
+    let value = number(next())?;
+    let if_str = next();
+    if if_str != "if" {
+        return Err(error(if_str, "`if`").into());
+    }
+    let command_register = register(next())?;
+    let operator_str = next();
+    let op = Operator::try_from(operator_str).map_err(|_| error(operator_str, "a comparison like `>` or `!=`"))?;
+    let condition_value = number(next())?;
 
     register_map.entry(name.clone()).or_insert(0);
+    register_map.entry(command_register.clone()).or_insert(0);
 
     commands.push(RegisterCommand {
         register: name,
-        command: TryFrom::try_from(*command_str)?,
+        command,
         value,
         condition: Condition {
             register: command_register,
-            op: TryFrom::try_from(*operator_str)?,
+            op,
             value: condition_value,
         },
     });
@@ -169,10 +214,10 @@ fn execute_command(register_map: &mut HashMap<String, i32>, register_command: &R
 
 #[cfg(test)]
 fn generate_map_entry(register_map: &mut HashMap<String, i32>, commands: &mut Vec<RegisterCommand>) -> Result<()> {
-    generate_register_map_entry_and_command("b inc 5 if a > 1", register_map, commands)?;
-    generate_register_map_entry_and_command("a inc 1 if b < 5", register_map, commands)?;
-    generate_register_map_entry_and_command("c dec -10 if a >= 1", register_map, commands)?;
-    generate_register_map_entry_and_command("c inc -20 if c == 10", register_map, commands)?;
+    generate_register_map_entry_and_command(0, "b inc 5 if a > 1", register_map, commands)?;
+    generate_register_map_entry_and_command(0, "a inc 1 if b < 5", register_map, commands)?;
+    generate_register_map_entry_and_command(0, "c dec -10 if a >= 1", register_map, commands)?;
+    generate_register_map_entry_and_command(0, "c inc -20 if c == 10", register_map, commands)?;
     Ok(())
 }
 
@@ -1,27 +1,56 @@
 //! Advent of Code - Day 9 "Stream Processing" Solution
-use anyhow::{Result, anyhow};
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::ParseError;
+use crate::solver::Solver;
+use anyhow::Result;
 use std::io::BufRead;
 
+/// Day 9 "Stream Processing"
+pub(crate) struct Day09;
+
+impl Solver for Day09 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD09
+    }
+
+    fn title(&self) -> &'static str {
+        "Stream Processing"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Calculate the largest value in a register.
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let mut score = (0, 0);
-    for line_result in reader.lines() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
-        score = process_line_as_chars(line)?;
+    for (idx, line_result) in reader.lines().enumerate() {
+        let line = &line_result?;
+        score = process_line_as_chars(idx, line)?;
     }
 
-    if second_star { Ok(score.1) } else { Ok(score.0) }
+    if second_star { Ok(score.1.into()) } else { Ok(score.0.into()) }
 }
 
 /// Process a line as a stream of chars.
-fn process_line_as_chars(line: &str) -> Result<(u32, u32)> {
+fn process_line_as_chars(idx: usize, line: &str) -> Result<(u32, u32)> {
     let mut scores: Vec<u32> = Vec::new();
     let mut current_nesting = 0;
     let mut garbage_count = 0;
     let mut in_garbage = false;
     let mut skip_next = false;
 
-    for c in line.chars() {
+    for (col, c) in line.char_indices() {
         if skip_next {
             skip_next = false;
             continue;
@@ -30,7 +59,7 @@ fn process_line_as_chars(line: &str) -> Result<(u32, u32)> {
             '{' if !in_garbage => {
                 current_nesting += 1;
             }
-            '}' if !in_garbage => {
+            '}' if !in_garbage && current_nesting > 0 => {
                 scores.push(current_nesting);
                 current_nesting -= 1;
             }
@@ -50,7 +79,10 @@ fn process_line_as_chars(line: &str) -> Result<(u32, u32)> {
                 garbage_count += 1;
                 continue;
             }
-            _ => return Err(anyhow!("Unknown character encountered!")),
+            _ => {
+                let token = &line[col..col + c.len_utf8()];
+                return Err(ParseError::new(AoCYear::AOC2017, AoCDay::AOCD09, idx, line, token, "a group or garbage").into());
+            }
         }
     }
     Ok((scores.iter().sum(), garbage_count))
@@ -62,14 +94,14 @@ mod one_star {
 
     #[test]
     fn solution() {
-        assert_eq!(process_line_as_chars("{}").unwrap_or((0, 0)).0, 1);
-        assert_eq!(process_line_as_chars("{{{}}}").unwrap_or((0, 0)).0, 6);
-        assert_eq!(process_line_as_chars("{{},{}}").unwrap_or((0, 0)).0, 5);
-        assert_eq!(process_line_as_chars("{{{},{},{{}}}}").unwrap_or((0, 0)).0, 16);
-        assert_eq!(process_line_as_chars("{<a>,<a>,<a>,<a>}").unwrap_or((0, 0)).0, 1);
-        assert_eq!(process_line_as_chars("{{<ab>},{<ab>},{<ab>},{<ab>}}").unwrap_or((0, 0)).0, 9);
-        assert_eq!(process_line_as_chars("{{<!!>},{<!!>},{<!!>},{<!!>}}").unwrap_or((0, 0)).0, 9);
-        assert_eq!(process_line_as_chars("{{<a!>},{<a!>},{<a!>},{<ab>}}").unwrap_or((0, 0)).0, 3)
+        assert_eq!(process_line_as_chars(0, "{}").unwrap_or((0, 0)).0, 1);
+        assert_eq!(process_line_as_chars(0, "{{{}}}").unwrap_or((0, 0)).0, 6);
+        assert_eq!(process_line_as_chars(0, "{{},{}}").unwrap_or((0, 0)).0, 5);
+        assert_eq!(process_line_as_chars(0, "{{{},{},{{}}}}").unwrap_or((0, 0)).0, 16);
+        assert_eq!(process_line_as_chars(0, "{<a>,<a>,<a>,<a>}").unwrap_or((0, 0)).0, 1);
+        assert_eq!(process_line_as_chars(0, "{{<ab>},{<ab>},{<ab>},{<ab>}}").unwrap_or((0, 0)).0, 9);
+        assert_eq!(process_line_as_chars(0, "{{<!!>},{<!!>},{<!!>},{<!!>}}").unwrap_or((0, 0)).0, 9);
+        assert_eq!(process_line_as_chars(0, "{{<a!>},{<a!>},{<a!>},{<ab>}}").unwrap_or((0, 0)).0, 3)
     }
 }
 
@@ -79,12 +111,12 @@ mod two_star {
 
     #[test]
     fn solution() {
-        assert_eq!(process_line_as_chars("<>").unwrap_or((0, 1)).1, 0);
-        assert_eq!(process_line_as_chars("<random characters>").unwrap_or((0, 0)).1, 17);
-        assert_eq!(process_line_as_chars("<<<<>").unwrap_or((0, 0)).1, 3);
-        assert_eq!(process_line_as_chars("<{!>}>").unwrap_or((0, 0)).1, 2);
-        assert_eq!(process_line_as_chars("<!!>").unwrap_or((0, 0)).1, 0);
-        assert_eq!(process_line_as_chars("<!!!>>").unwrap_or((0, 0)).1, 0);
-        assert_eq!(process_line_as_chars("<{o\"i!a,<{i<a>").unwrap_or((0, 0)).1, 10);
+        assert_eq!(process_line_as_chars(0, "<>").unwrap_or((0, 1)).1, 0);
+        assert_eq!(process_line_as_chars(0, "<random characters>").unwrap_or((0, 0)).1, 17);
+        assert_eq!(process_line_as_chars(0, "<<<<>").unwrap_or((0, 0)).1, 3);
+        assert_eq!(process_line_as_chars(0, "<{!>}>").unwrap_or((0, 0)).1, 2);
+        assert_eq!(process_line_as_chars(0, "<!!>").unwrap_or((0, 0)).1, 0);
+        assert_eq!(process_line_as_chars(0, "<!!!>>").unwrap_or((0, 0)).1, 0);
+        assert_eq!(process_line_as_chars(0, "<{o\"i!a,<{i<a>").unwrap_or((0, 0)).1, 10);
     }
 }
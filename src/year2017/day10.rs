@@ -1,28 +1,56 @@
 //! Advent of Code - Day 10 "Knot Hash" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse;
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
 use std::io::BufRead;
 
+/// Day 10 "Knot Hash"
+pub(crate) struct Day10;
+
+impl Solver for Day10 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD10
+    }
+
+    fn title(&self) -> &'static str {
+        "Knot Hash"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Find the solution for Advent of Code 2017
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    use std::io::{self, Write};
-    let mut result = 0;
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
+    let mut result = Answer::Unsigned(0);
     let mut hash = Vec::new();
-    for line_result in reader.lines() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
-        parse_list_and_hash(&mut hash, line, 256, second_star)?;
+    for (idx, line_result) in reader.lines().enumerate() {
+        let line = &line_result?;
+        parse_list_and_hash(&mut hash, idx, line, 256, second_star)?;
 
         if second_star {
-            writeln!(io::stdout(), "{}", squash_and_hex(&hash)?)?;
+            result = squash_and_hex(&hash)?.into();
         } else {
-            result = hash[0] * hash[1];
+            result = (hash[0] * hash[1]).into();
         }
     }
     Ok(result)
 }
 
 /// Parse the list of lengths and calculate the hash.
-fn parse_list_and_hash(hash: &mut Vec<u32>, line: &str, num_elements: u32, second_star: bool) -> Result<()> {
-    let lengths = generate_lengths(line, second_star)?;
+fn parse_list_and_hash(hash: &mut Vec<u32>, idx: usize, line: &str, num_elements: u32, second_star: bool) -> Result<()> {
+    let lengths = generate_lengths(idx, line, second_star)?;
 
     for i in 0..num_elements {
         hash.push(i);
@@ -57,7 +85,7 @@ fn parse_list_and_hash(hash: &mut Vec<u32>, line: &str, num_elements: u32, secon
 }
 
 /// Generate the list of lengths.
-fn generate_lengths(line: &str, second_star: bool) -> Result<Vec<u8>> {
+fn generate_lengths(idx: usize, line: &str, second_star: bool) -> Result<Vec<u8>> {
     let mut lengths = Vec::new();
 
     if second_star {
@@ -66,9 +94,8 @@ fn generate_lengths(line: &str, second_star: bool) -> Result<Vec<u8>> {
         }
         lengths.extend(vec![17, 31, 73, 47, 23]);
     } else {
-        let length_strs: Vec<&str> = line.split(',').collect();
-        for length_str in length_strs {
-            lengths.push(length_str.parse::<u8>()?);
+        for length_str in line.split(',') {
+            lengths.push(parse::number(AoCYear::AOC2017, AoCDay::AOCD10, idx, line, length_str.trim())?);
         }
     }
 
@@ -99,7 +126,7 @@ mod one_star {
     #[test]
     fn solution() {
         let mut hash = Vec::new();
-        super::parse_list_and_hash(&mut hash, "3,4,1,5", 5, false).expect("");
+        super::parse_list_and_hash(&mut hash, 0, "3,4,1,5", 5, false).expect("");
         assert_eq!(hash[0] * hash[1], 12);
     }
 }
@@ -109,16 +136,16 @@ mod two_star {
     #[test]
     fn solution() {
         let mut hash = Vec::new();
-        super::parse_list_and_hash(&mut hash, "", 256, true).expect("");
+        super::parse_list_and_hash(&mut hash, 0, "", 256, true).expect("");
         assert_eq!(super::squash_and_hex(&hash).expect(""), "a2582a3a0e66e6e86e3812dcb672a272");
         hash.clear();
-        super::parse_list_and_hash(&mut hash, "AoC 2017", 256, true).expect("");
+        super::parse_list_and_hash(&mut hash, 0, "AoC 2017", 256, true).expect("");
         assert_eq!(super::squash_and_hex(&hash).expect(""), "33efeb34ea91902bb2f59c9920caa6cd");
         hash.clear();
-        super::parse_list_and_hash(&mut hash, "1,2,3", 256, true).expect("");
+        super::parse_list_and_hash(&mut hash, 0, "1,2,3", 256, true).expect("");
         assert_eq!(super::squash_and_hex(&hash).expect(""), "3efbe78a8d82f29979031a4aa0b16a9d");
         hash.clear();
-        super::parse_list_and_hash(&mut hash, "1,2,4", 256, true).expect("");
+        super::parse_list_and_hash(&mut hash, 0, "1,2,4", 256, true).expect("");
         assert_eq!(super::squash_and_hex(&hash).expect(""), "63960835bcdc130f0b66d7ff4f6a5a8e");
     }
 }
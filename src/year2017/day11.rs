@@ -1,25 +1,54 @@
 //! Advent of Code - Day 11 "Hex Ed" Solution
-use anyhow::{Result, anyhow};
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::ParseError;
+use crate::solver::Solver;
+use anyhow::Result;
 use std::io::BufRead;
 
+/// Day 11 "Hex Ed"
+pub(crate) struct Day11;
+
+impl Solver for Day11 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD11
+    }
+
+    fn title(&self) -> &'static str {
+        "Hex Ed"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Find the solution for Advent of Code 2017
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let mut result = 0;
-    for line_result in reader.lines() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
-        result = parse_and_go(line, second_star)?;
+    for (idx, line_result) in reader.lines().enumerate() {
+        let line = &line_result?;
+        result = parse_and_go(idx, line, second_star)?;
     }
-    Ok(result)
+    Ok(result.into())
 }
 
 /// Parse the input and go.
-fn parse_and_go(line: &str, second_star: bool) -> Result<u32> {
-    let steps: Vec<&str> = line.split(',').collect();
+fn parse_and_go(idx: usize, line: &str, second_star: bool) -> Result<u32> {
     let mut coords = (0, 0, 0);
     let mut max_distance = 0;
 
-    for step in steps {
-        move_in_direction(step, &mut coords)?;
+    for step in line.split(',') {
+        move_in_direction(step, &mut coords)
+            .ok_or_else(|| ParseError::new(AoCYear::AOC2017, AoCDay::AOCD11, idx, line, step, "a direction like `n` or `se`"))?;
         let curr_distance = manhattan_distance_from_origin(coords)?;
 
         if curr_distance > max_distance {
@@ -40,8 +69,8 @@ fn manhattan_distance_from_origin(coords: (i32, i32, i32)) -> Result<u32> {
     Ok(TryFrom::try_from(distance)?)
 }
 
-/// Adjust the coordinates given a movement command.
-fn move_in_direction(direction: &str, coords: &mut (i32, i32, i32)) -> Result<()> {
+/// Adjust the coordinates given a movement command, or `None` if there is no such direction.
+fn move_in_direction(direction: &str, coords: &mut (i32, i32, i32)) -> Option<()> {
     match direction {
         "n" => {
             coords.1 += 1;
@@ -67,19 +96,19 @@ fn move_in_direction(direction: &str, coords: &mut (i32, i32, i32)) -> Result<()
             coords.0 -= 1;
             coords.1 += 1;
         }
-        _ => return Err(anyhow!("Invalid movement direction")),
+        _ => return None,
     }
-    Ok(())
+    Some(())
 }
 
 #[cfg(test)]
 mod one_star {
     #[test]
     fn solution() {
-        assert_eq!(super::parse_and_go("ne,ne,ne", false).unwrap_or(0), 3);
-        assert_eq!(super::parse_and_go("ne,ne,sw,sw", false).unwrap_or(1), 0);
-        assert_eq!(super::parse_and_go("ne,ne,s,s", false).unwrap_or(0), 2);
-        assert_eq!(super::parse_and_go("se,sw,se,sw,sw", false).unwrap_or(0), 3);
+        assert_eq!(super::parse_and_go(0, "ne,ne,ne", false).unwrap_or(0), 3);
+        assert_eq!(super::parse_and_go(0, "ne,ne,sw,sw", false).unwrap_or(1), 0);
+        assert_eq!(super::parse_and_go(0, "ne,ne,s,s", false).unwrap_or(0), 2);
+        assert_eq!(super::parse_and_go(0, "se,sw,se,sw,sw", false).unwrap_or(0), 3);
     }
 }
 
@@ -87,9 +116,9 @@ mod one_star {
 mod two_star {
     #[test]
     fn solution() {
-        assert_eq!(super::parse_and_go("ne,ne,ne", true).unwrap_or(0), 3);
-        assert_eq!(super::parse_and_go("ne,ne,sw,sw", true).unwrap_or(0), 2);
-        assert_eq!(super::parse_and_go("ne,ne,s,s", true).unwrap_or(0), 2);
-        assert_eq!(super::parse_and_go("se,sw,se,sw,sw", true).unwrap_or(0), 3);
+        assert_eq!(super::parse_and_go(0, "ne,ne,ne", true).unwrap_or(0), 3);
+        assert_eq!(super::parse_and_go(0, "ne,ne,sw,sw", true).unwrap_or(0), 2);
+        assert_eq!(super::parse_and_go(0, "ne,ne,s,s", true).unwrap_or(0), 2);
+        assert_eq!(super::parse_and_go(0, "se,sw,se,sw,sw", true).unwrap_or(0), 3);
     }
 }
@@ -1,17 +1,45 @@
 //! Advent of Code - Day 12 "Digital Plumber" Solution
 
-use crate::utils::PrivateTryFromUsize;
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::{self, ParseError};
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
 use std::collections::{HashMap, HashSet};
 use std::io::BufRead;
 
+/// Day 12 "Digital Plumber"
+pub(crate) struct Day12;
+
+impl Solver for Day12 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD12
+    }
+
+    fn title(&self) -> &'static str {
+        "Digital Plumber"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Find the solution for Advent of Code 2017
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let mut group_map = HashMap::new();
 
-    for line_result in reader.lines() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
-        parse_and_add(line, &mut group_map)?;
+    for (idx, line_result) in reader.lines().enumerate() {
+        let line = &line_result?;
+        parse_and_add(idx, line, &mut group_map)?;
     }
 
     if second_star {
@@ -22,24 +50,25 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
             add_to_groups(*k, &group_map_clone, &mut groups)?;
         }
 
-        Ok(u32::private_try_from(groups.len())?)
+        Ok(groups.len().into())
     } else {
         let mut group_zero = HashSet::new();
         get_and_add(0, &group_map, &mut group_zero)?;
-        Ok(u32::private_try_from(group_zero.len())?)
+        Ok(group_zero.len().into())
     }
 }
 
 /// Parse the line and add to group map.
-fn parse_and_add(line: &str, group_map: &mut HashMap<u32, Vec<u32>>) -> Result<()> {
-    let piped: Vec<&str> = line.split(" <-> ").collect();
-    let group_str = piped.first().ok_or(anyhow!("Invalid group"))?;
-    let group = group_str.parse::<u32>()?;
-    let piped_to_strs: Vec<&str> = piped.get(1).ok_or(anyhow!("Invalid pipes"))?.split(", ").collect();
+fn parse_and_add(idx: usize, line: &str, group_map: &mut HashMap<u32, Vec<u32>>) -> Result<()> {
+    let number = |token: &str| parse::number(AoCYear::AOC2017, AoCDay::AOCD12, idx, line, token);
+    let (group_str, piped_to_strs) = line
+        .split_once(" <-> ")
+        .ok_or_else(|| ParseError::whole_line(AoCYear::AOC2017, AoCDay::AOCD12, idx, line, "pipes like `2 <-> 0, 3, 4`"))?;
+    let group = number(group_str)?;
     let mut piped_to_vec = Vec::new();
 
-    for piped_to_str in piped_to_strs {
-        piped_to_vec.push(piped_to_str.parse::<u32>()?);
+    for piped_to_str in piped_to_strs.split(", ") {
+        piped_to_vec.push(number(piped_to_str)?);
     }
 
     group_map.insert(group, piped_to_vec);
@@ -88,13 +117,13 @@ mod one_star {
     fn solution() {
         let mut group_map = HashMap::new();
         let mut group_zero = HashSet::new();
-        super::parse_and_add("0 <-> 2", &mut group_map).expect("");
-        super::parse_and_add("1 <-> 1", &mut group_map).expect("");
-        super::parse_and_add("2 <-> 0, 3, 4", &mut group_map).expect("");
-        super::parse_and_add("3 <-> 2, 4", &mut group_map).expect("");
-        super::parse_and_add("4 <-> 2, 3, 6", &mut group_map).expect("");
-        super::parse_and_add("5 <-> 6", &mut group_map).expect("");
-        super::parse_and_add("6 <-> 4, 5", &mut group_map).expect("");
+        super::parse_and_add(0, "0 <-> 2", &mut group_map).expect("");
+        super::parse_and_add(0, "1 <-> 1", &mut group_map).expect("");
+        super::parse_and_add(0, "2 <-> 0, 3, 4", &mut group_map).expect("");
+        super::parse_and_add(0, "3 <-> 2, 4", &mut group_map).expect("");
+        super::parse_and_add(0, "4 <-> 2, 3, 6", &mut group_map).expect("");
+        super::parse_and_add(0, "5 <-> 6", &mut group_map).expect("");
+        super::parse_and_add(0, "6 <-> 4, 5", &mut group_map).expect("");
         super::get_and_add(0, &group_map, &mut group_zero).expect("");
 
         assert_eq!(group_zero.len(), 6);
@@ -108,13 +137,13 @@ mod two_star {
     #[test]
     fn solution() {
         let mut group_map = HashMap::new();
-        super::parse_and_add("0 <-> 2", &mut group_map).expect("");
-        super::parse_and_add("1 <-> 1", &mut group_map).expect("");
-        super::parse_and_add("2 <-> 0, 3, 4", &mut group_map).expect("");
-        super::parse_and_add("3 <-> 2, 4", &mut group_map).expect("");
-        super::parse_and_add("4 <-> 2, 3, 6", &mut group_map).expect("");
-        super::parse_and_add("5 <-> 6", &mut group_map).expect("");
-        super::parse_and_add("6 <-> 4, 5", &mut group_map).expect("");
+        super::parse_and_add(0, "0 <-> 2", &mut group_map).expect("");
+        super::parse_and_add(0, "1 <-> 1", &mut group_map).expect("");
+        super::parse_and_add(0, "2 <-> 0, 3, 4", &mut group_map).expect("");
+        super::parse_and_add(0, "3 <-> 2, 4", &mut group_map).expect("");
+        super::parse_and_add(0, "4 <-> 2, 3, 6", &mut group_map).expect("");
+        super::parse_and_add(0, "5 <-> 6", &mut group_map).expect("");
+        super::parse_and_add(0, "6 <-> 4, 5", &mut group_map).expect("");
 
         let mut groups: Vec<HashSet<u32>> = Vec::new();
         let group_map_clone = group_map.clone();
@@ -1,16 +1,45 @@
 //! Advent of Code - Day 13 "Packet Scanners" Solution
 
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::{self, ParseError};
+use crate::solver::Solver;
 use crate::utils::PrivateTryFromUsize;
 use anyhow::{Result, anyhow};
 use std::collections::HashMap;
 use std::io::BufRead;
 
+/// Day 13 "Packet Scanners"
+pub(crate) struct Day13;
+
+impl Solver for Day13 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD13
+    }
+
+    fn title(&self) -> &'static str {
+        "Packet Scanners"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Find the solution for Advent of Code 2017
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let mut layer_map = HashMap::new();
-    for line_result in reader.lines() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
-        add_layer_to_map(line, &mut layer_map)?;
+    for (idx, line_result) in reader.lines().enumerate() {
+        let line = &line_result?;
+        add_layer_to_map(idx, line, &mut layer_map)?;
     }
 
     let mut layers: HashMap<usize, Option<u32>> = HashMap::new();
@@ -32,14 +61,19 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
         result = traverse_firewall(&layers, 0, false)?;
     }
 
-    Ok(result)
+    Ok(result.into())
 }
 
 /// Add a layer to the layer map.
-fn add_layer_to_map(line: &str, layer_map: &mut HashMap<usize, u32>) -> Result<()> {
-    let layer_desc_vec: Vec<&str> = line.split(": ").collect();
-    let layer = layer_desc_vec.first().ok_or(anyhow!("Invalid layer number"))?.parse::<usize>()?;
-    let depth = layer_desc_vec.get(1).ok_or(anyhow!("Invalid depty number"))?.parse::<u32>()?;
+fn add_layer_to_map(idx: usize, line: &str, layer_map: &mut HashMap<usize, u32>) -> Result<()> {
+    let (layer_str, depth_str) = line
+        .split_once(": ")
+        .ok_or_else(|| ParseError::whole_line(AoCYear::AOC2017, AoCDay::AOCD13, idx, line, "a layer like `4: 2`"))?;
+    let layer = parse::number(AoCYear::AOC2017, AoCDay::AOCD13, idx, line, layer_str)?;
+    let depth = parse::number(AoCYear::AOC2017, AoCDay::AOCD13, idx, line, depth_str)
+        .ok()
+        .filter(|depth| *depth > 0)
+        .ok_or_else(|| ParseError::new(AoCYear::AOC2017, AoCDay::AOCD13, idx, line, depth_str, "a depth of at least 1"))?;
 
     layer_map.insert(layer, depth);
 
@@ -76,7 +110,7 @@ fn traverse_firewall(layers: &HashMap<usize, Option<u32>>, delay: u32, second_st
         // caught at this level, so skip.
         if let Some(max_depth) = *curr_layer {
             let current_picosecond = u32::private_try_from(i)?;
-            let scan_length = (max_depth - 1) * 2;
+            let scan_length = ((max_depth - 1) * 2).max(1);
 
             // Each scanner loops back to the 0 level at `((max_depth - 1) * 2)` picoseconds.
             // This means that if `current_picosecond % ((max_depth - 1) * 2) == 0`, then the packet
@@ -101,10 +135,10 @@ mod one_star {
     #[test]
     fn solution() {
         let mut layer_map = HashMap::new();
-        super::add_layer_to_map("0: 3", &mut layer_map).expect("");
-        super::add_layer_to_map("1: 2", &mut layer_map).expect("");
-        super::add_layer_to_map("4: 4", &mut layer_map).expect("");
-        super::add_layer_to_map("6: 4", &mut layer_map).expect("");
+        super::add_layer_to_map(0, "0: 3", &mut layer_map).expect("");
+        super::add_layer_to_map(0, "1: 2", &mut layer_map).expect("");
+        super::add_layer_to_map(0, "4: 4", &mut layer_map).expect("");
+        super::add_layer_to_map(0, "6: 4", &mut layer_map).expect("");
         assert_eq!(layer_map.len(), 4);
         let mut layers: HashMap<usize, Option<u32>> = HashMap::new();
         let maximum_layer = super::find_maximum_layer(&layer_map).expect("");
@@ -130,10 +164,10 @@ mod two_star {
     #[test]
     fn solution() {
         let mut layer_map = HashMap::new();
-        super::add_layer_to_map("0: 3", &mut layer_map).expect("");
-        super::add_layer_to_map("1: 2", &mut layer_map).expect("");
-        super::add_layer_to_map("4: 4", &mut layer_map).expect("");
-        super::add_layer_to_map("6: 4", &mut layer_map).expect("");
+        super::add_layer_to_map(0, "0: 3", &mut layer_map).expect("");
+        super::add_layer_to_map(0, "1: 2", &mut layer_map).expect("");
+        super::add_layer_to_map(0, "4: 4", &mut layer_map).expect("");
+        super::add_layer_to_map(0, "6: 4", &mut layer_map).expect("");
         assert_eq!(layer_map.len(), 4);
         let mut layers: HashMap<usize, Option<u32>> = HashMap::new();
         let maximum_layer = super::find_maximum_layer(&layer_map).expect("");
@@ -1,15 +1,42 @@
 //! Advent of Code - Day 14 "Disk Defragmentation" Solution
 
-use crate::utils::PrivateTryFromUsize;
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
 use ndarray::Array2;
 use std::io::BufRead;
 
+/// Day 14 "Disk Defragmentation"
+pub(crate) struct Day14;
+
+impl Solver for Day14 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD14
+    }
+
+    fn title(&self) -> &'static str {
+        "Disk Defragmentation"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Find the solution for Advent of Code 2017
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let mut count = 0;
     for line_result in reader.lines() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
+        let line = &line_result?;
         let mut disk_arr: Array2<u32> = Array2::zeros((128, 128));
         let mut visited: Array2<bool> = Array2::from_elem((128, 128), false);
 
@@ -42,7 +69,7 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
             }
         }
     }
-    u32::private_try_from(count)
+    Ok(count.into())
 }
 
 /// Parse the list of lengths and calculate the hash.
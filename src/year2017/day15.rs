@@ -1,21 +1,74 @@
 //! Advent of Code - Day 15 'Dueling Generators' Solution
-use anyhow::Result;
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::parse;
+use crate::solver::{self, Solver};
+use anyhow::{Result, anyhow};
+use std::any::Any;
 use std::io::BufRead;
 
+/// Day 15 "Dueling Generators"
+pub(crate) struct Day15;
+
+impl Solver for Day15 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD15
+    }
+
+    fn title(&self) -> &'static str {
+        "Dueling Generators"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+
+    fn parse(&self, reader: &mut dyn BufRead) -> Result<Option<Box<dyn Any>>> {
+        Ok(Some(Box::new(parse_generators(reader)?)))
+    }
+
+    fn solve_parsed(&self, part: u8, input: &dyn Any, _ctx: &Context) -> Result<Answer> {
+        let &(init_a, init_b) = solver::parsed::<(u64, u64)>(input)?;
+        judge(init_a, init_b, part == 2)
+    }
+}
+
 /// Find the solution for Advent of Code 2017
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
+    let (init_a, init_b) = parse_generators(reader)?;
+    judge(init_a, init_b, second_star)
+}
+
+/// Parse the starting values of generators `A` and `B`.
+fn parse_generators<T: BufRead>(reader: T) -> Result<(u64, u64)> {
     let mut factors: Vec<u64> = Vec::new();
 
-    for line_result in reader.lines() {
+    for (idx, line_result) in reader.lines().enumerate() {
         let line = &line_result?;
-        factors.push(line.parse::<u64>()?);
+        factors.push(parse::number(AoCYear::AOC2017, AoCDay::AOCD15, idx, line, line)?);
     }
+    let [init_a, init_b] = factors[..] else {
+        return Err(anyhow!("expected the starting values of two generators, found {}", factors.len()));
+    };
+    Ok((init_a, init_b))
+}
 
-    Ok(if second_star {
-        check_x_for_matches(factors[0], factors[1], 5_000_000, second_star)?
+/// Count the pairs the judge finds matching.
+fn judge(init_a: u64, init_b: u64, second_star: bool) -> Result<Answer> {
+    Ok(Answer::from(if second_star {
+        check_x_for_matches(init_a, init_b, 5_000_000, second_star)?
     } else {
-        check_x_for_matches(factors[0], factors[1], 40_000_000, second_star)?
-    })
+        check_x_for_matches(init_a, init_b, 40_000_000, second_star)?
+    }))
 }
 
 /// Check `x` results for matches.
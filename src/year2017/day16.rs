@@ -1,8 +1,45 @@
 //! Advent of Code - Day 16 Solution
-use anyhow::{Result, anyhow};
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::parse::{self, ParseError};
+use crate::solver::Solver;
+use anyhow::Result;
 use regex::Regex;
 use std::io::BufRead;
 
+/// Day 16 "Permutation Promenade"
+pub(crate) struct Day16;
+
+impl Solver for Day16 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD16
+    }
+
+    fn title(&self) -> &'static str {
+        "Permutation Promenade"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false, &Context::default())
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true, &Context::default())
+    }
+
+    fn star(&self, part: u8, reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer> {
+        find_solution(reader, part == 2, ctx)
+    }
+}
+
+/// How many programs are dancing.
+const DANCERS: u8 = 16;
+
 /// Various Dance Moves
 enum Move {
     /// Exchange pos1 with pos2
@@ -14,15 +51,14 @@ enum Move {
 }
 
 /// Find the solution for Advent of Code 2017
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    use std::io::{self, Write};
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool, ctx: &Context) -> Result<Answer> {
     let mut moves: Vec<Move> = Vec::new();
     let mut dancers = vec!['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p'];
     let orig = dancers.clone();
 
-    for line_result in reader.lines() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
-        generate_moves(line, &mut moves)?;
+    for (idx, line_result) in reader.lines().enumerate() {
+        let line = &line_result?;
+        generate_moves(idx, line, &mut moves)?;
     }
 
     if second_star {
@@ -30,6 +66,7 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
         // to calculate 1_000_000_000 % repeat to figure this one out.
         let mut repeat = 0;
         for i in 0..1_000_000_000 {
+            ctx.check()?;
             apply_moves(&moves, &mut dancers)?;
             if dancers == orig {
                 repeat = i + 1;
@@ -42,42 +79,37 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
     } else {
         apply_moves(&moves, &mut dancers)?;
     }
-    for i in dancers {
-        write!(io::stdout(), "{i}")?;
-    }
-    writeln!(io::stdout())?;
-    Ok(0)
+    Ok(dancers.into_iter().collect::<String>().into())
 }
 
 /// Generates the moves vector
-fn generate_moves(line: &str, moves: &mut Vec<Move>) -> Result<()> {
-    let tokens: Vec<&str> = line.split(',').collect();
-    let spin_re = Regex::new(r"s(\d{1,2})")?;
-    let exchange_re = Regex::new(r"x(\d{1,2})/(\d{1,2})")?;
-    let partner_re = Regex::new(r"p([a-p])/([a-p])")?;
-
-    for token in tokens {
-        if spin_re.is_match(token) {
-            let caps = spin_re.captures(token).ok_or(anyhow!("invalid spin captures"))?;
-            let val_str = caps.get(1).ok_or(anyhow!("invalid spin value"))?.as_str();
-            let val = val_str.parse::<u32>()?;
+fn generate_moves(idx: usize, line: &str, moves: &mut Vec<Move>) -> Result<()> {
+    let spin_re = Regex::new(r"^s(\d{1,2})$")?;
+    let exchange_re = Regex::new(r"^x(\d{1,2})/(\d{1,2})$")?;
+    let partner_re = Regex::new(r"^p([a-p])/([a-p])$")?;
+    let error = |token: &str, expected: &str| ParseError::new(AoCYear::AOC2017, AoCDay::AOCD16, idx, line, token, expected);
+    let position = |token: &str| -> Result<u8> {
+        match parse::number(AoCYear::AOC2017, AoCDay::AOCD16, idx, line, token)? {
+            position if position < DANCERS => Ok(position),
+            _ => Err(error(token, "a position from 0 to 15").into()),
+        }
+    };
+
+    for token in line.split(',') {
+        if let Some(caps) = spin_re.captures(token) {
+            let val_str = &caps[1];
+            let val = parse::number(AoCYear::AOC2017, AoCDay::AOCD16, idx, line, val_str)?;
+            if val > u32::from(DANCERS) {
+                return Err(error(val_str, "a spin from 0 to 16").into());
+            }
             moves.push(Move::Spin(val));
-        } else if exchange_re.is_match(token) {
-            let caps = exchange_re.captures(token).ok_or(anyhow!("invalid exchange captures"))?;
-            let pos1_str = caps.get(1).ok_or(anyhow!("invalid exchange pos1"))?.as_str();
-            let pos2_str = caps.get(2).ok_or(anyhow!("invalid exchange pos2"))?.as_str();
-            let pos1 = pos1_str.parse::<u8>()?;
-            let pos2 = pos2_str.parse::<u8>()?;
-            moves.push(Move::Exchange(pos1, pos2));
-        } else if partner_re.is_match(token) {
-            let caps = partner_re.captures(token).ok_or(anyhow!("invalid partner captures"))?;
-            let name1_str = caps.get(1).ok_or(anyhow!("invalid partner name1"))?.as_str();
-            let name2_str = caps.get(2).ok_or(anyhow!("invalid partner name2"))?.as_str();
-            let name1 = name1_str.chars().next().ok_or(anyhow!("name1 not a char"))?;
-            let name2 = name2_str.chars().next().ok_or(anyhow!("name2 not a char"))?;
-            moves.push(Move::Partner(name1, name2));
+        } else if let Some(caps) = exchange_re.captures(token) {
+            moves.push(Move::Exchange(position(&caps[1])?, position(&caps[2])?));
+        } else if let Some(caps) = partner_re.captures(token) {
+            let name = |i: usize| caps[i].chars().next().unwrap_or_default();
+            moves.push(Move::Partner(name(1), name(2)));
         } else {
-            return Err(anyhow!(format!("Invalid token: {token}")));
+            return Err(error(token, "a dance move like `s1`, `x3/4` or `pe/b`").into());
         }
     }
     Ok(())
@@ -132,19 +164,19 @@ mod one_star {
     fn solution() {
         let mut dancers = vec!['a', 'b', 'c', 'd', 'e'];
         let mut moves = Vec::new();
-        super::generate_moves("s1,x3/4,pe/b", &mut moves).expect("");
+        super::generate_moves(0, "s1,x3/4,pe/b", &mut moves).expect("");
         assert_eq!(moves.len(), 3);
         super::apply_moves(&moves, &mut dancers).expect("");
         assert_eq!(dancers, vec!['b', 'a', 'e', 'd', 'c']);
         moves.clear();
         dancers = vec!['a', 'b', 'c', 'd', 'e'];
-        super::generate_moves("s3", &mut moves).expect("");
+        super::generate_moves(0, "s3", &mut moves).expect("");
         super::apply_moves(&moves, &mut dancers).expect("");
         assert_eq!(dancers, vec!['c', 'd', 'e', 'a', 'b']);
 
         moves.clear();
         dancers = vec!['a', 'b', 'c', 'd', 'e'];
-        super::generate_moves("x0/3", &mut moves).expect("");
+        super::generate_moves(0, "x0/3", &mut moves).expect("");
         super::apply_moves(&moves, &mut dancers).expect("");
         assert_eq!(dancers, vec!['d', 'b', 'c', 'a', 'e']);
     }
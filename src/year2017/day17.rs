@@ -1,15 +1,43 @@
 //! Advent of Code - Day 17 Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse;
+use crate::solver::Solver;
 use anyhow::Result;
 use std::io::BufRead;
 
+/// Day 17 "Spinlock"
+pub(crate) struct Day17;
+
+impl Solver for Day17 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD17
+    }
+
+    fn title(&self) -> &'static str {
+        "Spinlock"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Find the solution for Advent of Code 2017
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    use std::io::{self, Write};
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let mut steps = 0;
     let mut buf = vec![0, 0];
-    for line_result in reader.lines() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
-        steps = line.parse::<u64>()?;
+    for (idx, line_result) in reader.lines().enumerate() {
+        let line = &line_result?;
+        steps = parse::number(AoCYear::AOC2017, AoCDay::AOCD17, idx, line, line)?;
     }
 
     let result = if second_star {
@@ -17,8 +45,7 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
     } else {
         spinlock(&mut buf, steps, 2017, second_star)?
     };
-    writeln!(io::stdout(), "{result}")?;
-    Ok(0)
+    Ok(result.into())
 }
 
 /// Run the spinlock.
@@ -1,8 +1,36 @@
 //! Advent of Code - Day 19 Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::grid::Grid;
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
-use ndarray::Array2;
 use std::fmt;
-use std::io::{self, BufRead, Write};
+use std::io::BufRead;
+
+/// Day 19 "A Series of Tubes"
+pub(crate) struct Day19;
+
+impl Solver for Day19 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD19
+    }
+
+    fn title(&self) -> &'static str {
+        "A Series of Tubes"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
 
 /// Direction of Movement
 #[derive(Debug, Eq, PartialEq)]
@@ -30,57 +58,37 @@ impl fmt::Display for Direction {
 }
 
 /// Find the solution for Advent of Code 2017
-pub fn find_solution<T: BufRead>(reader: T, _second_star: bool) -> Result<u32> {
-    let mut network_map: Array2<u8> = Array2::zeros((201, 201));
-    for (idx, line_result) in reader.lines().enumerate() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
-        fill_row(line, idx, &mut network_map);
-    }
-
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
+    let network_map = Grid::parse_chars(reader)?;
     let (letters, steps) = traverse_map(&network_map)?;
-    writeln!(io::stdout(), "{letters}")?;
 
-    Ok(steps)
-}
-
-/// Fill a row in the network map array.
-fn fill_row(line: &str, row: usize, network_map: &mut Array2<u8>) {
-    for (idx, bit) in line.as_bytes().iter().enumerate() {
-        network_map[[row, idx]] = *bit;
-    }
+    if second_star { Ok(steps.into()) } else { Ok(letters.into()) }
 }
 
 /// Traverse the map.
-fn traverse_map(network_map: &Array2<u8>) -> Result<(String, u32)> {
-    let mut bytes = Vec::new();
+fn traverse_map(network_map: &Grid<char>) -> Result<(String, u32)> {
+    let mut letters = String::new();
     let mut curr_row = 0;
     let mut curr_col = 0;
-    let max_col = network_map.ncols();
-    let max_row = network_map.nrows();
     let mut curr_direction = Direction::Down;
     let mut steps = 0;
 
     loop {
-        if curr_row == max_row {
-            return Err(anyhow!(format!("Invalid row value: {curr_row}")));
-        }
-        if curr_col == max_col {
-            return Err(anyhow!(format!("Invalid col value: {curr_col}")));
-        }
-
-        let curr_byte = network_map[[curr_row, curr_col]];
+        let curr_char = *network_map
+            .get([curr_col, curr_row])
+            .ok_or_else(|| anyhow!("Invalid position: row {curr_row}, col {curr_col}"))?;
 
-        match curr_byte {
-            32 => {
+        match curr_char {
+            ' ' => {
                 if curr_row == 0 {
                     // We are still in the first row.  We need to find
-                    // the down byte.
+                    // the down character.
                     curr_col += 1;
                 } else {
                     break;
                 }
             }
-            124 | 45 => {
+            '|' | '-' => {
                 match curr_direction {
                     Direction::Down => curr_row += 1,
                     Direction::Up => curr_row -= 1,
@@ -89,8 +97,8 @@ fn traverse_map(network_map: &Array2<u8>) -> Result<(String, u32)> {
                 }
                 steps += 1;
             }
-            43 => {
-                let (next_row, next_col) = get_next_neighbor(curr_row, curr_col, max_row, max_col, &curr_direction, network_map)?;
+            '+' => {
+                let [next_col, next_row] = get_next_neighbor([curr_col, curr_row], &curr_direction, network_map)?;
                 let next_direction = get_next_dir(curr_row, curr_col, next_row, next_col, &curr_direction)?;
                 curr_row = next_row;
                 curr_col = next_col;
@@ -98,7 +106,7 @@ fn traverse_map(network_map: &Array2<u8>) -> Result<(String, u32)> {
                 steps += 1;
             }
             x => {
-                bytes.push(x);
+                letters.push(x);
 
                 match curr_direction {
                     Direction::Up => curr_row -= 1,
@@ -111,35 +119,20 @@ fn traverse_map(network_map: &Array2<u8>) -> Result<(String, u32)> {
         }
     }
 
-    Ok((String::from_utf8_lossy(&bytes).into_owned(), steps))
+    Ok((letters, steps))
 }
 
-/// Check the three nearest neighbors for the next valid direction.
-fn get_next_neighbor(row: usize, col: usize, max_row: usize, max_col: usize, direction: &Direction, network_map: &Array2<u8>) -> Result<(usize, usize)> {
-    let row_i: isize = TryFrom::try_from(row)?;
-    let col_i: isize = TryFrom::try_from(col)?;
-
-    let (row_deltas, col_deltas) = match *direction {
-        Direction::Down | Direction::Up => (vec![0, 0], vec![-1, 1]),
-        Direction::Right | Direction::Left => (vec![-1, 1], vec![0, 0]),
+/// Check the two neighbors across the current direction for the next valid position.
+fn get_next_neighbor(pos: [usize; 2], direction: &Direction, network_map: &Grid<char>) -> Result<[usize; 2]> {
+    let across = |[col, row]: [usize; 2]| match *direction {
+        Direction::Down | Direction::Up => row == pos[1],
+        Direction::Right | Direction::Left => col == pos[0],
     };
 
-    // Check the four adjacent neighbors (left, down, up, right)
-    for k in 0..2 {
-        if let Ok(adj_row) = TryFrom::try_from(row_i + row_deltas[k]) {
-            if let Ok(adj_col) = TryFrom::try_from(col_i + col_deltas[k]) {
-                if adj_row < max_row && adj_col < max_col && network_map[[adj_row, adj_col]] != 32 {
-                    return Ok((adj_row, adj_col));
-                }
-            } else {
-                continue;
-            }
-        } else {
-            continue;
-        }
-    }
-
-    Err(anyhow!("Unable to find valid next neighbor"))
+    network_map
+        .neighbors4(pos)
+        .find(|adj| across(*adj) && network_map[*adj] != ' ')
+        .ok_or_else(|| anyhow!("Unable to find valid next neighbor"))
 }
 
 /// Get the next direction
@@ -166,25 +159,26 @@ fn get_next_dir(curr_row: usize, curr_col: usize, next_row: usize, next_col: usi
 
 #[cfg(test)]
 mod one_star {
-    use super::{fill_row, traverse_map};
-    use ndarray::Array2;
+    use super::traverse_map;
+    use crate::grid::Grid;
+    use anyhow::Result;
+    use std::io::Cursor;
+
+    const TEST_MAP: &str = r"     |         
+     |  +--+   
+     A  |  C   
+ F---|----E|--+
+     |  |  |  D
+     +B-+  +--+";
 
     #[test]
-    fn solution() {
-        let mut network_map: Array2<u8> = Array2::zeros((6, 15));
-        fill_row("     |         ", 0, &mut network_map);
-        assert_eq!(network_map[[0, 5]], 124);
-        fill_row("     |  +--+   ", 1, &mut network_map);
-        assert_eq!(network_map[[1, 5]], 124);
-        fill_row("     A  |  C   ", 2, &mut network_map);
-        assert_eq!(network_map[[2, 5]], 65);
-        fill_row(" F---|----E|--+", 3, &mut network_map);
-        assert_eq!(network_map[[3, 5]], 124);
-        fill_row("     |  |  |  D", 4, &mut network_map);
-        assert_eq!(network_map[[4, 5]], 124);
-        fill_row("     +B-+  +--+", 5, &mut network_map);
-        assert_eq!(network_map[[5, 5]], 43);
-        assert_eq!(traverse_map(&network_map).expect(""), ("ABCDEF".to_string(), 38));
+    fn solution() -> Result<()> {
+        let network_map = Grid::parse_chars(Cursor::new(TEST_MAP))?;
+        assert_eq!(network_map[[5, 0]], '|');
+        assert_eq!(network_map[[5, 2]], 'A');
+        assert_eq!(network_map[[5, 5]], '+');
+        assert_eq!(traverse_map(&network_map)?, ("ABCDEF".to_string(), 38));
+        Ok(())
     }
 }
 
@@ -1,9 +1,38 @@
 //! Advent of Code - Day 20 'Particle Swarm' Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::{self, ParseError};
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
 use regex::Regex;
 use std::collections::HashMap;
 use std::io::BufRead;
 
+/// Day 20 "Particle Swarm"
+pub(crate) struct Day20;
+
+impl Solver for Day20 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD20
+    }
+
+    fn title(&self) -> &'static str {
+        "Particle Swarm"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// A particle has x,y,z coords, a velocity, an acceleration,
 /// and a Manhattan Distance from the origin.
 struct Particle {
@@ -49,15 +78,14 @@ struct Acc {
 }
 
 /// Find the solution for Advent of Code 2017
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    use std::io::{self, Write};
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let mut particle_map: HashMap<usize, Particle> = HashMap::new();
-    let coords_re = Regex::new(r"p=< *(-?\d+),(-?\d+),(-?\d+)>")?;
-    let vel_re = Regex::new(r"v=< *(-?\d+),(-?\d+),(-?\d+)>")?;
-    let acc_re = Regex::new(r"a=< *(-?\d+),(-?\d+),(-?\d+)>")?;
+    let coords_re = Regex::new(r"^p=< *(-?\d+),(-?\d+),(-?\d+)>$")?;
+    let vel_re = Regex::new(r"^v=< *(-?\d+),(-?\d+),(-?\d+)>$")?;
+    let acc_re = Regex::new(r"^a=< *(-?\d+),(-?\d+),(-?\d+)>$")?;
 
     for (idx, line_result) in reader.lines().enumerate() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
+        let line = &line_result?;
         add_particle_to_map(idx, line, &mut particle_map, &coords_re, &vel_re, &acc_re)?;
     }
 
@@ -72,60 +100,35 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
             for idx in matches {
                 particle_map.remove(&idx);
             }
-            writeln!(io::stdout(), "{}", particle_map.len())?;
-        } else {
-            let idx = find_minimum_md(&particle_map)?;
-            writeln!(io::stdout(), "{idx}")?;
         }
     }
 
-    writeln!(io::stdout())?;
-    Ok(0)
+    if second_star {
+        Ok(particle_map.len().into())
+    } else {
+        Ok(find_minimum_md(&particle_map)?.into())
+    }
 }
 
 /// Add a particle to the particle map
 #[allow(clippy::similar_names)]
 fn add_particle_to_map(idx: usize, line: &str, particle_map: &mut HashMap<usize, Particle>, coords_re: &Regex, vel_re: &Regex, acc_re: &Regex) -> Result<()> {
-    let parts: Vec<&str> = line.split(", ").collect();
-
-    let coords = if coords_re.is_match(parts[0]) {
-        let caps = coords_re.captures(parts[0]).ok_or(anyhow!("invalid coords captures"))?;
-        let x_str = caps.get(1).ok_or(anyhow!("invalid x value"))?.as_str();
-        let y_str = caps.get(2).ok_or(anyhow!("invalid y value"))?.as_str();
-        let z_str = caps.get(3).ok_or(anyhow!("invalid z value"))?.as_str();
-        let x = x_str.parse::<i64>()?;
-        let y = y_str.parse::<i64>()?;
-        let z = z_str.parse::<i64>()?;
-        Coords { x, y, z }
-    } else {
-        return Err(anyhow!("invalid coordinates"));
+    let mut parts = line.split(", ");
+    let mut triple = |re: &Regex, expected: &str| -> Result<[i64; 3]> {
+        let part = parts.next().unwrap_or(&line[line.len()..]);
+        let caps = re
+            .captures(part)
+            .ok_or_else(|| ParseError::new(AoCYear::AOC2017, AoCDay::AOCD20, idx, line, part, expected))?;
+        let number = |i: usize| parse::number(AoCYear::AOC2017, AoCDay::AOCD20, idx, line, &caps[i]);
+        Ok([number(1)?, number(2)?, number(3)?])
     };
 
-    let velocity = if vel_re.is_match(parts[1]) {
-        let caps = vel_re.captures(parts[1]).ok_or(anyhow!("invalid velocity captures"))?;
-        let vx_str = caps.get(1).ok_or(anyhow!("invalid vx value"))?.as_str();
-        let vy_str = caps.get(2).ok_or(anyhow!("invalid vy value"))?.as_str();
-        let vz_str = caps.get(3).ok_or(anyhow!("invalid vz value"))?.as_str();
-        let vx = vx_str.parse::<i64>()?;
-        let vy = vy_str.parse::<i64>()?;
-        let vz = vz_str.parse::<i64>()?;
-        Velocity { vx, vy, vz }
-    } else {
-        return Err(anyhow!("invalid velocity"));
-    };
-
-    let acc = if acc_re.is_match(parts[2]) {
-        let caps = acc_re.captures(parts[2]).ok_or(anyhow!("invalid acceleration captures"))?;
-        let ax_str = caps.get(1).ok_or(anyhow!("invalid ax value"))?.as_str();
-        let ay_str = caps.get(2).ok_or(anyhow!("invalid ay value"))?.as_str();
-        let az_str = caps.get(3).ok_or(anyhow!("invalid az value"))?.as_str();
-        let ax = ax_str.parse::<i64>()?;
-        let ay = ay_str.parse::<i64>()?;
-        let az = az_str.parse::<i64>()?;
-        Acc { ax, ay, az }
-    } else {
-        return Err(anyhow!("invalid acceleration"));
-    };
+    let [x, y, z] = triple(coords_re, "a position like `p=<1,2,3>`")?;
+    let coords = Coords { x, y, z };
+    let [vx, vy, vz] = triple(vel_re, "a velocity like `v=<1,2,3>`")?;
+    let velocity = Velocity { vx, vy, vz };
+    let [ax, ay, az] = triple(acc_re, "an acceleration like `a=<1,2,3>`")?;
+    let acc = Acc { ax, ay, az };
 
     let md: usize = TryFrom::try_from(coords.x.abs() + coords.y.abs() + coords.z.abs())?;
     let particle = Particle {
@@ -1,28 +1,54 @@
 //! Advent of Code - Day 21 'Fractal Art' Solution
 
-use crate::utils::PrivateTryFromUsize;
-use anyhow::{Result, anyhow};
-use itertools::{Itertools, iproduct};
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::ParseError;
+use crate::solver::Solver;
+use anyhow::Result;
+use itertools::iproduct;
 use pathfinding::matrix::Matrix;
 use std::collections::HashMap;
 use std::io::BufRead;
 
+/// Day 21 "Fractal Art"
+pub(crate) struct Day21;
+
+impl Solver for Day21 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD21
+    }
+
+    fn title(&self) -> &'static str {
+        "Fractal Art"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Find the solution for Advent of Code 2017
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    let subst = reader
-        .lines()
-        .map_while(Result::ok)
-        .flat_map(|line| {
-            let (k, v) = line
-                .trim()
-                .split(" => ")
-                .filter_map(matrix)
-                .next_tuple()
-                .ok_or(anyhow!("blah"))
-                .expect("tough cookies");
-            iproduct!(vec![k.clone(), k.flipped_ud(), k.flipped_lr()], 0..4).map(move |(m, i)| (m.rotated_cw(i), v.clone()))
-        })
-        .collect::<HashMap<_, _>>();
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
+    let mut subst = HashMap::new();
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let (k, v) = line
+            .trim()
+            .split_once(" => ")
+            .ok_or_else(|| ParseError::whole_line(AoCYear::AOC2017, AoCDay::AOCD21, idx, &line, "a rule like `../.# => ##./#../...`"))?;
+        let pattern =
+            |text| matrix(text).ok_or_else(|| ParseError::new(AoCYear::AOC2017, AoCDay::AOCD21, idx, &line, text, "rows of `.` and `#` split by `/`"));
+        let (k, v) = (pattern(k)?, pattern(v)?);
+        subst.extend(iproduct!(vec![k.clone(), k.flipped_ud(), k.flipped_lr()], 0..4).map(move |(m, i)| (m.rotated_cw(i), v.clone())));
+    }
     let mut sharps = (0..).scan(matrix(".#./..#/###"), |grid, _| {
         if let Some(grid) = grid {
             let pt = 2 + (grid.rows % 2);
@@ -40,9 +66,9 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
     });
 
     if second_star {
-        Ok(u32::private_try_from(sharps.nth(4).unwrap_or(0))?)
+        Ok(sharps.nth(4).unwrap_or(0).into())
     } else {
-        Ok(u32::private_try_from(sharps.nth(12).unwrap_or(0))?)
+        Ok(sharps.nth(12).unwrap_or(0).into())
     }
 }
 
@@ -1,8 +1,36 @@
 //! Advent of Code - Day 22 Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::grid::{Grid, UnboundedGrid};
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
-use ndarray::Array2;
 use std::io::BufRead;
 
+/// Day 22 "Sporifica Virus"
+pub(crate) struct Day22;
+
+impl Solver for Day22 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD22
+    }
+
+    fn title(&self) -> &'static str {
+        "Sporifica Virus"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// The direction the virus is facing.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum Direction {
@@ -30,20 +58,22 @@ enum State {
 }
 
 /// Find the solution for Advent of Code 2017
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    let dim = 1001;
-    let mut arr: Array2<char> = Array2::from_elem((dim, dim), '.');
-    let center = dim / 2;
-
-    for (i, line_result) in reader.lines().enumerate() {
-        let line = &line_result?;
-        add_to_arr(i, center, line, &mut arr);
-    }
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
+    let bursts = if second_star { 10_000_000 } else { 10_000 };
+    Ok(run_bursts(reader, bursts, second_star)?.into())
+}
+
+/// Run the virus for `bursts` bursts from the middle of the map, counting the new infections.
+fn run_bursts<T: BufRead>(reader: T, bursts: usize, second_star: bool) -> Result<usize> {
+    let map = Grid::parse_chars(reader)?;
+    let half = |len: usize| -i64::try_from(len / 2).unwrap_or_default();
+    let origin = [half(map.width()), half(map.height())];
+    let mut arr = UnboundedGrid::from_grid(map, origin, '.');
 
     let mut curr_dir = Direction::Up;
-    let mut coords = (center, center);
+    let mut coords = [0, 0];
     let mut count = 0;
-    for _ in 0..if second_star { 10_000_000 } else { 10_000 } {
+    for _ in 0..bursts {
         curr_dir = change_direction(coords, &curr_dir, &arr)?;
 
         if change_state(coords, &mut arr, second_star)? {
@@ -55,18 +85,8 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
     Ok(count)
 }
 
-/// Add the line at index to the array.
-fn add_to_arr(row: usize, center: usize, line: &str, arr: &mut Array2<char>) {
-    let half = line.len() / 2;
-    let start_col = center - half;
-    let actual_row = row + center - half;
-    for (j, c) in line.chars().enumerate() {
-        arr[[actual_row, start_col + j]] = c;
-    }
-}
-
 /// Determine new direction
-fn change_direction(coords: (usize, usize), curr_direction: &Direction, arr: &Array2<char>) -> Result<Direction> {
+fn change_direction(coords: [i64; 2], curr_direction: &Direction, arr: &UnboundedGrid<char>) -> Result<Direction> {
     let curr_state = get_state(coords, arr)?;
 
     Ok(match curr_state {
@@ -93,9 +113,8 @@ fn change_direction(coords: (usize, usize), curr_direction: &Direction, arr: &Ar
 }
 
 /// Determine the state of the current coord.
-fn get_state(coords: (usize, usize), arr: &Array2<char>) -> Result<State> {
-    let coords: [usize; 2] = coords.into();
-    let val = arr[coords];
+fn get_state(coords: [i64; 2], arr: &UnboundedGrid<char>) -> Result<State> {
+    let val = *arr.get(coords);
     let res = match val {
         '#' => State::Infected,
         'W' => State::Weakened,
@@ -106,29 +125,29 @@ fn get_state(coords: (usize, usize), arr: &Array2<char>) -> Result<State> {
     Ok(res)
 }
 /// Infect or clean the given coords.
-fn change_state(coords: (usize, usize), arr: &mut Array2<char>, second_star: bool) -> Result<bool> {
+fn change_state(coords: [i64; 2], arr: &mut UnboundedGrid<char>, second_star: bool) -> Result<bool> {
     let mut new_infection = false;
     let curr_state = get_state(coords, arr)?;
-    let coords: [usize; 2] = coords.into();
+    let cell = arr.get_mut(coords);
 
     if second_star {
         match curr_state {
-            State::Clean => arr[coords] = 'W',
+            State::Clean => *cell = 'W',
             State::Weakened => {
-                arr[coords] = '#';
+                *cell = '#';
                 new_infection = true;
             }
-            State::Infected => arr[coords] = 'F',
-            State::Flagged => arr[coords] = '.',
+            State::Infected => *cell = 'F',
+            State::Flagged => *cell = '.',
         }
     } else {
         match curr_state {
             State::Clean => {
-                arr[coords] = '#';
+                *cell = '#';
                 new_infection = true;
             }
             State::Infected => {
-                arr[coords] = '.';
+                *cell = '.';
             }
             _ => return Err(anyhow!("invalid state for one star")),
         }
@@ -138,69 +157,39 @@ fn change_state(coords: (usize, usize), arr: &mut Array2<char>, second_star: boo
 }
 
 /// Move the virus
-fn move_virus(coords: &mut (usize, usize), direction: &Direction) {
+fn move_virus(coords: &mut [i64; 2], direction: &Direction) {
     match *direction {
-        Direction::Up => coords.0 -= 1,
-        Direction::Left => coords.1 -= 1,
-        Direction::Down => coords.0 += 1,
-        Direction::Right => coords.1 += 1,
+        Direction::Up => coords[1] -= 1,
+        Direction::Left => coords[0] -= 1,
+        Direction::Down => coords[1] += 1,
+        Direction::Right => coords[0] += 1,
     }
 }
 
 #[cfg(test)]
 mod one_star {
-    use super::Direction;
-    use ndarray::Array2;
+    use super::run_bursts;
+    use anyhow::Result;
+    use std::io::Cursor;
 
     #[test]
-    fn solution() {
-        let dim = 1001;
-        let mut arr: Array2<char> = Array2::from_elem((dim, dim), '.');
-        let center = dim / 2;
-        super::add_to_arr(0, center, "..#", &mut arr);
-        super::add_to_arr(1, center, "#..", &mut arr);
-        super::add_to_arr(2, center, "...", &mut arr);
-
-        let mut curr_dir = Direction::Up;
-        let mut coords = (center, center);
-        let mut count = 0;
-        for _ in 0..10000 {
-            curr_dir = super::change_direction(coords, &curr_dir, &arr).expect("");
-
-            if super::change_state(coords, &mut arr, false).expect("") {
-                count += 1;
-            }
-            super::move_virus(&mut coords, &curr_dir);
-        }
-        assert_eq!(count, 5587);
+    fn solution() -> Result<()> {
+        assert_eq!(run_bursts(Cursor::new("..#\n#..\n..."), 70, false)?, 41);
+        assert_eq!(run_bursts(Cursor::new("..#\n#..\n..."), 10000, false)?, 5587);
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod two_star {
-    use super::Direction;
-    use ndarray::Array2;
+    use super::run_bursts;
+    use anyhow::Result;
+    use std::io::Cursor;
 
     #[test]
-    fn solution() {
-        let dim = 1001;
-        let mut arr: Array2<char> = Array2::from_elem((dim, dim), '.');
-        let center = dim / 2;
-        super::add_to_arr(0, center, "..#", &mut arr);
-        super::add_to_arr(1, center, "#..", &mut arr);
-        super::add_to_arr(2, center, "...", &mut arr);
-
-        let mut curr_dir = Direction::Up;
-        let mut coords = (center, center);
-        let mut count = 0;
-        for _ in 0..10_000_000 {
-            curr_dir = super::change_direction(coords, &curr_dir, &arr).expect("");
-
-            if super::change_state(coords, &mut arr, true).expect("") {
-                count += 1;
-            }
-            super::move_virus(&mut coords, &curr_dir);
-        }
-        assert_eq!(count, 2_511_944);
+    fn solution() -> Result<()> {
+        assert_eq!(run_bursts(Cursor::new("..#\n#..\n..."), 100, true)?, 26);
+        assert_eq!(run_bursts(Cursor::new("..#\n#..\n..."), 10_000_000, true)?, 2_511_944);
+        Ok(())
     }
 }
@@ -1,147 +1,166 @@
 //! Advent of Code - Day 23 'Coprocessor Conflagration' Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::solver::Solver;
+use crate::year2017::duet::{self, Device, Instruction, Machine, Program, Value};
 use anyhow::{Result, anyhow};
-use std::collections::HashMap;
 use std::io::BufRead;
 
-/// A value can either be a pointer to a register or a number.
-#[derive(Debug, Eq, PartialEq)]
-enum Value {
-    /// A number value.
-    Number(i64),
-    /// A registe pointer value.
-    Register(String),
-}
+/// Day 23 "Coprocessor Conflagration"
+pub(crate) struct Day23;
 
-/// Find the solution for Advent of Code 2017
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    let mut commands: HashMap<i64, (String, String, Option<Value>)> = HashMap::new();
-    let mut register_map: HashMap<String, i64> = HashMap::new();
-    for (idx, line_result) in reader.lines().enumerate() {
-        let line = &line_result.unwrap_or_else(|_| "".to_string());
-        commands.insert(idx as i64, parse_command(line)?);
+impl Solver for Day23 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
     }
 
-    initialize_register_map(&commands, &mut register_map)?;
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD23
+    }
 
-    let mul_count = if second_star {
-        let b = 106_700;
-        let c = 123_700;
-        let mut h = 0;
-        for b in (b..=c).step_by(17) {
-            if !primal::is_prime(b) {
-                h += 1
-            }
-        }
-        h
-    } else {
-        let mut id = 0;
-        let mut count = 0;
-        loop {
-            if id < 0 || id == commands.len() as i64 {
-                break;
-            }
-            let next_command = commands.get(&id).ok_or(anyhow!("invalid command"))?;
-            let (new_id, new_mul_count) = run_command((id, next_command), &mut register_map, count)?;
-            id = new_id;
-            count = new_mul_count;
-        }
-        count
-    };
+    fn title(&self) -> &'static str {
+        "Coprocessor Conflagration"
+    }
 
-    Ok(mul_count)
-}
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false, &Context::default())
+    }
 
-/// Parse a command into (command, register, value)
-fn parse_command(command: &str) -> Result<(String, String, Option<Value>)> {
-    let token_strs: Vec<&str> = command.split(' ').collect();
-
-    if token_strs.len() == 3 {
-        let value = if let Ok(number) = token_strs[2].parse::<i64>() {
-            Value::Number(number)
-        } else {
-            Value::Register(token_strs[2].to_string())
-        };
-        Ok((token_strs[0].to_string(), token_strs[1].to_string(), Some(value)))
-    } else if token_strs.len() == 2 {
-        Ok((token_strs[0].to_string(), token_strs[1].to_string(), None))
-    } else {
-        Err(anyhow!("Invalid command"))
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true, &Context::default())
+    }
+
+    fn star(&self, part: u8, reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer> {
+        find_solution(reader, part == 2, ctx)
     }
 }
 
-/// Initialize the register map.
-fn initialize_register_map(commands: &HashMap<i64, (String, String, Option<Value>)>, register_map: &mut HashMap<String, i64>) -> Result<()> {
-    for (_, command) in commands.iter() {
-        register_map.entry(command.1.clone()).or_insert(0);
+/// Counts the `mul` instructions executed.
+#[derive(Debug, Default)]
+struct Coprocessor {
+    /// How many `mul` instructions have been executed
+    muls: usize,
+}
+
+impl Device for Coprocessor {
+    fn multiplied(&mut self) {
+        self.muls += 1;
     }
-    Ok(())
 }
 
-/// Run a command
-fn run_command((id, command): (i64, &(String, String, Option<Value>)), register_map: &mut HashMap<String, i64>, count: u32) -> Result<(i64, u32)> {
-    let cmd = &command.0;
-    let register = &command.1;
-    let value = &command.2;
-    let mut mul_count = count;
-
-    match &cmd[..] {
-        "set" => {
-            let actual_value = match *value {
-                Some(Value::Number(x)) => x,
-                Some(Value::Register(ref x)) => *register_map.get(x).ok_or(anyhow!("invalid register"))?,
-                _ => return Err(anyhow!("Invalid set command")),
-            };
-            *register_map.get_mut(register).ok_or(anyhow!("invalid register"))? = actual_value;
-        }
-        "sub" => {
-            let actual_value = match *value {
-                Some(Value::Number(x)) => x,
-                Some(Value::Register(ref x)) => *register_map.get(x).ok_or(anyhow!("invalid register"))?,
-                _ => return Err(anyhow!("Invalid set command")),
-            };
-            let x = register_map.get_mut(register).ok_or(anyhow!("invalid register"))?;
-            *x -= actual_value;
-        }
-        "mul" => {
-            let actual_value = match *value {
-                Some(Value::Number(x)) => x,
-                Some(Value::Register(ref x)) => *register_map.get(x).ok_or(anyhow!("invalid register"))?,
-                _ => return Err(anyhow!("Invalid set command")),
-            };
-            let x = register_map.get_mut(register).ok_or(anyhow!("invalid register"))?;
-            *x *= actual_value;
-            mul_count += 1;
-        }
-        "jnz" => {
-            let should_jump = if let Ok(val) = register.parse::<i64>() {
-                val
-            } else {
-                *register_map.get(register).ok_or(anyhow!("invalid register"))?
-            };
-
-            if should_jump != 0 {
-                let actual_value = match *value {
-                    Some(Value::Number(x)) => x,
-                    Some(Value::Register(ref x)) => *register_map.get(x).ok_or(anyhow!("invalid register"))?,
-                    _ => return Err(anyhow!("Invalid set command")),
-                };
-                return Ok((id + actual_value, mul_count));
-            }
+/// Find the solution for Advent of Code 2017
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool, ctx: &Context) -> Result<Answer> {
+    let program = Program::parse(reader, AoCDay::AOCD23)?;
+
+    let count = if second_star {
+        composites(&program, ctx)?
+    } else {
+        let mut coprocessor = Coprocessor::default();
+        let mut machine = Machine::new(&program);
+        let _ = machine.run(&mut coprocessor, ctx)?;
+        if ctx.tracing() {
+            eprintln!("Stopped after {} instructions", machine.executed());
         }
-        _ => {}
+        coprocessor.muls
+    };
+
+    Ok(count.into())
+}
+
+/// Count the values of `b` the outer loop tries that are not prime, which is what register `h` ends up holding.
+///
+/// The outer loop runs from where the final `jnz` jumps back to, stepping `b` by the `sub b` just before that
+/// jump until it reaches `c`.  Running everything before the loop with `a` set to 1 gives the first `b` and `c`.
+fn composites(program: &Program, ctx: &Context) -> Result<usize> {
+    let shape = || anyhow!("the program does not end in a loop stepping `b` by a `sub` and jumping back with `jnz 1`");
+    let [
+        ..,
+        Instruction::Sub(stepped, Value::Number(step)),
+        Instruction::Jnz(Value::Number(1), Value::Number(back)),
+    ] = program.instructions[..]
+    else {
+        return Err(shape());
+    };
+    let end = program.instructions.len() - 1;
+    let start = end.checked_add_signed(isize::try_from(back)?).filter(|start| *start < end).ok_or_else(shape)?;
+    let setup = Program {
+        instructions: program.instructions[..start].to_vec(),
+    };
+
+    let mut machine = Machine::new(&setup);
+    machine.set_register('a', 1);
+    let _ = machine.run(&mut Coprocessor::default(), ctx)?;
+    let (first, last) = (u64::try_from(machine.register('b'))?, u64::try_from(machine.register('c'))?);
+    let step = usize::try_from(step.checked_neg().ok_or_else(shape)?)?;
+    if stepped != duet::register('b') || step == 0 {
+        return Err(shape());
     }
 
-    Ok((id + 1, mul_count))
+    Ok((first..=last).step_by(step).filter(|b| !primal::is_prime(*b)).count())
 }
 
 #[cfg(test)]
 mod one_star {
+    use super::find_solution;
+    use crate::context::Context;
+    use anyhow::Result;
+    use std::io::Cursor;
+
+    /// The puzzle's program, checking 20, 37 and 54 when `a` is 1, and just 5 otherwise.
+    pub(super) const TEST_CODE: &str = r"set b 5
+set c b
+jnz a 2
+jnz 1 5
+mul b 2
+sub b -10
+set c b
+sub c -34
+set f 1
+set d 2
+set e 2
+set g d
+mul g e
+sub g b
+jnz g 2
+set f 0
+sub e -1
+set g e
+sub g b
+jnz g -8
+sub d -1
+set g d
+sub g b
+jnz g -13
+jnz f 2
+sub h -1
+set g b
+sub g c
+jnz g 2
+jnz 1 3
+sub b -17
+jnz 1 -23";
+
     #[test]
-    fn solution() {}
+    fn solution() -> Result<()> {
+        // `d` and `e` each run from 2 to 4 for the single `b` of 5.
+        assert_eq!(find_solution(Cursor::new(TEST_CODE), false, &Context::default())?, 9_usize.into());
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod two_star {
+    use super::{find_solution, one_star::TEST_CODE};
+    use crate::context::Context;
+    use anyhow::Result;
+    use std::io::Cursor;
+
     #[test]
-    fn solution() {}
+    fn solution() -> Result<()> {
+        // 20 and 54 are composite, 37 is prime.
+        assert_eq!(find_solution(Cursor::new(TEST_CODE), true, &Context::default())?, 2_usize.into());
+        assert!(find_solution(Cursor::new("set b 5\nset c b"), true, &Context::default()).is_err());
+        Ok(())
+    }
 }
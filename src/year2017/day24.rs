@@ -1,8 +1,37 @@
 //! Advent of Code - Day 24 Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::{self, ParseError};
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
 use std::collections::HashSet;
 use std::io::BufRead;
 
+/// Day 24 "Electromagnetic Moat"
+pub(crate) struct Day24;
+
+impl Solver for Day24 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD24
+    }
+
+    fn title(&self) -> &'static str {
+        "Electromagnetic Moat"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 /// Component
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 struct Component {
@@ -13,9 +42,11 @@ struct Component {
 }
 
 /// Find the solution for Advent of Code 2017
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    // use std::io::{self, Write};
-    let all = reader.lines().filter_map(to_component).collect::<HashSet<Component>>();
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
+    let mut all = HashSet::new();
+    for (idx, line_result) in reader.lines().enumerate() {
+        all.insert(to_component(idx, &line_result?)?);
+    }
 
     let mut scores = Vec::new();
     next(0, &[], &all, &mut scores);
@@ -35,25 +66,23 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
         }
 
         let max = ml_scores.iter().map(|&(s, _)| s).max().ok_or(anyhow!("no max"))?;
-        Ok(max)
+        Ok(max.into())
     } else {
         let max = scores.iter().map(|&(s, _)| s).max().ok_or(anyhow!("no max"))?;
-        Ok(max)
+        Ok(max.into())
     }
 }
 
-/// Convert a String to a Component
-fn to_component(line_res: ::std::result::Result<String, ::std::io::Error>) -> Option<Component> {
-    if let Ok(line) = line_res {
-        let parts = line.split('/').map(|s| s.parse::<u32>().expect("")).collect::<Vec<u32>>();
-
-        Some(Component {
-            left: parts[0],
-            right: parts[1],
-        })
-    } else {
-        None
-    }
+/// Parse a component like `0/2`.
+fn to_component(idx: usize, line: &str) -> Result<Component> {
+    let (left, right) = line
+        .split_once('/')
+        .ok_or_else(|| ParseError::whole_line(AoCYear::AOC2017, AoCDay::AOCD24, idx, line, "a component like `0/2`"))?;
+    let port = |token: &str| parse::number(AoCYear::AOC2017, AoCDay::AOCD24, idx, line, token);
+    Ok(Component {
+        left: port(left)?,
+        right: port(right)?,
+    })
 }
 
 /// Find the next component given a start, the current path, and the set of components.
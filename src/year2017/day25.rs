@@ -1,10 +1,52 @@
 //! Advent of Code - Day 25 Solution
 
-use crate::utils::PrivateTryFromUsize;
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::parse::{self, ParseError};
+use crate::solver::{Solver, Unsolved};
 use anyhow::{Error, Result, anyhow};
 use regex::Regex;
 use std::{collections::BTreeMap, fmt, io::BufRead};
 
+/// The number of slots on the tape, with the cursor starting in the middle.
+const TAPE_LEN: usize = 10_000_000;
+/// How many steps to take between checks of the run's limits.
+const CHECK_INTERVAL: usize = 1 << 16;
+
+/// Day 25 "The Halting Problem"
+pub(crate) struct Day25;
+
+impl Solver for Day25 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2017
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD25
+    }
+
+    fn title(&self) -> &'static str {
+        "The Halting Problem"
+    }
+
+    fn stars(&self) -> u8 {
+        1
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false, &Context::default())
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true, &Context::default())
+    }
+
+    fn star(&self, part: u8, reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer> {
+        find_solution(reader, part == 2, ctx)
+    }
+}
+
 /// The direction to move on the tape
 #[derive(Debug, Default)]
 enum Move {
@@ -69,8 +111,14 @@ impl fmt::Display for State {
 }
 
 /// Find the solution for Advent of Code 2017
-pub fn find_solution<T: BufRead>(reader: T, _second_star: bool) -> Result<u32> {
-    let mut tape: Vec<u8> = vec![0; 10_000_000];
+///
+/// Day 25 has no second puzzle, so the second star is never solved.
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool, ctx: &Context) -> Result<Answer> {
+    if second_star {
+        return Err(Unsolved.into());
+    }
+    ctx.reserve(TAPE_LEN)?;
+    let mut tape: Vec<u8> = vec![0; TAPE_LEN];
     let mut states: BTreeMap<char, State> = BTreeMap::new();
 
     let begin_re = Regex::new(r"^Begin in state ([A-Z])\.$")?;
@@ -82,85 +130,80 @@ pub fn find_solution<T: BufRead>(reader: T, _second_star: bool) -> Result<u32> {
     let cont_re = Regex::new(r"- Continue with state ([A-Z])\.$")?;
 
     let mut start_state = 'A';
-    let mut step_count = 0;
+    let mut step_count: usize = 0;
     let mut parsing_state = false;
     let mut curr_state = 'A';
     let mut curr_val = 0;
 
-    for line in reader.lines().map_while(Result::ok) {
-        if begin_re.is_match(&line) {
-            let caps = begin_re.captures(&line).ok_or(anyhow!("invalid begin captures"))?;
-            let state_str = caps.get(1).ok_or(anyhow!("invalid state value"))?.as_str();
-            let val = state_str.parse::<char>()?;
-            start_state = val;
-        } else if dc_re.is_match(&line) {
-            let caps = dc_re.captures(&line).ok_or(anyhow!("invalid diagnostic checksum captures"))?;
-            let steps_str = caps.get(1).ok_or(anyhow!("invalid diagnostic checksum value"))?.as_str();
-            let steps = steps_str.parse::<usize>()?;
-            step_count = steps;
-        } else if in_state_re.is_match(&line) {
-            let caps = in_state_re.captures(&line).ok_or(anyhow!("invalid in state captures"))?;
-            let state_str = caps.get(1).ok_or(anyhow!("invalid in state value"))?.as_str();
-            let val = state_str.parse::<char>()?;
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let error = |token: &str, expected: &str| ParseError::new(AoCYear::AOC2017, AoCDay::AOCD25, idx, &line, token, expected);
+        let state = |token: &str| token.chars().next().ok_or_else(|| error(token, "a state from `A` to `Z`"));
+        let bit = |token: &str| -> Result<u8> {
+            match parse::number(AoCYear::AOC2017, AoCDay::AOCD25, idx, &line, token)? {
+                val @ (0 | 1) => Ok(val),
+                _ => Err(error(token, "a value of 0 or 1").into()),
+            }
+        };
+
+        if let Some(caps) = begin_re.captures(&line) {
+            start_state = state(&caps[1])?;
+        } else if let Some(caps) = dc_re.captures(&line) {
+            step_count = parse::number(AoCYear::AOC2017, AoCDay::AOCD25, idx, &line, &caps[1])?;
+        } else if let Some(caps) = in_state_re.captures(&line) {
+            let val = state(&caps[1])?;
             parsing_state = true;
             curr_state = val;
             states.insert(val, Default::default());
-        } else if if_curr_re.is_match(&line) && parsing_state {
-            let caps = if_curr_re.captures(&line).ok_or(anyhow!("invalid if current value captures"))?;
-            let val_str = caps.get(1).ok_or(anyhow!("invalid if current value"))?.as_str();
-            let val = val_str.parse::<u8>()?;
-            curr_val = val;
-        } else if write_val_re.is_match(&line) && parsing_state {
-            let caps = write_val_re.captures(&line).ok_or(anyhow!("invalid write value captures"))?;
-            let val_str = caps.get(1).ok_or(anyhow!("invalid write value"))?.as_str();
-            let val = val_str.parse::<u8>()?;
+        } else if let Some(caps) = if_curr_re.captures(&line)
+            && parsing_state
+        {
+            curr_val = bit(&caps[1])?;
+        } else if let Some(caps) = write_val_re.captures(&line)
+            && parsing_state
+        {
+            let val = bit(&caps[1])?;
             let state_ptr = states.entry(curr_state).or_default();
 
             if curr_val == 0 {
                 state_ptr.zero_write = val;
-            } else if curr_val == 1 {
-                state_ptr.one_write = val;
             } else {
-                return Err(anyhow!("Invalid curr value"));
+                state_ptr.one_write = val;
             }
-        } else if move_re.is_match(&line) {
-            let caps = move_re.captures(&line).ok_or(anyhow!("invalid move captures"))?;
-            let move_str = caps.get(1).ok_or(anyhow!("invalid move value"))?.as_str();
+        } else if let Some(caps) = move_re.captures(&line) {
+            let mov = Move::try_from(&caps[1])?;
             let state_ptr = states.entry(curr_state).or_default();
 
             if curr_val == 0 {
-                state_ptr.zero_move = TryFrom::try_from(move_str)?;
-            } else if curr_val == 1 {
-                state_ptr.one_move = TryFrom::try_from(move_str)?;
+                state_ptr.zero_move = mov;
             } else {
-                return Err(anyhow!("Invalid curr value"));
+                state_ptr.one_move = mov;
             }
-        } else if cont_re.is_match(&line) {
-            let caps = cont_re.captures(&line).ok_or(anyhow!("invalid continue captures"))?;
-            let cont_str = caps.get(1).ok_or(anyhow!("invalid continue value"))?.as_str();
-            let val = cont_str.parse::<char>()?;
+        } else if let Some(caps) = cont_re.captures(&line) {
+            let val = state(&caps[1])?;
             let state_ptr = states.entry(curr_state).or_default();
 
             if curr_val == 0 {
                 state_ptr.zero_next = val;
-            } else if curr_val == 1 {
-                state_ptr.one_next = val;
             } else {
-                return Err(anyhow!("Invalid curr value"));
+                state_ptr.one_next = val;
             }
         } else if line.is_empty() && parsing_state {
             parsing_state = false;
         } else if line.is_empty() {
             // Do nothing.
         } else {
-            return Err(anyhow!(format!("Unable to parse line: {line}")));
+            return Err(ParseError::whole_line(AoCYear::AOC2017, AoCDay::AOCD25, idx, &line, "a line of a Turing machine blueprint").into());
         }
     }
 
-    let mut curr_idx = 5_000_000;
+    let mut curr_idx = TAPE_LEN / 2;
     curr_state = start_state;
 
-    for _ in 0..step_count {
+    for step in 0..step_count {
+        if step.is_multiple_of(CHECK_INTERVAL) {
+            ctx.check()?;
+        }
         let tape_val = tape.get_mut(curr_idx).ok_or(anyhow!("invalid tape value"))?;
         let state = states.get(&curr_state).ok_or(anyhow!("invalid state value"))?;
 
@@ -181,18 +224,56 @@ pub fn find_solution<T: BufRead>(reader: T, _second_star: bool) -> Result<u32> {
         }
     }
 
-    let count = bytecount::count(&tape, 1);
-    u32::private_try_from(count)
+    Ok(bytecount::count(&tape, 1).into())
 }
 
 #[cfg(test)]
 mod one_star {
+    use super::find_solution;
+    use crate::context::Context;
+    use anyhow::Result;
+    use std::io::Cursor;
+
+    pub(super) const TEST_CODE: &str = r"Begin in state A.
+Perform a diagnostic checksum after 6 steps.
+
+In state A:
+  If the current value is 0:
+    - Write the value 1.
+    - Move one slot to the right.
+    - Continue with state B.
+  If the current value is 1:
+    - Write the value 0.
+    - Move one slot to the left.
+    - Continue with state B.
+
+In state B:
+  If the current value is 0:
+    - Write the value 1.
+    - Move one slot to the left.
+    - Continue with state A.
+  If the current value is 1:
+    - Write the value 1.
+    - Move one slot to the right.
+    - Continue with state A.";
+
     #[test]
-    fn solution() {}
+    fn solution() -> Result<()> {
+        assert_eq!(find_solution(Cursor::new(TEST_CODE), false, &Context::default())?, 3_usize.into());
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod two_star {
+    use super::{find_solution, one_star::TEST_CODE};
+    use crate::context::Context;
+    use crate::solver::Unsolved;
+    use std::io::Cursor;
+
     #[test]
-    fn solution() {}
+    fn solution() {
+        let error = find_solution(Cursor::new(TEST_CODE), true, &Context::default()).err();
+        assert!(error.is_some_and(|e| e.is::<Unsolved>()));
+    }
 }
@@ -0,0 +1,311 @@
+//! The assembly language of 2017 days 18 and 23
+//!
+//! A [`Program`] is a list of instructions over the registers `a` to `z`.  A [`Machine`] runs
+//! one, handing `snd`, `rcv` and `mul` to a [`Device`] that decides what they mean: sounds or
+//! messages between two programs on day 18, and a count of multiplications on day 23.
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::parse::ParseError;
+use anyhow::{Result, anyhow};
+use std::fmt;
+use std::io::BufRead;
+
+/// How many registers a machine has, `a` to `z`.
+const REGISTERS: usize = 26;
+/// How many instructions to execute between checks of the run's limits.
+const CHECK_INTERVAL: usize = 1 << 16;
+
+/// The index of the register called `name`.
+pub(crate) fn register(name: char) -> usize {
+    debug_assert!(name.is_ascii_lowercase(), "`{name}` is not a register");
+    (name as u8 - b'a') as usize
+}
+
+/// An input that is either a register or a number.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Value {
+    /// The value held in a register
+    Register(usize),
+    /// A number
+    Number(i64),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Register(reg) => write!(f, "{}", (b'a' + *reg as u8) as char),
+            Value::Number(number) => write!(f, "{number}"),
+        }
+    }
+}
+
+/// One instruction, with its register and value inputs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Instruction {
+    /// `snd X` hands the value of X to the device
+    Snd(Value),
+    /// `set X Y` sets register X to the value of Y
+    Set(usize, Value),
+    /// `add X Y` increases register X by the value of Y
+    Add(usize, Value),
+    /// `sub X Y` decreases register X by the value of Y
+    Sub(usize, Value),
+    /// `mul X Y` sets register X to the result of multiplying it by the value of Y
+    Mul(usize, Value),
+    /// `mod X Y` sets register X to the remainder of dividing it by the value of Y
+    Mod(usize, Value),
+    /// `rcv X` asks the device for a new value of register X
+    Rcv(usize),
+    /// `jgz X Y` jumps by the value of Y if the value of X is greater than zero
+    Jgz(Value, Value),
+    /// `jnz X Y` jumps by the value of Y if the value of X is not zero
+    Jnz(Value, Value),
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = |reg: &usize| Value::Register(*reg);
+        match self {
+            Instruction::Snd(x) => write!(f, "snd {x}"),
+            Instruction::Set(x, y) => write!(f, "set {} {y}", name(x)),
+            Instruction::Add(x, y) => write!(f, "add {} {y}", name(x)),
+            Instruction::Sub(x, y) => write!(f, "sub {} {y}", name(x)),
+            Instruction::Mul(x, y) => write!(f, "mul {} {y}", name(x)),
+            Instruction::Mod(x, y) => write!(f, "mod {} {y}", name(x)),
+            Instruction::Rcv(x) => write!(f, "rcv {}", name(x)),
+            Instruction::Jgz(x, y) => write!(f, "jgz {x} {y}"),
+            Instruction::Jnz(x, y) => write!(f, "jnz {x} {y}"),
+        }
+    }
+}
+
+/// A program for the tablet or the coprocessor.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct Program {
+    /// The instructions
+    pub(crate) instructions: Vec<Instruction>,
+}
+
+impl Program {
+    /// Parse one instruction per line, reporting bad lines against `day`.
+    pub(crate) fn parse<T: BufRead>(reader: T, day: AoCDay) -> Result<Self> {
+        let mut program = Program::default();
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            let mut tokens = line.split_whitespace();
+            let Some(first) = tokens.next() else {
+                continue;
+            };
+            let error = |token: &str, expected: &str| ParseError::new(AoCYear::AOC2017, day, idx, &line, token, expected);
+            let mut next = || tokens.next().unwrap_or(&line[line.len()..]);
+            let reg = |token: &str| -> Result<usize> {
+                match token.as_bytes() {
+                    [name] if name.is_ascii_lowercase() => Ok(register(*name as char)),
+                    _ => Err(error(token, "a register from `a` to `z`").into()),
+                }
+            };
+            let value = |token: &str| -> Result<Value> {
+                match token.parse() {
+                    Ok(number) => Ok(Value::Number(number)),
+                    Err(_) => reg(token)
+                        .map(Value::Register)
+                        .map_err(|_| error(token, "a register from `a` to `z` or a number").into()),
+                }
+            };
+
+            let instruction = match first {
+                "snd" => Instruction::Snd(value(next())?),
+                "set" => Instruction::Set(reg(next())?, value(next())?),
+                "add" => Instruction::Add(reg(next())?, value(next())?),
+                "sub" => Instruction::Sub(reg(next())?, value(next())?),
+                "mul" => Instruction::Mul(reg(next())?, value(next())?),
+                "mod" => Instruction::Mod(reg(next())?, value(next())?),
+                "rcv" => Instruction::Rcv(reg(next())?),
+                "jgz" => Instruction::Jgz(value(next())?, value(next())?),
+                "jnz" => Instruction::Jnz(value(next())?, value(next())?),
+                _ => return Err(error(first, "an instruction like `set` or `jgz`").into()),
+            };
+            program.instructions.push(instruction);
+        }
+        Ok(program)
+    }
+}
+
+/// What `snd`, `rcv` and `mul` mean to a machine.
+///
+/// Every hook does nothing by default, with `rcv` leaving its register as it is.
+pub(crate) trait Device {
+    /// Handle `snd` of `value`.
+    fn send(&mut self, _value: i64) {}
+
+    /// Handle `rcv` of a register holding `value`, returning its new value, or `None` to stop the
+    /// machine at the `rcv` without executing it.
+    fn receive(&mut self, value: i64) -> Option<i64> {
+        Some(value)
+    }
+
+    /// Handle an executed `mul`.
+    fn multiplied(&mut self) {}
+}
+
+/// Why a run stopped.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Stop {
+    /// The device refused a `rcv`, which is executed when the run is resumed
+    Blocked,
+    /// The instruction pointer left the program
+    Halted,
+}
+
+/// A tablet or coprocessor running a program.
+#[derive(Clone, Debug)]
+pub(crate) struct Machine<'a> {
+    /// The program being run
+    program: &'a Program,
+    /// The registers `a` to `z`
+    registers: [i64; REGISTERS],
+    /// The address of the next instruction
+    ip: i64,
+    /// How many instructions have been executed
+    executed: usize,
+}
+
+impl<'a> Machine<'a> {
+    /// A machine about to run `program` from its first instruction, with every register 0.
+    pub(crate) fn new(program: &'a Program) -> Self {
+        Self {
+            program,
+            registers: [0; REGISTERS],
+            ip: 0,
+            executed: 0,
+        }
+    }
+
+    /// Set the register called `name` to `value`.
+    pub(crate) fn set_register(&mut self, name: char, value: i64) {
+        self.registers[register(name)] = value;
+    }
+
+    /// The value of the register called `name`.
+    pub(crate) fn register(&self, name: char) -> i64 {
+        self.registers[register(name)]
+    }
+
+    /// How many instructions have been executed.
+    pub(crate) fn executed(&self) -> usize {
+        self.executed
+    }
+
+    /// The value of `value`.
+    fn value(&self, value: Value) -> i64 {
+        match value {
+            Value::Register(reg) => self.registers[reg],
+            Value::Number(number) => number,
+        }
+    }
+
+    /// Run until the instruction pointer leaves the program or `device` refuses a `rcv`.
+    ///
+    /// Every executed instruction is traced when `ctx` asks.
+    pub(crate) fn run<D: Device + ?Sized>(&mut self, device: &mut D, ctx: &Context) -> Result<Stop> {
+        loop {
+            let Some(instruction) = usize::try_from(self.ip).ok().and_then(|ip| self.program.instructions.get(ip)) else {
+                return Ok(Stop::Halted);
+            };
+            if self.executed.is_multiple_of(CHECK_INTERVAL) {
+                ctx.check()?;
+            }
+
+            let mut offset = 1;
+            match *instruction {
+                Instruction::Snd(x) => device.send(self.value(x)),
+                Instruction::Set(x, y) => self.registers[x] = self.value(y),
+                Instruction::Add(x, y) => self.registers[x] = self.registers[x].wrapping_add(self.value(y)),
+                Instruction::Sub(x, y) => self.registers[x] = self.registers[x].wrapping_sub(self.value(y)),
+                Instruction::Mul(x, y) => {
+                    self.registers[x] = self.registers[x].wrapping_mul(self.value(y));
+                    device.multiplied();
+                }
+                Instruction::Mod(x, y) => {
+                    self.registers[x] = self.registers[x]
+                        .checked_rem(self.value(y))
+                        .ok_or_else(|| anyhow!("`{instruction}` at {} divides by zero", self.ip))?;
+                }
+                Instruction::Rcv(x) => match device.receive(self.registers[x]) {
+                    Some(value) => self.registers[x] = value,
+                    None => return Ok(Stop::Blocked),
+                },
+                Instruction::Jgz(x, y) if self.value(x) > 0 => offset = self.value(y),
+                Instruction::Jnz(x, y) if self.value(x) != 0 => offset = self.value(y),
+                Instruction::Jgz(..) | Instruction::Jnz(..) => {}
+            }
+            if ctx.tracing() {
+                eprintln!("ip={} {instruction} {:?}", self.ip, self.registers);
+            }
+
+            self.ip = self.ip.saturating_add(offset);
+            self.executed += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Device, Instruction, Machine, Program, Stop, Value};
+    use crate::constants::AoCDay;
+    use crate::context::Context;
+    use anyhow::Result;
+    use std::io::Cursor;
+
+    /// Records every `snd`, and answers each `rcv` with the last message in its inbox.
+    #[derive(Default)]
+    struct Recorder {
+        inbox: Vec<i64>,
+        sent: Vec<i64>,
+        multiplied: usize,
+    }
+
+    impl Device for Recorder {
+        fn send(&mut self, value: i64) {
+            self.sent.push(value);
+        }
+
+        fn receive(&mut self, _value: i64) -> Option<i64> {
+            self.inbox.pop()
+        }
+
+        fn multiplied(&mut self) {
+            self.multiplied += 1;
+        }
+    }
+
+    #[test]
+    fn run() -> Result<()> {
+        let program = Program::parse(Cursor::new("set a 2\nmul a a\nsnd a\nrcv b\nsub a 2\njnz a -1\nsnd b\njgz 1 7"), AoCDay::AOCD18)?;
+        assert_eq!(program.instructions[5], Instruction::Jnz(Value::Register(0), Value::Number(-1)));
+        let mut machine = Machine::new(&program);
+        let mut recorder = Recorder::default();
+        assert_eq!(machine.run(&mut recorder, &Context::default())?, Stop::Blocked);
+        assert_eq!(machine.executed(), 3);
+
+        // The run picks up at the refused `rcv`.
+        recorder.inbox.push(7);
+        assert_eq!(machine.run(&mut recorder, &Context::default())?, Stop::Halted);
+        assert_eq!((machine.executed(), recorder.sent, recorder.multiplied), (10, vec![4, 7], 1));
+        Ok(())
+    }
+
+    #[test]
+    fn bad_line() {
+        let error = Program::parse(Cursor::new("set a 1\nset 1 a"), AoCDay::AOCD18).err();
+        assert_eq!(
+            error.map(|e| e.to_string()),
+            Some("2017 day18 line 2, column 5: expected a register from `a` to `z`, found `1`".to_string())
+        );
+        let error = Program::parse(Cursor::new("jgz a"), AoCDay::AOCD18).err();
+        assert_eq!(
+            error.map(|e| e.to_string()),
+            Some("2017 day18 line 1, column 6: expected a register from `a` to `z` or a number, found the end of the line".to_string())
+        );
+    }
+}
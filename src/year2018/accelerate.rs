@@ -0,0 +1,632 @@
+//! Loop acceleration for ElfCode programs
+//!
+//! The programs of 2018 days 19 and 21 spend nearly all of their time in a few tight loops.
+//! An [`Accelerator`] skips those loops by working out their effect directly, either because
+//! it recognizes the loop as a known idiom, or by summarizing one pass through the loop as
+//! arithmetic on the registers and solving for the pass where that summary stops holding.
+use crate::year2018::elfcode::{Instruction, OpCode, Operand, Program};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Add, Sub};
+
+/// The most instructions a single pass through a loop may take and still be summarized.
+const MAX_PASS: usize = 64;
+/// The longest a loop header is left alone after a failed summary, in arrivals.
+const MAX_BACKOFF: u32 = 1 << 12;
+
+/// A stretch of execution the accelerator skipped.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Skip {
+    /// The divisor sum loop at `header` was replaced by summing the divisors of `n`
+    DivisorSum { header: usize, n: usize, instructions: usize },
+    /// `passes` passes through the loop at `header` were applied at once
+    Passes { header: usize, passes: usize, instructions: usize },
+}
+
+impl Skip {
+    /// How many instructions the skipped stretch would have executed.
+    pub(crate) fn instructions(&self) -> usize {
+        match *self {
+            Skip::DivisorSum { instructions, .. } | Skip::Passes { instructions, .. } => instructions,
+        }
+    }
+}
+
+impl fmt::Display for Skip {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Skip::DivisorSum { header, n, instructions } => {
+                write!(
+                    f,
+                    "Summed the divisors of {n} for the loop at ip={header}, skipping {instructions} instructions"
+                )
+            }
+            Skip::Passes { header, passes, instructions } => {
+                write!(f, "Skipped {passes} passes through the loop at ip={header}, {instructions} instructions")
+            }
+        }
+    }
+}
+
+/// The registers of a recognized divisor sum loop.
+///
+/// ```text
+///  h     a = 1
+///  h+1   b = 1
+///  h+2   t = a * b
+///  h+3   t = t == n
+///  h+4   if t goto h+6
+///  h+5   goto h+7
+///  h+6   s = s + a
+///  h+7   b = b + 1
+///  h+8   t = b > n
+///  h+9   if t goto h+11
+///  h+10  goto h+2
+///  h+11  a = a + 1
+///  h+12  t = a > n
+///  h+13  if t goto h+15
+///  h+14  goto h+1
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct DivisorSum {
+    /// The outer counter
+    a: usize,
+    /// The inner counter
+    b: usize,
+    /// The scratch register for products and comparisons
+    t: usize,
+    /// The number whose divisors are summed
+    n: usize,
+    /// The running sum
+    s: usize,
+}
+
+/// The length of a divisor sum loop, in instructions.
+const DIVISOR_SUM_LEN: usize = 15;
+
+impl DivisorSum {
+    /// The divisor sum loop starting at `h`, if there is one.
+    fn find(program: &Program, h: usize) -> Option<Self> {
+        let ip = program.ip_register?;
+        let ins = program.instructions.get(h..h + DIVISOR_SUM_LEN)?;
+        let is = |idx: usize, opcode: OpCode, a: usize, b: Option<usize>, c: usize| {
+            let [ia, ib, ic] = ins[idx].args;
+            ins[idx].opcode == opcode && ia == a && b.is_none_or(|b| ib == b) && ic == c
+        };
+        // The other input of a commutative operation on `x`, if it writes `c`.
+        let other = |idx: usize, opcode: OpCode, x: usize, c: usize| {
+            let [ia, ib, ic] = ins[idx].args;
+            (ins[idx].opcode == opcode && ic == c && (ia == x || ib == x)).then_some(if ia == x { ib } else { ia })
+        };
+        let target = |idx: usize| (ins[idx].opcode == OpCode::Seti && ins[idx].args[2] == ip).then_some(ins[idx].args[0]);
+
+        let [_, _, a] = ins[0].args;
+        let [_, _, b] = ins[1].args;
+        let [_, _, t] = ins[2].args;
+        let n = other(3, OpCode::Eqrr, t, t)?;
+        let s = ins[6].args[2];
+        let found = is(0, OpCode::Seti, 1, None, a)
+            && is(1, OpCode::Seti, 1, None, b)
+            && other(2, OpCode::Mulr, a, t) == Some(b)
+            && other(4, OpCode::Addr, t, ip) == Some(ip)
+            && is(5, OpCode::Addi, ip, Some(1), ip)
+            && other(6, OpCode::Addr, a, s) == Some(s)
+            && is(7, OpCode::Addi, b, Some(1), b)
+            && is(8, OpCode::Gtrr, b, Some(n), t)
+            && other(9, OpCode::Addr, t, ip) == Some(ip)
+            && target(10) == Some(h + 1)
+            && is(11, OpCode::Addi, a, Some(1), a)
+            && is(12, OpCode::Gtrr, a, Some(n), t)
+            && other(13, OpCode::Addr, t, ip) == Some(ip)
+            && target(14) == Some(h);
+
+        let mut registers = [a, b, t, n, s, ip];
+        registers.sort_unstable();
+        let distinct = registers.windows(2).all(|pair| pair[0] != pair[1]);
+        (found && distinct).then_some(Self { a, b, t, n, s })
+    }
+
+    /// Run the loop from its start at `h`, leaving `ip` just past its end.
+    fn apply(&self, h: usize, registers: &mut [usize], ip: &mut usize, ip_register: usize) -> Option<Skip> {
+        let n = registers[self.n];
+        if n == 0 {
+            return None;
+        }
+        let sum = (1..)
+            .take_while(|d| d * d <= n)
+            .filter(|d| n.is_multiple_of(*d))
+            .map(|d| if d * d == n { d } else { d + n / d })
+            .sum::<usize>();
+
+        registers[self.s] = registers[self.s].checked_add(sum)?;
+        registers[self.a] = n + 1;
+        registers[self.b] = n + 1;
+        registers[self.t] = 1;
+        registers[ip_register] = h + DIVISOR_SUM_LEN - 1;
+        *ip = h + DIVISOR_SUM_LEN;
+
+        // Each of the n passes of the outer loop runs 8n - 1 instructions of the inner loop
+        // and 4 of its own, less the final jump back, plus the very first instruction.
+        Some(Skip::DivisorSum {
+            header: h,
+            n,
+            instructions: 8 * n * n + 4 * n,
+        })
+    }
+}
+
+/// A value as a sum of multiples of the registers at the start of a pass, plus a constant.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Linear<const N: usize> {
+    /// The constant term
+    constant: i128,
+    /// The multiple of each register
+    coefficients: [i128; N],
+}
+
+impl<const N: usize> Linear<N> {
+    /// The constant `value`.
+    fn constant(value: usize) -> Self {
+        Self {
+            constant: value as i128,
+            coefficients: [0; N],
+        }
+    }
+
+    /// The value register `reg` held at the start of the pass.
+    fn register(reg: usize) -> Self {
+        let mut coefficients = [0; N];
+        coefficients[reg] = 1;
+        Self { constant: 0, coefficients }
+    }
+
+    /// The constant value, if this is one.
+    fn as_constant(&self) -> Option<i128> {
+        self.coefficients.iter().all(|c| *c == 0).then_some(self.constant)
+    }
+
+    /// This value multiplied by `factor`.
+    fn scale(&self, factor: i128) -> Option<Self> {
+        let mut coefficients = [0; N];
+        for (scaled, c) in coefficients.iter_mut().zip(self.coefficients) {
+            *scaled = c.checked_mul(factor)?;
+        }
+        Some(Self {
+            constant: self.constant.checked_mul(factor)?,
+            coefficients,
+        })
+    }
+
+    /// The value, given the registers at the start of the pass.
+    fn eval(&self, registers: &[i128; N]) -> Option<i128> {
+        self.coefficients
+            .iter()
+            .zip(registers)
+            .try_fold(self.constant, |acc, (c, r)| acc.checked_add(c.checked_mul(*r)?))
+    }
+
+    /// Whether the value only depends on the registers in `allowed`.
+    fn depends_only_on(&self, allowed: &[bool; N]) -> bool {
+        self.coefficients.iter().zip(allowed).all(|(c, allowed)| *c == 0 || *allowed)
+    }
+}
+
+impl<const N: usize> Add for Linear<N> {
+    type Output = Option<Linear<N>>;
+
+    fn add(self, other: Linear<N>) -> Option<Linear<N>> {
+        let mut coefficients = [0; N];
+        for ((sum, a), b) in coefficients.iter_mut().zip(self.coefficients).zip(other.coefficients) {
+            *sum = a.checked_add(b)?;
+        }
+        Some(Linear {
+            constant: self.constant.checked_add(other.constant)?,
+            coefficients,
+        })
+    }
+}
+
+impl<const N: usize> Sub for Linear<N> {
+    type Output = Option<Linear<N>>;
+
+    fn sub(self, other: Linear<N>) -> Option<Linear<N>> {
+        self + other.scale(-1)?
+    }
+}
+
+/// A comparison made during a pass, as `difference > 0` or `difference == 0`.
+#[derive(Clone, Debug)]
+struct Condition<const N: usize> {
+    /// The first input less the second
+    difference: Linear<N>,
+    /// Whether the comparison is an equality rather than a greater-than
+    equality: bool,
+    /// How the comparison came out on the summarized pass
+    held: bool,
+}
+
+impl<const N: usize> Condition<N> {
+    /// The first pass, counting the summarized one as 0, on which the comparison comes out
+    /// differently, given the difference on pass 0 and its change from pass to pass.
+    fn first_flip(&self, start: i128, step: i128) -> Option<i128> {
+        match (self.equality, self.held) {
+            (true, true) => (step != 0).then_some(1),
+            (true, false) => (step != 0 && (-start) % step == 0 && (-start) / step > 0).then(|| (-start) / step),
+            // Flips once start + k * step <= 0.
+            (false, true) => (step < 0).then(|| (start + (-step) - 1) / (-step)),
+            // Flips once start + k * step > 0.
+            (false, false) => (step > 0).then(|| (-start) / step + 1),
+        }
+    }
+}
+
+/// One pass through a loop, summarized as arithmetic on the registers it starts with.
+#[derive(Clone, Debug)]
+struct Pass<const N: usize> {
+    /// The instructions executed
+    len: usize,
+    /// What each register written during the pass holds at its end
+    written: [Option<Linear<N>>; N],
+    /// The comparisons whose outcome picked the path through the pass
+    conditions: Vec<Condition<N>>,
+}
+
+impl<const N: usize> Pass<N> {
+    /// Follow one pass through the loop at `header` from `registers`, unless it runs past
+    /// `MAX_PASS` instructions, leaves the program, or reaches a breakpoint first.
+    fn path(program: &Program, header: usize, registers: &[usize; N], breakpoints: &[usize]) -> Option<Vec<(usize, usize)>> {
+        if breakpoints.contains(&header) {
+            return None;
+        }
+        let mut registers = *registers;
+        let mut ip = header;
+        let mut path = Vec::new();
+        while path.is_empty() || ip != header {
+            if path.len() == MAX_PASS || (!path.is_empty() && breakpoints.contains(&ip)) {
+                return None;
+            }
+            let instruction = program.instructions.get(ip)?;
+            if let Some(reg) = program.ip_register {
+                registers[reg] = ip;
+            }
+            let [a, b, c] = instruction.args;
+            let (kind_a, kind_b) = instruction.opcode.operands();
+            let in_range = |kind, value| kind != Operand::Register || value < N;
+            if !in_range(kind_a, a) || !in_range(kind_b, b) || c >= N {
+                return None;
+            }
+            instruction.opcode.execute(&mut registers, instruction.args);
+            path.push((ip, registers[c]));
+            ip = program.ip_register.map_or(ip, |reg| registers[reg]).checked_add(1)?;
+        }
+        Some(path)
+    }
+
+    /// Summarize the pass along `path`, which starts from `registers`, if every value written
+    /// during it is linear in the registers it starts with.
+    ///
+    /// Registers the pass never writes keep their current values throughout, so they are
+    /// treated as constants, which lets a product of a counter and one of them stay linear.
+    fn summarize(program: &Program, path: &[(usize, usize)], registers: &[usize; N]) -> Option<Self> {
+        let mut changes = [false; N];
+        for &(ip, _) in path {
+            changes[program.instructions[ip].args[2]] = true;
+        }
+        if let Some(reg) = program.ip_register {
+            changes[reg] = true;
+        }
+        let mut values: [Linear<N>; N] = std::array::from_fn(|reg| {
+            if changes[reg] {
+                Linear::register(reg)
+            } else {
+                Linear::constant(registers[reg])
+            }
+        });
+        let mut written = [false; N];
+        let mut conditions = Vec::new();
+
+        for &(ip, result) in path {
+            let Instruction { opcode, args: [a, b, c] } = program.instructions[ip];
+            if let Some(reg) = program.ip_register {
+                values[reg] = Linear::constant(ip);
+                written[reg] = true;
+            }
+            let (kind_a, kind_b) = opcode.operands();
+            let input = |kind, value: usize| match kind {
+                Operand::Register => values[value],
+                _ => Linear::constant(value),
+            };
+            let (x, y) = (input(kind_a, a), input(kind_b, b));
+
+            let value = match opcode {
+                OpCode::Addr | OpCode::Addi => (x + y)?,
+                OpCode::Mulr | OpCode::Muli => match (x.as_constant(), y.as_constant()) {
+                    (Some(factor), _) => y.scale(factor)?,
+                    (_, Some(factor)) => x.scale(factor)?,
+                    _ => return None,
+                },
+                OpCode::Banr | OpCode::Bani | OpCode::Borr | OpCode::Bori => {
+                    // Bitwise operations only fold when both inputs are already known.
+                    let (x, y) = (usize::try_from(x.as_constant()?).ok()?, usize::try_from(y.as_constant()?).ok()?);
+                    let value = if matches!(opcode, OpCode::Banr | OpCode::Bani) { x & y } else { x | y };
+                    Linear::constant(value)
+                }
+                OpCode::Setr | OpCode::Seti => x,
+                OpCode::Gtir | OpCode::Gtri | OpCode::Gtrr | OpCode::Eqir | OpCode::Eqri | OpCode::Eqrr => {
+                    let difference = (x - y)?;
+                    if difference.as_constant().is_none() {
+                        let equality = matches!(opcode, OpCode::Eqir | OpCode::Eqri | OpCode::Eqrr);
+                        conditions.push(Condition {
+                            difference,
+                            equality,
+                            held: result == 1,
+                        });
+                    }
+                    Linear::constant(result)
+                }
+            };
+            // A jump computed from a changing register may take another path on the next pass.
+            if program.ip_register == Some(c) && value.as_constant().is_none() {
+                return None;
+            }
+            values[c] = value;
+            written[c] = true;
+        }
+
+        let written = std::array::from_fn(|reg| written[reg].then_some(values[reg]));
+        Some(Self {
+            len: path.len(),
+            written,
+            conditions,
+        })
+    }
+
+    /// Apply as many passes as can be taken along the same path from `registers` at once,
+    /// stopping one pass short of the first whose comparisons would come out differently.
+    ///
+    /// Registers that go up by a constant on every pass are counters; every other register
+    /// written must be recomputed from the counters alone on each pass, and every comparison
+    /// must look only at counters, or the loop is left alone.  Returns the passes applied.
+    fn skip(&self, registers: &mut [usize; N]) -> Option<usize> {
+        let steps: [Option<i128>; N] = std::array::from_fn(|reg| match self.written[reg] {
+            Some(value) => (value - Linear::register(reg))?.as_constant(),
+            None => Some(0),
+        });
+        let counters = steps.map(|step| step.is_some());
+        let steps = steps.map(Option::unwrap_or_default);
+        if !self.written.iter().flatten().all(|value| value.depends_only_on(&counters))
+            || !self.conditions.iter().all(|condition| condition.difference.depends_only_on(&counters))
+        {
+            return None;
+        }
+
+        let start = registers.map(|r| r as i128);
+        let mut flip = None;
+        for condition in &self.conditions {
+            let step = condition
+                .difference
+                .coefficients
+                .iter()
+                .zip(steps)
+                .try_fold(0_i128, |acc, (c, s)| acc.checked_add(c.checked_mul(s)?))?;
+            if let Some(k) = condition.first_flip(condition.difference.eval(&start)?, step) {
+                flip = Some(flip.map_or(k, |flip: i128| flip.min(k)));
+            }
+        }
+        // A loop that never flips a comparison never ends; leave that to the interpreter.
+        let passes = flip? - 1;
+        if passes < 1 {
+            return None;
+        }
+
+        let at = |passes: i128| -> Option<[i128; N]> {
+            let mut values = [0; N];
+            for ((value, r), s) in values.iter_mut().zip(start).zip(steps) {
+                *value = r.checked_add(s.checked_mul(passes)?)?;
+            }
+            Some(values)
+        };
+        let before_last = at(passes - 1)?;
+        let mut next = at(passes)?;
+        for (reg, value) in self.written.iter().enumerate() {
+            if let Some(value) = value
+                && value != &Linear::register(reg)
+            {
+                next[reg] = value.eval(&before_last)?;
+            }
+        }
+        for (register, value) in registers.iter_mut().zip(next) {
+            *register = usize::try_from(value).ok()?;
+        }
+        usize::try_from(passes).ok()
+    }
+}
+
+/// When a loop header is next worth another attempt at a summary.
+#[derive(Clone, Copy, Debug, Default)]
+struct Backoff {
+    /// Arrivals to let pass before trying again
+    wait: u32,
+    /// The wait after the next failure
+    next: u32,
+}
+
+/// Skips the hot loops of a program as it runs.
+#[derive(Clone, Debug)]
+pub(crate) struct Accelerator {
+    /// The divisor sum loop starting at each address, if any
+    divisor_sums: Vec<Option<DivisorSum>>,
+    /// The backoff for each loop header a summary has failed at
+    backoff: HashMap<usize, Backoff>,
+}
+
+impl Accelerator {
+    /// An accelerator for `program`.
+    pub(crate) fn new(program: &Program) -> Self {
+        Self {
+            divisor_sums: (0..program.instructions.len()).map(|h| DivisorSum::find(program, h)).collect(),
+            backoff: HashMap::new(),
+        }
+    }
+
+    /// Skip ahead from `ip`, if the machine is at the start of a loop that can be skipped
+    /// without passing any of `breakpoints`.
+    ///
+    /// Idioms are recognized wherever they start.  Other loops are only summarized when
+    /// `jumped_back` says the last instruction jumped back to `ip`, the loop's header.
+    pub(crate) fn accelerate<const N: usize>(
+        &mut self,
+        program: &Program,
+        registers: &mut [usize; N],
+        ip: &mut usize,
+        jumped_back: bool,
+        breakpoints: &[usize],
+    ) -> Option<Skip> {
+        if let Some(Some(divisor_sum)) = self.divisor_sums.get(*ip)
+            && let Some(ip_register) = program.ip_register
+            && !breakpoints.iter().any(|b| (*ip..*ip + DIVISOR_SUM_LEN).contains(b))
+        {
+            return divisor_sum.apply(*ip, registers, ip, ip_register);
+        }
+        if !jumped_back {
+            return None;
+        }
+
+        let backoff = self.backoff.entry(*ip).or_default();
+        if backoff.wait > 0 {
+            backoff.wait -= 1;
+            return None;
+        }
+        let header = *ip;
+        let skipped = Pass::path(program, header, registers, breakpoints)
+            .and_then(|path| Pass::summarize(program, &path, registers))
+            .and_then(|pass| Some((pass.skip(registers)?, pass.len)));
+        match skipped {
+            Some((passes, len)) => {
+                *backoff = Backoff::default();
+                Some(Skip::Passes {
+                    header,
+                    passes,
+                    instructions: passes * len,
+                })
+            }
+            None => {
+                backoff.wait = backoff.next;
+                backoff.next = (backoff.next * 2).clamp(1, MAX_BACKOFF);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Accelerator, Skip};
+    use crate::constants::AoCDay;
+    use crate::context::Context;
+    use crate::limits::LimitExceeded;
+    use crate::year2018::elfcode::{Machine, Program};
+    use anyhow::Result;
+    use std::io::Cursor;
+    use std::time::Duration;
+
+    /// Run `code` from `registers` with and without acceleration, returning both machines'
+    /// registers and instruction counts.
+    fn both_ways(code: &str, registers: [usize; 6]) -> Result<[([usize; 6], usize); 2]> {
+        let program = Program::parse(Cursor::new(code), AoCDay::AOCD19)?;
+        let mut plain = Machine::<6>::new(&program)?;
+        let mut fast = Machine::<6>::new(&program)?.accelerated();
+        plain.registers = registers;
+        fast.registers = registers;
+        plain.run(&Context::default())?;
+        fast.run(&Context::default())?;
+        Ok([(plain.registers, plain.executed()), (fast.registers, fast.executed())])
+    }
+
+    #[test]
+    fn divisor_sum() -> Result<()> {
+        let code = "#ip 4\nseti 1 3 3\nseti 1 4 2\nmulr 3 2 1\neqrr 1 5 1\naddr 1 4 4\naddi 4 1 4\naddr 3 0 0\naddi 2 1 2\n\
+                    gtrr 2 5 1\naddr 4 1 4\nseti 1 2 4\naddi 3 1 3\ngtrr 3 5 1\naddr 1 4 4\nseti 0 6 4";
+        let [plain, fast] = both_ways(code, [0, 0, 0, 0, 0, 36])?;
+        assert_eq!(plain, fast);
+        assert_eq!(fast.0[0], 91);
+
+        let program = Program::parse(Cursor::new(code), AoCDay::AOCD19)?;
+        let (mut registers, mut ip) = ([0, 0, 0, 0, 0, 36], 0);
+        let skip = Accelerator::new(&program).accelerate(&program, &mut registers, &mut ip, false, &[]);
+        assert_eq!(
+            skip,
+            Some(Skip::DivisorSum {
+                header: 0,
+                n: 36,
+                instructions: fast.1
+            })
+        );
+        assert_eq!(Accelerator::new(&program).accelerate(&program, &mut registers, &mut 0, false, &[5]), None);
+        Ok(())
+    }
+
+    #[test]
+    fn division_loop() -> Result<()> {
+        // r2 = r4 / 256, by counting up until (r5 + 1) * 256 exceeds r4.
+        let code = "#ip 1\nseti 0 2 5\naddi 5 1 2\nmuli 2 256 2\ngtrr 2 4 2\naddr 2 1 1\naddi 1 1 1\nseti 8 3 1\naddi 5 1 5\n\
+                    seti 0 1 1\nsetr 5 2 2";
+        for value in [0, 255, 256, 65_535, 1_000_000] {
+            let [plain, fast] = both_ways(code, [0, 0, 0, 0, value, 0])?;
+            assert_eq!(plain, fast);
+            assert_eq!(fast.0[2], value / 256);
+        }
+
+        let program = Program::parse(Cursor::new(code), AoCDay::AOCD19)?;
+        let (mut registers, mut ip) = ([0, 8, 0, 0, 1_000_000, 0], 1);
+        let skip = Accelerator::new(&program).accelerate(&program, &mut registers, &mut ip, true, &[]);
+        assert_eq!(
+            skip,
+            Some(Skip::Passes {
+                header: 1,
+                passes: 3905,
+                instructions: 3905 * 7
+            })
+        );
+        assert_eq!((registers[5], ip), (3905, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn skipped_runs_check_limits() -> Result<()> {
+        // The division loop again, restarted forever from r5 = 6.  Each restart runs 262,144 instructions, all
+        // but a few of them skipped, and none of those few lands on a multiple of the check interval.
+        let code = "#ip 1\nseti 0 2 5\naddi 5 1 2\nmuli 2 256 2\ngtrr 2 4 2\naddr 2 1 1\naddi 1 1 1\nseti 8 3 1\naddi 5 1 5\n\
+                    seti 0 1 1\nsetr 5 2 2\nseti 6 0 5\nseti 0 0 1";
+        let program = Program::parse(Cursor::new(code), AoCDay::AOCD19)?;
+        let mut machine = Machine::<6>::new(&program)?.accelerated();
+        machine.registers[4] = 9_588_224;
+        let timeout = Duration::from_millis(50);
+        let ctx = Context::default().with_limits(Some(timeout), None).started();
+        let error = machine.run(&ctx).err().map(|e| e.downcast::<LimitExceeded>().ok());
+        assert_eq!(error, Some(Some(LimitExceeded::TimedOut(timeout))));
+        Ok(())
+    }
+
+    #[test]
+    fn counted_match() -> Result<()> {
+        // r0 counts the r2 up to r3 where 7 * r2 is r4, so the equality flips partway through.
+        let code = "#ip 5\nseti 0 0 2\nmuli 2 7 1\neqrr 1 4 1\naddr 1 5 5\naddi 5 1 5\naddi 0 1 0\naddi 2 1 2\ngtrr 2 3 1\n\
+                    addr 5 1 5\nseti 0 0 5";
+        let [plain, fast] = both_ways(code, [0, 0, 0, 1000, 700, 0])?;
+        assert_eq!(plain, fast);
+        assert_eq!((fast.0[0], fast.0[2]), (1, 1001));
+        Ok(())
+    }
+
+    #[test]
+    fn computed_jump() -> Result<()> {
+        // The header jumps by the counter r5 into a table of pads, two of which go through the `addi 0 1 0`.
+        let code = "#ip 1\nseti 0 0 5\naddr 5 1 1\nseti 9 0 1\nseti 10 0 1\nseti 10 0 1\nseti 9 0 1\nseti 10 0 1\nseti 10 0 1\n\
+                    seti 10 0 1\nseti 10 0 1\naddi 0 1 0\naddi 5 1 5\ngtri 5 7 3\naddr 3 1 1\nseti 0 0 1";
+        let [plain, fast] = both_ways(code, [0; 6])?;
+        assert_eq!(plain, fast);
+        assert_eq!(fast.0[0], 2);
+        Ok(())
+    }
+}
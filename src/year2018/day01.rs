@@ -1,21 +1,53 @@
 //! Advent of Code - Day 1 "Chronal Calibration" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::{self, ParseError};
+use crate::solver::Solver;
 use anyhow::Result;
 use std::collections::HashSet;
 use std::io::BufRead;
 
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+/// Day 1 "Chronal Calibration"
+pub(crate) struct Day01;
+
+impl Solver for Day01 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD01
+    }
+
+    fn title(&self) -> &'static str {
+        "Chronal Calibration"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let mut acc_vec = Vec::new();
 
-    for line in reader.lines().map_while(Result::ok) {
-        let chars: Vec<char> = line.chars().collect();
-        let num_str: String = chars[1..].iter().collect();
-        let num_to_add = num_str.parse::<i32>()?;
-        let tuple = (chars[0] == '+', num_to_add);
-        acc_vec.push(tuple);
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let (add, num_str) = match line.as_bytes().first() {
+            Some(b'+') => (true, &line[1..]),
+            Some(b'-') => (false, &line[1..]),
+            _ => {
+                let sign = &line[..line.chars().next().map_or(0, char::len_utf8)];
+                return Err(ParseError::new(AoCYear::AOC2018, AoCDay::AOCD01, idx, &line, sign, "`+` or `-`").into());
+            }
+        };
+        acc_vec.push((add, parse::number(AoCYear::AOC2018, AoCDay::AOCD01, idx, &line, num_str)?));
     }
-    let acc = val(&acc_vec, second_star)?;
-    println!("Result: {acc}");
-    Ok(0)
+    Ok(val(&acc_vec, second_star)?.into())
 }
 
 /// Calculate the 'inverse captcha' value for a byte array.
@@ -44,8 +76,9 @@ fn val(acc_vec: &[(bool, i32)], second_star: bool) -> Result<i32> {
 
 #[cfg(test)]
 mod one_star {
-    use super::val;
+    use super::{find_solution, val};
     use anyhow::Result;
+    use std::io::Cursor;
 
     #[test]
     fn solution() -> Result<()> {
@@ -54,6 +87,20 @@ mod one_star {
         assert_eq!(val(&[(false, 1), (false, 2), (false, 3)], false)?, -6);
         Ok(())
     }
+
+    #[test]
+    fn bad_line() {
+        let error = find_solution(Cursor::new("+1\n\n-2"), false).err();
+        assert_eq!(
+            error.map(|e| e.to_string()),
+            Some("2018 day01 line 2, column 1: expected `+` or `-`, found the end of the line".to_string())
+        );
+        let error = find_solution(Cursor::new("+1\n-x"), false).err();
+        assert_eq!(
+            error.map(|e| e.to_string()),
+            Some("2018 day01 line 2, column 2: expected a number, found `x`".to_string())
+        );
+    }
 }
 
 #[cfg(test)]
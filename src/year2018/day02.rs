@@ -1,14 +1,43 @@
 //! Advent of Code - Day 2 "Inventory Management System" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::solver::Solver;
 use anyhow::Result;
 use std::collections::HashMap;
 use std::io::BufRead;
 
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+/// Day 2 "Inventory Management System"
+pub(crate) struct Day02;
+
+impl Solver for Day02 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD02
+    }
+
+    fn title(&self) -> &'static str {
+        "Inventory Management System"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let mut twos = 0;
     let mut threes = 0;
     let mut all_ids = Vec::new();
 
-    for line in reader.lines().map_while(Result::ok) {
+    for line in reader.lines() {
+        let line = line?;
         if second_star {
             all_ids.push(line);
         } else {
@@ -23,10 +52,10 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
     }
 
     if second_star {
-        println!("{}", find_closest(&mut all_ids));
+        Ok(find_closest(&mut all_ids).into())
+    } else {
+        Ok((twos * threes).into())
     }
-
-    Ok(twos * threes)
 }
 
 fn has_two_or_three(line: &str) -> (bool, bool) {
@@ -1,4 +1,8 @@
 //! Advent of Code - Day 3 "No Matter How You Slice It" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::{self, ParseError};
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
 use ndarray::Array2;
 use regex::Regex;
@@ -6,6 +10,31 @@ use std::collections::BTreeMap;
 use std::fmt;
 use std::io::BufRead;
 
+/// Day 3 "No Matter How You Slice It"
+pub(crate) struct Day03;
+
+impl Solver for Day03 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD03
+    }
+
+    fn title(&self) -> &'static str {
+        "No Matter How You Slice It"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 struct Point {
     x: usize,
@@ -30,28 +59,31 @@ impl fmt::Display for Rectangle {
     }
 }
 
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    let line_re = Regex::new(r"#(\d+) @ (\d+),(\d+): (\d+)x(\d+)")?;
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
+    let line_re = Regex::new(r"^#(\d+) @ (\d+),(\d+): (\d+)x(\d+)$")?;
     let mut rectangles = BTreeMap::new();
 
-    for line in reader.lines().map_while(Result::ok) {
-        for cap in line_re.captures_iter(&line) {
-            let id = (cap[1]).parse::<usize>()?;
-            let l = (cap[2]).parse::<usize>()?;
-            let t = (cap[3]).parse::<usize>()?;
-            let w = (cap[4]).parse::<usize>()?;
-            let h = (cap[5]).parse::<usize>()?;
-            let top_left = Point { x: l, y: t };
-            let bottom_right = Point { x: l + w - 1, y: t + h - 1 };
-            let rectangle = Rectangle { top_left, bottom_right };
-            rectangles.insert(id, rectangle);
-        }
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let cap = line_re
+            .captures(&line)
+            .ok_or_else(|| ParseError::whole_line(AoCYear::AOC2018, AoCDay::AOCD03, idx, &line, "a claim like `#1 @ 1,3: 4x4`"))?;
+        let number = |i: usize| parse::number::<usize>(AoCYear::AOC2018, AoCDay::AOCD03, idx, &line, &cap[i]);
+        let size = |i: usize| match number(i)? {
+            0 => Err(ParseError::new(AoCYear::AOC2018, AoCDay::AOCD03, idx, &line, &cap[i], "a size of at least 1")),
+            size => Ok(size),
+        };
+        let (id, l, t, w, h) = (number(1)?, number(2)?, number(3)?, size(4)?, size(5)?);
+        let top_left = Point { x: l, y: t };
+        let bottom_right = Point { x: l + w - 1, y: t + h - 1 };
+        let rectangle = Rectangle { top_left, bottom_right };
+        rectangles.insert(id, rectangle);
     }
 
     if second_star {
-        Ok(find_non_overlaps(&rectangles)? as u32)
+        Ok(find_non_overlaps(&rectangles)?.into())
     } else {
-        Ok(check_points(&rectangles, 1000, 1000)?)
+        Ok(check_points(&rectangles, 1000, 1000)?.into())
     }
 }
 
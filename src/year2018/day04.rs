@@ -1,32 +1,62 @@
 //! Advent of Code - Day 4 "Repose Record" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::{self, ParseError};
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
 use regex::Regex;
 use std::collections::BTreeMap;
 use std::io::BufRead;
-use time::{Month, OffsetDateTime};
+use time::{Date, Month};
 
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    let line_re = Regex::new(r"\[(\d{4})-(\d{2})-(\d{2}) (\d{2}):(\d{2})\] (.*)")?;
-    let guard_re = Regex::new(r"Guard #(\d+) begins shift")?;
+/// Day 4 "Repose Record"
+pub(crate) struct Day04;
+
+impl Solver for Day04 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD04
+    }
+
+    fn title(&self) -> &'static str {
+        "Repose Record"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
+    let line_re = Regex::new(r"^\[((\d{4})-(\d{2})-(\d{2}) (\d{2}):(\d{2}))\] (.*)$")?;
+    let guard_re = Regex::new(r"^Guard #(\d+) begins shift$")?;
     let mut sorted_events = BTreeMap::new();
 
-    for line in reader.lines().map_while(Result::ok) {
-        for cap in line_re.captures_iter(&line) {
-            let y = (cap[1]).parse::<i32>()?;
-            let mon = (cap[2]).parse::<u8>()?;
-            let d = (cap[3]).parse::<u8>()?;
-            let h = (cap[4]).parse::<u8>()?;
-            let m = (cap[5]).parse::<u8>()?;
-            let rest = &cap[6];
-            let dt = OffsetDateTime::now_utc()
-                .replace_day(d)?
-                .replace_month(Month::try_from(mon)?)?
-                .replace_year(y)?
-                .replace_hour(h)?
-                .replace_minute(m)?
-                .replace_second(0)?;
-            sorted_events.insert(dt, rest.to_string());
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let error = |token: &str, expected: &str| ParseError::new(AoCYear::AOC2018, AoCDay::AOCD04, idx, &line, token, expected);
+        let cap = line_re
+            .captures(&line)
+            .ok_or_else(|| ParseError::whole_line(AoCYear::AOC2018, AoCDay::AOCD04, idx, &line, "a record like `[1518-11-01 00:05] falls asleep`"))?;
+        let y = parse::number(AoCYear::AOC2018, AoCDay::AOCD04, idx, &line, &cap[2])?;
+        let number = |i: usize| parse::number::<u8>(AoCYear::AOC2018, AoCDay::AOCD04, idx, &line, &cap[i]);
+        let (mon, d, h, m) = (number(3)?, number(4)?, number(5)?, number(6)?);
+        let dt = Month::try_from(mon)
+            .and_then(|mon| Date::from_calendar_date(y, mon, d))
+            .and_then(|date| date.with_hms(h, m, 0))
+            .map_err(|_| error(&cap[1], "a date and time like `1518-11-01 00:05`"))?;
+        let rest = &cap[7];
+        if !(rest == "falls asleep" || rest == "wakes up" || guard_re.is_match(rest)) {
+            return Err(error(rest, "`falls asleep`, `wakes up` or `Guard #10 begins shift`").into());
         }
+        sorted_events.insert(dt, rest.to_string());
     }
 
     let mut guards_napping: BTreeMap<u32, BTreeMap<u8, u32>> = BTreeMap::new();
@@ -82,7 +112,7 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
         }
     }
 
-    Ok(max_id * u32::from(max_minute_asleep))
+    Ok((max_id * u32::from(max_minute_asleep)).into())
 }
 
 #[cfg(test)]
@@ -107,12 +137,13 @@ const TEST_LINES: &str = r#"[1518-11-01 00:00] Guard #10 begins shift
 #[cfg(test)]
 mod one_star {
     use super::{TEST_LINES, find_solution};
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::io::Cursor;
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_solution(Cursor::new(TEST_LINES), false)?, 240);
+        assert_eq!(find_solution(Cursor::new(TEST_LINES), false)?, Answer::Unsigned(240));
         Ok(())
     }
 }
@@ -120,12 +151,13 @@ mod one_star {
 #[cfg(test)]
 mod two_star {
     use super::{TEST_LINES, find_solution};
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::io::Cursor;
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_solution(Cursor::new(TEST_LINES), true)?, 4455);
+        assert_eq!(find_solution(Cursor::new(TEST_LINES), true)?, Answer::Unsigned(4455));
         Ok(())
     }
 }
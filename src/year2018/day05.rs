@@ -1,11 +1,44 @@
 //! Advent of Code - Day 5 "Alchemical Reduction" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::ParseError;
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
 use std::cmp::{max, min};
 use std::collections::HashMap;
 use std::io::BufRead;
 
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    if let Some(line) = reader.lines().map_while(Result::ok).next() {
+/// Day 5 "Alchemical Reduction"
+pub(crate) struct Day05;
+
+impl Solver for Day05 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD05
+    }
+
+    fn title(&self) -> &'static str {
+        "Alchemical Reduction"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
+    if let Some(line) = reader.lines().next().transpose()? {
+        if let Some(col) = line.find(|ch: char| !ch.is_ascii_alphabetic()) {
+            let unit = &line[col..col + line[col..].chars().next().map_or(0, char::len_utf8)];
+            return Err(ParseError::new(AoCYear::AOC2018, AoCDay::AOCD05, 0, &line, unit, "a unit from `a` to `z` or `A` to `Z`").into());
+        }
         if second_star {
             let mut results = HashMap::new();
             for lower in 97..=122 {
@@ -17,12 +50,12 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
             }
 
             if let Some(min) = results.values().min() {
-                return Ok(*min);
+                return Ok((*min).into());
             } else {
                 return Err(anyhow!("unable to find minimum"));
             }
         } else {
-            return Ok(collapse_polymer(&mut line.as_bytes().to_vec()));
+            return Ok(collapse_polymer(&mut line.as_bytes().to_vec()).into());
         }
     }
     Err(anyhow!("unable to parse input"))
@@ -57,6 +90,7 @@ fn collapse_polymer(bytes: &mut Vec<u8>) -> u32 {
 #[cfg(test)]
 mod one_star {
     use super::find_solution;
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -64,7 +98,7 @@ mod one_star {
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), false)?, 10);
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), false)?, Answer::Unsigned(10));
         Ok(())
     }
 }
@@ -72,6 +106,7 @@ mod one_star {
 #[cfg(test)]
 mod two_star {
     use super::find_solution;
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -79,7 +114,7 @@ mod two_star {
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), true)?, 4);
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), true)?, Answer::Unsigned(4));
         Ok(())
     }
 }
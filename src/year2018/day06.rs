@@ -1,20 +1,50 @@
 //! Advent of Code - Day 6 "Chronal Coordinates" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::{self, ParseError};
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
 use indexmap::IndexSet;
 use regex::Regex;
 use std::collections::{BTreeMap, HashMap};
 use std::io::BufRead;
 
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    let line_re = Regex::new(r"(\d+), (\d+)")?;
+/// Day 6 "Chronal Coordinates"
+pub(crate) struct Day06;
+
+impl Solver for Day06 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD06
+    }
+
+    fn title(&self) -> &'static str {
+        "Chronal Coordinates"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
+    let line_re = Regex::new(r"^(\d+), (\d+)$")?;
     let mut coords: Vec<(i32, i32)> = Vec::new();
 
-    for line in reader.lines().map_while(Result::ok) {
-        for cap in line_re.captures_iter(&line) {
-            let x = &cap[1].parse::<i32>()?;
-            let y = &cap[2].parse::<i32>()?;
-            coords.push((*x, *y));
-        }
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let cap = line_re
+            .captures(&line)
+            .ok_or_else(|| ParseError::whole_line(AoCYear::AOC2018, AoCDay::AOCD06, idx, &line, "a coordinate like `1, 6`"))?;
+        let number = |i: usize| parse::number(AoCYear::AOC2018, AoCDay::AOCD06, idx, &line, &cap[i]);
+        coords.push((number(1)?, number(2)?));
     }
 
     let (max_x, max_y) = max_coords(&coords);
@@ -31,7 +61,7 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
                 }
             }
         }
-        Ok(less_than_d)
+        Ok(less_than_d.into())
     } else {
         let mut md_map = BTreeMap::new();
         let mut on_boundary = IndexSet::new();
@@ -62,7 +92,7 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
         }
 
         let max = frequency.iter().max_by_key(|(_, x)| *x).map(|(_, x)| *x).ok_or(anyhow!("no maximum"))?;
-        Ok(max)
+        Ok(max.into())
     }
 }
 
@@ -102,6 +132,7 @@ fn max_coords(coords: &[(i32, i32)]) -> (i32, i32) {
 #[cfg(test)]
 mod one_star {
     use super::find_solution;
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -114,7 +145,7 @@ mod one_star {
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), false)?, 17);
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), false)?, Answer::Unsigned(17));
         Ok(())
     }
 }
@@ -122,19 +153,20 @@ mod one_star {
 #[cfg(test)]
 mod two_star {
     use super::find_solution;
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::io::Cursor;
 
     const TEST_CHAIN: &str = r"1, 1
-    1, 6
-    8, 3
-    3, 4
-    5, 5
-    8, 9";
+1, 6
+8, 3
+3, 4
+5, 5
+8, 9";
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), true)?, 16);
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), true)?, Answer::Signed(16));
         Ok(())
     }
 }
@@ -1,4 +1,9 @@
 //! Advent of Code - Day 7 "The Sum of Its Parts" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::parse::ParseError;
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
 use getset::{Getters, Setters};
 use indexmap::IndexSet;
@@ -7,47 +12,60 @@ use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::io::BufRead;
 
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+/// Day 7 "The Sum of Its Parts"
+pub(crate) struct Day07;
+
+impl Solver for Day07 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD07
+    }
+
+    fn title(&self) -> &'static str {
+        "The Sum of Its Parts"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false, &Context::default())
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true, &Context::default())
+    }
+
+    fn star(&self, part: u8, reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer> {
+        find_solution(reader, part == 2, ctx)
+    }
+}
+
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool, ctx: &Context) -> Result<Answer> {
     if second_star {
-        let duration = find_duration(reader, false)?;
-        Ok(duration)
+        Ok(find_duration(reader, false, ctx)?.into())
     } else {
-        let order = find_order(reader)?;
-        println!("{order}");
-        Ok(0)
+        Ok(find_order(reader)?.into())
     }
 }
 
-fn duration_of(val: &str, base: u32) -> Result<u32> {
-    Ok(match val {
-        "A" => base + 1,
-        "B" => base + 2,
-        "C" => base + 3,
-        "D" => base + 4,
-        "E" => base + 5,
-        "F" => base + 6,
-        "G" => base + 7,
-        "H" => base + 8,
-        "I" => base + 9,
-        "J" => base + 10,
-        "K" => base + 11,
-        "L" => base + 12,
-        "M" => base + 13,
-        "N" => base + 14,
-        "O" => base + 15,
-        "P" => base + 16,
-        "Q" => base + 17,
-        "R" => base + 18,
-        "S" => base + 19,
-        "T" => base + 20,
-        "U" => base + 21,
-        "V" => base + 22,
-        "W" => base + 23,
-        "X" => base + 24,
-        "Y" => base + 25,
-        "Z" => base + 26,
-        _ => return Err(anyhow!("invalid instructions")),
-    })
+fn duration_of(step: char, base: u32) -> u32 {
+    base + u32::from(step as u8 - b'A') + 1
+}
+
+/// Parse a line like `Step C must be finished before step A can begin.` into its two steps.
+fn parse_step(line_re: &Regex, idx: usize, line: &str) -> Result<(char, char)> {
+    let cap = line_re.captures(line).ok_or_else(|| {
+        ParseError::whole_line(
+            AoCYear::AOC2018,
+            AoCDay::AOCD07,
+            idx,
+            line,
+            "a step like `Step C must be finished before step A can begin.`",
+        )
+    })?;
+    let step = |i: usize| cap[i].chars().next().unwrap_or_default();
+    Ok((step(1), step(2)))
 }
 
 #[derive(Clone, Debug, Default, Getters, Setters)]
@@ -69,8 +87,8 @@ impl fmt::Display for Worker {
     }
 }
 
-fn find_duration<T: BufRead>(reader: T, test: bool) -> Result<u32> {
-    let line_re = Regex::new(r#"Step ([A-Z]) must be finished before step ([A-Z])"#)?;
+fn find_duration<T: BufRead>(reader: T, test: bool, ctx: &Context) -> Result<u32> {
+    let line_re = Regex::new(r"^Step ([A-Z]) must be finished before step ([A-Z]) can begin\.$")?;
     let base = if test { 0 } else { 60 };
     let workers_count = if test { 2 } else { 5 };
     let mut workers = Vec::new();
@@ -84,28 +102,20 @@ fn find_duration<T: BufRead>(reader: T, test: bool) -> Result<u32> {
     let mut parents_map = HashMap::new();
     let mut pending = IndexSet::new();
 
-    for line_result in reader.lines() {
-        if let Ok(line) = line_result {
-            for cap in line_re.captures_iter(&line) {
-                let first = (cap[1]).to_string();
-                let first_duration = duration_of(&first, base)?;
-                let first_tuple = (first, first_duration);
-                let second = (cap[2]).to_string();
-                let second_duration = duration_of(&second, base)?;
-                let second_tuple = (second, second_duration);
-                pending.insert(first_tuple.clone());
-                pending.insert(second_tuple.clone());
-                let children = child_map.entry(first_tuple.clone()).or_insert_with(Vec::new);
-                children.push(second_tuple.clone());
-                let parents = parents_map.entry(second_tuple).or_insert_with(Vec::new);
-                parents.push(first_tuple);
-            }
-        } else {
-            return Err(anyhow!("unable to parse input"));
-        }
+    for (idx, line_result) in reader.lines().enumerate() {
+        let line = line_result?;
+        let (first, second) = parse_step(&line_re, idx, &line)?;
+        let first_tuple = (first.to_string(), duration_of(first, base));
+        let second_tuple = (second.to_string(), duration_of(second, base));
+        pending.insert(first_tuple.clone());
+        pending.insert(second_tuple.clone());
+        let children = child_map.entry(first_tuple.clone()).or_insert_with(Vec::new);
+        children.push(second_tuple.clone());
+        let parents = parents_map.entry(second_tuple).or_insert_with(Vec::new);
+        parents.push(first_tuple);
     }
 
-    let all_children: IndexSet<(String, u32)> = child_map.iter().flat_map(|(_, c)| c).cloned().collect();
+    let all_children: IndexSet<(String, u32)> = child_map.values().flatten().cloned().collect();
     let mut ready: IndexSet<(String, u32)> = pending.difference(&all_children).cloned().collect();
     let mut complete: IndexSet<(String, u32)> = IndexSet::new();
 
@@ -154,14 +164,15 @@ fn find_duration<T: BufRead>(reader: T, test: bool) -> Result<u32> {
         // Complete 1 second of work on each busy worker
         adjust_workers(&mut workers);
 
-        // println!();
-        // println!("Tick: {}", tick);
-        // println!("Ready: {:?}", ready);
-        // println!("Complete: {:?}", complete);
-
-        // for worker in &workers {
-        //     println!("{}", worker);
-        // }
+        if ctx.tracing() {
+            eprintln!("Tick: {tick}");
+            eprintln!("Ready: {ready:?}");
+            eprintln!("Complete: {complete:?}");
+            for worker in &workers {
+                eprintln!("{worker}");
+            }
+            eprintln!();
+        }
 
         // Are we done?
         if ready.is_empty() && all_idle(&workers) {
@@ -217,7 +228,10 @@ fn consume_work(worker: &mut Worker, work: &(String, u32)) -> bool {
 
 fn complete(node_map: &BTreeMap<char, Vec<char>>) -> Result<char> {
     let ready: Vec<char> = node_map.iter().filter_map(|(x, y)| if y.is_empty() { Some(*x) } else { None }).collect();
-    ready.first().cloned().ok_or(anyhow!("blah"))
+    ready.first().cloned().ok_or_else(|| {
+        let blocked: String = node_map.keys().collect();
+        anyhow!("steps {blocked} wait on each other, so none of them can be done")
+    })
 }
 
 fn remove_from_parents(node_map: &mut BTreeMap<char, Vec<char>>, key: char) {
@@ -232,19 +246,17 @@ fn find_order<T: BufRead>(reader: T) -> Result<String> {
     let mut node_map: BTreeMap<char, Vec<char>> = BTreeMap::new();
 
     // Parse the input file and load the map.
-    let line_re = Regex::new(r#"Step ([A-Z]) must be finished before step ([A-Z])"#)?;
-    for line in reader.lines().map_while(Result::ok) {
-        for cap in line_re.captures_iter(&line) {
-            let first = (cap[1]).chars().next().ok_or(anyhow!("invalid char"))?;
-            let second = (cap[2]).chars().next().ok_or(anyhow!("invalid char"))?;
-
-            {
-                node_map.entry(first).or_insert_with(|| Vec::with_capacity(25));
-            }
-            {
-                let snode = node_map.entry(second).or_insert_with(|| Vec::with_capacity(25));
-                snode.push(first);
-            }
+    let line_re = Regex::new(r"^Step ([A-Z]) must be finished before step ([A-Z]) can begin\.$")?;
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let (first, second) = parse_step(&line_re, idx, &line)?;
+
+        {
+            node_map.entry(first).or_insert_with(|| Vec::with_capacity(25));
+        }
+        {
+            let snode = node_map.entry(second).or_insert_with(|| Vec::with_capacity(25));
+            snode.push(first);
         }
     }
 
@@ -256,7 +268,7 @@ fn find_order<T: BufRead>(reader: T) -> Result<String> {
     while !node_map.is_empty() {
         let completed = complete(&node_map)?;
         remove_from_parents(&mut node_map, completed);
-        node_map.remove(&completed).ok_or(anyhow!("blah"))?;
+        node_map.remove(&completed);
         result.push(completed);
     }
 
@@ -282,11 +294,30 @@ Step F must be finished before step E can begin.";
         assert_eq!(find_order(Cursor::new(TEST_CHAIN))?, "CABDFE".to_string());
         Ok(())
     }
+
+    #[test]
+    fn bad_input() {
+        let error = find_order(Cursor::new("Step C must be finished before step A can begin.\nStep A must finish first")).err();
+        assert_eq!(
+            error.map(|e| e.to_string()),
+            Some(
+                "2018 day07 line 2, column 1: expected a step like `Step C must be finished before step A can begin.`, found `Step A must finish first`"
+                    .to_string()
+            )
+        );
+        let cycle = "Step A must be finished before step B can begin.\nStep B must be finished before step A can begin.";
+        let error = find_order(Cursor::new(cycle)).err();
+        assert_eq!(
+            error.map(|e| e.to_string()),
+            Some("steps AB wait on each other, so none of them can be done".to_string())
+        );
+    }
 }
 
 #[cfg(test)]
 mod two_star {
     use super::find_duration;
+    use crate::context::Context;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -300,7 +331,7 @@ Step F must be finished before step E can begin.";
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_duration(Cursor::new(TEST_CHAIN), true)?, 15);
+        assert_eq!(find_duration(Cursor::new(TEST_CHAIN), true, &Context::default())?, 15);
         Ok(())
     }
 }
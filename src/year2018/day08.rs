@@ -1,23 +1,53 @@
 //! Advent of Code - Day 8 "Memory Manuver" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse;
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
 use std::io::BufRead;
 
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+/// Day 8 "Memory Maneuver"
+pub(crate) struct Day08;
+
+impl Solver for Day08 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD08
+    }
+
+    fn title(&self) -> &'static str {
+        "Memory Maneuver"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let mut license_vec = Vec::new();
-    for line in reader.lines().map_while(Result::ok) {
-        for tok in line.split(' ').map(|x| x.parse::<u32>()).map_while(Result::ok) {
-            license_vec.push(tok);
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        for tok in line.split_whitespace() {
+            license_vec.push(parse::number(AoCYear::AOC2018, AoCDay::AOCD08, idx, &line, tok)?);
         }
     }
 
     license_vec.reverse();
 
-    recurse(&mut license_vec, second_star)
+    Ok(recurse(&mut license_vec, second_star)?.into())
 }
 
 fn recurse(license_vec: &mut Vec<u32>, second_star: bool) -> Result<u32> {
-    let children_count = license_vec.pop().ok_or(anyhow!(""))?;
-    let metadata_count = license_vec.pop().ok_or(anyhow!(""))?;
+    let children_count = license_vec.pop().ok_or_else(|| anyhow!("the license ends in the middle of a node"))?;
+    let metadata_count = license_vec.pop().ok_or_else(|| anyhow!("the license ends in the middle of a node"))?;
     let mut result = 0;
 
     if second_star {
@@ -28,11 +58,11 @@ fn recurse(license_vec: &mut Vec<u32>, second_star: bool) -> Result<u32> {
         }
 
         for _ in 0..metadata_count {
-            let metadata = license_vec.pop().ok_or(anyhow!(""))?;
+            let metadata = license_vec.pop().ok_or_else(|| anyhow!("the license ends in the middle of a node"))?;
             if children_count == 0 {
                 result += metadata;
             } else {
-                result += children_values.get(metadata as usize - 1).unwrap_or(&0);
+                result += (metadata as usize).checked_sub(1).and_then(|child| children_values.get(child)).unwrap_or(&0);
             }
         }
     } else {
@@ -41,7 +71,7 @@ fn recurse(license_vec: &mut Vec<u32>, second_star: bool) -> Result<u32> {
         }
 
         for _ in 0..metadata_count {
-            result += license_vec.pop().ok_or(anyhow!(""))?;
+            result += license_vec.pop().ok_or_else(|| anyhow!("the license ends in the middle of a node"))?;
         }
     }
 
@@ -51,6 +81,7 @@ fn recurse(license_vec: &mut Vec<u32>, second_star: bool) -> Result<u32> {
 #[cfg(test)]
 mod one_star {
     use super::find_solution;
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -58,7 +89,7 @@ mod one_star {
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), false)?, 138);
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), false)?, Answer::Unsigned(138));
         Ok(())
     }
 }
@@ -66,6 +97,7 @@ mod one_star {
 #[cfg(test)]
 mod two_star {
     use super::find_solution;
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -73,7 +105,7 @@ mod two_star {
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), true)?, 66);
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), true)?, Answer::Unsigned(66));
         Ok(())
     }
 }
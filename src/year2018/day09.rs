@@ -1,27 +1,88 @@
 //! Advent of Code - Day 9 "Marble Mania" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::parse::{self, ParseError};
+use crate::solver::{self, Solver};
 use anyhow::{Result, anyhow};
 use regex::Regex;
+use std::any::Any;
 use std::collections::VecDeque;
 use std::io::BufRead;
 
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    let line_re = Regex::new(r"(\d+) players; last marble is worth (\d+) points")?;
+/// Day 9 "Marble Mania"
+pub(crate) struct Day09;
+
+impl Solver for Day09 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD09
+    }
+
+    fn title(&self) -> &'static str {
+        "Marble Mania"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+
+    fn parse(&self, reader: &mut dyn BufRead) -> Result<Option<Box<dyn Any>>> {
+        Ok(Some(Box::new(parse_game(reader)?)))
+    }
+
+    fn solve_parsed(&self, part: u8, input: &dyn Any, _ctx: &Context) -> Result<Answer> {
+        let &(players, final_marble) = solver::parsed::<(usize, usize)>(input)?;
+        score(players, final_marble, part == 2)
+    }
+}
+
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
+    let (players, final_marble) = parse_game(reader)?;
+    score(players, final_marble, second_star)
+}
+
+/// The winning score, with a hundred times as many marbles for the second star.
+fn score(players: usize, final_marble: usize, second_star: bool) -> Result<Answer> {
+    let final_marble = if second_star { final_marble * 100 } else { final_marble };
+    Ok(play_game(players, final_marble)?.into())
+}
+
+/// Parse the number of players and the value of the last marble.
+fn parse_game<T: BufRead>(reader: T) -> Result<(usize, usize)> {
+    let line_re = Regex::new(r"^(\d+) players; last marble is worth (\d+) points$")?;
     let mut players = 0;
     let mut final_marble = 0;
 
-    for line in reader.lines().map_while(Result::ok) {
-        for cap in line_re.captures_iter(&line) {
-            players = (cap[1]).parse::<usize>()?;
-            final_marble = (cap[2]).parse::<usize>()?;
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let cap = line_re.captures(&line).ok_or_else(|| {
+            ParseError::whole_line(
+                AoCYear::AOC2018,
+                AoCDay::AOCD09,
+                idx,
+                &line,
+                "a game like `9 players; last marble is worth 25 points`",
+            )
+        })?;
+        players = parse::number(AoCYear::AOC2018, AoCDay::AOCD09, idx, &line, &cap[1])?;
+        if players == 0 {
+            return Err(ParseError::new(AoCYear::AOC2018, AoCDay::AOCD09, idx, &line, &cap[1], "at least 1 player").into());
         }
+        final_marble = parse::number(AoCYear::AOC2018, AoCDay::AOCD09, idx, &line, &cap[2])?;
     }
 
-    if second_star {
-        final_marble *= 100;
+    if players == 0 {
+        return Err(anyhow!("expected a game like `9 players; last marble is worth 25 points`, found no input"));
     }
-
-    let result = play_game(players, final_marble)?;
-    Ok(result as u32)
+    Ok((players, final_marble))
 }
 
 fn rotate_left(circle: &mut VecDeque<usize>, amt: usize) -> Result<()> {
@@ -61,6 +122,7 @@ fn play_game(players: usize, final_marble: usize) -> Result<usize> {
 #[cfg(test)]
 mod one_star {
     use super::find_solution;
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -73,12 +135,12 @@ mod one_star {
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), false)?, 32);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_1), false)?, 8317);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_2), false)?, 146_373);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_3), false)?, 2764);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_4), false)?, 54718);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_5), false)?, 37305);
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), false)?, Answer::Unsigned(32));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_1), false)?, Answer::Unsigned(8317));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_2), false)?, Answer::Unsigned(146_373));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_3), false)?, Answer::Unsigned(2764));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_4), false)?, Answer::Unsigned(54718));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_5), false)?, Answer::Unsigned(37305));
         Ok(())
     }
 }
@@ -86,6 +148,7 @@ mod one_star {
 #[cfg(test)]
 mod two_star {
     use super::find_solution;
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -98,12 +161,12 @@ mod two_star {
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), true)?, 22563);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_1), true)?, 74_765_078);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_2), true)?, 1_406_506_154);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_3), true)?, 20_548_882);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_4), true)?, 507_583_214);
-        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_5), true)?, 320_997_431);
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN), true)?, Answer::Unsigned(22563));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_1), true)?, Answer::Unsigned(74_765_078));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_2), true)?, Answer::Unsigned(1_406_506_154));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_3), true)?, Answer::Unsigned(20_548_882));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_4), true)?, Answer::Unsigned(507_583_214));
+        assert_eq!(find_solution(Cursor::new(TEST_CHAIN_5), true)?, Answer::Unsigned(320_997_431));
         Ok(())
     }
 }
@@ -1,46 +1,104 @@
 //! Advent of Code - Day 10 "The Stars Align" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::parse::{self, ParseError};
+use crate::solver::Solver;
 use anyhow::Result;
 use regex::Regex;
 use std::io::BufRead;
 
+/// Day 10 "The Stars Align"
+pub(crate) struct Day10;
+
+impl Solver for Day10 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD10
+    }
+
+    fn title(&self) -> &'static str {
+        "The Stars Align"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false, &Context::default())
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true, &Context::default())
+    }
+
+    fn star(&self, part: u8, reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer> {
+        find_solution(reader, part == 2, ctx)
+    }
+}
+
 /// Find the solution
-pub fn find_solution<T: BufRead>(reader: T, _second_star: bool) -> Result<u32> {
-    println!("{}", align(reader, false)?);
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool, ctx: &Context) -> Result<Answer> {
+    let (message, seconds) = align(reader, ctx)?;
 
-    Ok(0)
+    if second_star { Ok(seconds.into()) } else { Ok(Answer::Art(message)) }
 }
 
-fn align<T: BufRead>(reader: T, test: bool) -> Result<String> {
-    let line_re = Regex::new(r"position=<(.*), (.*)> velocity=<(.*), (.*)>")?;
+/// Move the stars until they are closest together, which is when they spell the message.
+fn align<T: BufRead>(reader: T, ctx: &Context) -> Result<(String, usize)> {
+    let line_re = Regex::new(r"^position=<(.*), (.*)> velocity=<(.*), (.*)>$")?;
     let mut star_map: Vec<(isize, isize, isize, isize)> = Vec::new();
 
-    for line in reader.lines().map_while(Result::ok) {
-        for cap in line_re.captures_iter(&line) {
-            let x = (cap[1]).trim().parse::<isize>()?;
-            let y = (cap[2]).trim().parse::<isize>()?;
-            let vx = (cap[3]).trim().parse::<isize>()?;
-            let vy = (cap[4]).trim().parse::<isize>()?;
-
-            star_map.push((x, y, vx, vy));
-        }
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let cap = line_re.captures(&line).ok_or_else(|| {
+            ParseError::whole_line(
+                AoCYear::AOC2018,
+                AoCDay::AOCD10,
+                idx,
+                &line,
+                "a star like `position=< 9,  1> velocity=< 0,  2>`",
+            )
+        })?;
+        let number = |i: usize| parse::number(AoCYear::AOC2018, AoCDay::AOCD10, idx, &line, cap[i].trim());
+        star_map.push((number(1)?, number(2)?, number(3)?, number(4)?));
     }
 
-    let max_step = if test { 3 } else { 10619 };
-
-    for _ in 0..max_step {
-        move_stars(&mut star_map);
+    let mut seconds = 0;
+    let mut height = span(&star_map);
+    loop {
+        ctx.check()?;
+        move_stars(&mut star_map, 1);
+        let next = span(&star_map);
+        if next >= height {
+            move_stars(&mut star_map, -1);
+            break;
+        }
+        height = next;
+        seconds += 1;
+        if ctx.tracing() {
+            eprintln!("After {seconds}s the stars span {} rows", height + 1);
+        }
     }
 
-    Ok(show_stars(&star_map))
+    Ok((show_stars(&star_map), seconds))
 }
 
-fn move_stars(star_map: &mut Vec<(isize, isize, isize, isize)>) {
+/// Move every star by its velocity, `by` times over.
+fn move_stars(star_map: &mut Vec<(isize, isize, isize, isize)>, by: isize) {
     for star in star_map {
-        star.0 += star.2;
-        star.1 += star.3;
+        star.0 += star.2 * by;
+        star.1 += star.3 * by;
     }
 }
 
+/// How far apart the highest and lowest stars are.
+fn span(star_map: &[(isize, isize, isize, isize)]) -> isize {
+    let min_y = star_map.iter().map(|star| star.1).min().unwrap_or(0);
+    let max_y = star_map.iter().map(|star| star.1).max().unwrap_or(0);
+    max_y - min_y
+}
+
 fn show_stars(star_map: &[(isize, isize, isize, isize)]) -> String {
     let mut output = String::new();
     let mut min_x = isize::MAX;
@@ -90,6 +148,7 @@ fn show_stars(star_map: &[(isize, isize, isize, isize)]) -> String {
 #[cfg(test)]
 mod one_star {
     use super::align;
+    use crate::context::Context;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -137,7 +196,7 @@ position=<-3,  6> velocity=< 2, -1>";
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(align(Cursor::new(TEST_CHAIN), true)?, EXPECTED);
+        assert_eq!(align(Cursor::new(TEST_CHAIN), &Context::default())?, (EXPECTED.to_string(), 3));
         Ok(())
     }
 }
@@ -1,19 +1,53 @@
 //! Advent of Code - Day 11 "Chronal Charge" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::parse;
+use crate::solver::Solver;
 use anyhow::Result;
 use ndarray::Array2;
 use std::io::BufRead;
 
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+/// Day 11 "Chronal Charge"
+pub(crate) struct Day11;
+
+impl Solver for Day11 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD11
+    }
+
+    fn title(&self) -> &'static str {
+        "Chronal Charge"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false, &Context::default())
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true, &Context::default())
+    }
+
+    fn star(&self, part: u8, reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer> {
+        find_solution(reader, part == 2, ctx)
+    }
+}
+
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool, ctx: &Context) -> Result<Answer> {
     let mut serial_number = 0;
 
-    for line in reader.lines().map_while(Result::ok) {
-        serial_number = line.parse::<usize>()?;
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        serial_number = parse::number(AoCYear::AOC2018, AoCDay::AOCD11, idx, &line, &line)?;
     }
 
-    let result = submatrix_sum_queries(serial_number, second_star)?;
-    println!("{},{},{} with power level {}", result.0, result.1, result.2, result.3);
+    let (x, y, size, _) = submatrix_sum_queries(serial_number, second_star, ctx)?;
 
-    Ok(0)
+    if second_star { Ok((x, y, size).into()) } else { Ok((x, y).into()) }
 }
 
 fn find_cell_power(x: usize, y: usize, serial_number: usize) -> Result<isize> {
@@ -26,7 +60,7 @@ fn find_cell_power(x: usize, y: usize, serial_number: usize) -> Result<isize> {
     Ok(power_level)
 }
 
-fn submatrix_sum_queries(serial_number: usize, second_star: bool) -> Result<(usize, usize, usize, isize)> {
+fn submatrix_sum_queries(serial_number: usize, second_star: bool, ctx: &Context) -> Result<(usize, usize, usize, isize)> {
     let mut power_level: Array2<isize> = Array2::zeros((300, 300));
 
     for i in 0..300 {
@@ -58,7 +92,10 @@ fn submatrix_sum_queries(serial_number: usize, second_star: bool) -> Result<(usi
 
     if second_star {
         for size in 0..300 {
-            println!("Checking size {size}");
+            ctx.check()?;
+            if ctx.tracing() {
+                eprintln!("Checking size {}", size + 1);
+            }
             submatrix_sum_query(&aux, size, &mut max_power_level, &mut max_cell);
         }
     } else {
@@ -107,6 +144,7 @@ fn sum_query(aux: &Array2<isize>, i: usize, j: usize, size: usize) -> isize {
 #[cfg(test)]
 mod one_star {
     use super::{find_cell_power, submatrix_sum_queries};
+    use crate::context::Context;
     use anyhow::Result;
 
     #[test]
@@ -115,9 +153,9 @@ mod one_star {
         assert_eq!(find_cell_power(122, 79, 57)?, -5);
         assert_eq!(find_cell_power(217, 196, 39)?, 0);
         assert_eq!(find_cell_power(101, 153, 71)?, 4);
-        assert_eq!(submatrix_sum_queries(18, false)?, (33, 45, 3, 29));
-        assert_eq!(submatrix_sum_queries(42, false)?, (21, 61, 3, 30));
-        assert_eq!(submatrix_sum_queries(7511, false)?, (21, 22, 3, 34));
+        assert_eq!(submatrix_sum_queries(18, false, &Context::default())?, (33, 45, 3, 29));
+        assert_eq!(submatrix_sum_queries(42, false, &Context::default())?, (21, 61, 3, 30));
+        assert_eq!(submatrix_sum_queries(7511, false, &Context::default())?, (21, 22, 3, 34));
         Ok(())
     }
 }
@@ -125,13 +163,14 @@ mod one_star {
 #[cfg(test)]
 mod two_star {
     use super::submatrix_sum_queries;
+    use crate::context::Context;
     use anyhow::Result;
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(submatrix_sum_queries(18, true)?, (90, 269, 16, 113));
-        assert_eq!(submatrix_sum_queries(42, true)?, (232, 251, 12, 119));
-        // assert_eq!(submatrix_sum_queries(7511, true)?, (236, 287, 13, 0));
+        assert_eq!(submatrix_sum_queries(18, true, &Context::default())?, (90, 269, 16, 113));
+        assert_eq!(submatrix_sum_queries(42, true, &Context::default())?, (232, 251, 12, 119));
+        // assert_eq!(submatrix_sum_queries(7511, true, &Context::default())?, (236, 287, 13, 0));
         Ok(())
     }
 }
@@ -1,4 +1,8 @@
 //! Advent of Code - Day 12 "Subterranean Sustainability" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::ParseError;
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
 use indexmap::IndexMap;
 use regex::Regex;
@@ -6,7 +10,32 @@ use sliding_windows::{IterExt, Storage};
 use std::collections::{BTreeMap, HashMap};
 use std::io::BufRead;
 
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+/// Day 12 "Subterranean Sustainability"
+pub(crate) struct Day12;
+
+impl Solver for Day12 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD12
+    }
+
+    fn title(&self) -> &'static str {
+        "Subterranean Sustainability"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let mut state_map = BTreeMap::new();
     let mut pattern_map = IndexMap::new();
 
@@ -18,35 +47,31 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
     } else {
         run_generations(20, &mut state_map, &pattern_map)?
     };
-    println!("Sum: {res}");
-    Ok(0)
+    Ok(res.into())
 }
 
 fn gen_maps<T: BufRead>(reader: T, state_map: &mut BTreeMap<isize, bool>, pattern_map: &mut IndexMap<Vec<bool>, bool>) -> Result<()> {
-    let initial_state_re = Regex::new(r"^initial state: ([\.#]+)")?;
-    let patt_re = Regex::new(r"([\.#]+) => ([\.#])")?;
-
-    for line in reader.lines().map_while(Result::ok) {
-        for cap in initial_state_re.captures_iter(&line) {
-            let state_str = &cap[1];
-
-            for (idx, ch) in state_str.chars().enumerate() {
-                match ch {
-                    '#' => state_map.insert(isize::try_from(idx)?, true),
-                    '.' => state_map.insert(isize::try_from(idx)?, false),
-                    _ => return Err(anyhow!("invalid state character")),
-                };
+    let initial_state_re = Regex::new(r"^initial state: ([\.#]+)$")?;
+    let patt_re = Regex::new(r"^([\.#]{5}) => ([\.#])$")?;
+
+    for (line_idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        if let Some(cap) = initial_state_re.captures(&line) {
+            for (idx, ch) in cap[1].chars().enumerate() {
+                state_map.insert(isize::try_from(idx)?, ch == '#');
             }
-        }
-
-        for cap in patt_re.captures_iter(&line) {
+        } else if let Some(cap) = patt_re.captures(&line) {
             let pattern: Vec<bool> = (cap[1]).chars().map(|ch| ch == '#').collect();
-
-            match &cap[2] {
-                "#" => pattern_map.insert(pattern, true),
-                "." => pattern_map.insert(pattern, false),
-                _ => return Err(anyhow!("invalid pattern character")),
-            };
+            pattern_map.insert(pattern, &cap[2] == "#");
+        } else if !line.is_empty() {
+            return Err(ParseError::whole_line(
+                AoCYear::AOC2018,
+                AoCDay::AOCD12,
+                line_idx,
+                &line,
+                "`initial state: #..#.` or a note like `..#.. => #`",
+            )
+            .into());
         }
     }
     Ok(())
@@ -1,13 +1,46 @@
 //! Advent of Code - Day 13 "Mine Cart Madness" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::grid::Grid;
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
 use getset::Getters;
-use ndarray::{Array2, Axis};
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::fmt;
 use std::io::BufRead;
 
-#[derive(Default)]
+/// Day 13 "Mine Cart Madness"
+pub(crate) struct Day13;
+
+impl Solver for Day13 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD13
+    }
+
+    fn title(&self) -> &'static str {
+        "Mine Cart Madness"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false, &Context::default())
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true, &Context::default())
+    }
+
+    fn star(&self, part: u8, reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer> {
+        find_solution(reader, part == 2, ctx)
+    }
+}
+
+#[derive(Clone, Default)]
 enum TrackKind {
     UpDown,
     LeftRight,
@@ -34,7 +67,7 @@ impl fmt::Display for TrackKind {
     }
 }
 
-#[derive(Default, Getters)]
+#[derive(Clone, Default, Getters)]
 struct Track {
     kind: TrackKind,
     #[getset(get)]
@@ -112,7 +145,7 @@ impl fmt::Display for CartDirection {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct Cart {
     direction: CartDirection,
     turn_state: CartTurnState,
@@ -124,69 +157,40 @@ impl fmt::Display for Cart {
     }
 }
 
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    if let Some((i, j)) = run_carts(reader, 150, 150, second_star, false)? {
-        println!("Result: {i},{j}");
-    }
-
-    Ok(0)
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool, ctx: &Context) -> Result<Answer> {
+    let coords = run_carts(reader, second_star, ctx)?.ok_or(anyhow!("no carts left on the tracks"))?;
+    Ok(coords.into())
 }
 
-fn gen_mine<T: BufRead>(reader: T, i: usize, j: usize) -> Result<Array2<Track>> {
-    let mut mine_arr: Array2<Track> = Array2::default((i, j));
-    for (j, line) in reader.lines().map_while(Result::ok).enumerate() {
-        for (i, ch) in line.chars().enumerate() {
-            let (kind, cart) = match ch {
-                '/' => (TrackKind::CurveRight, None),
-                '\\' => (TrackKind::CurveLeft, None),
-                '|' => (TrackKind::UpDown, None),
-                '-' => (TrackKind::LeftRight, None),
-                '+' => (TrackKind::Junction, None),
-                '^' => (
-                    TrackKind::UpDown,
-                    Some(Cart {
-                        direction: CartDirection::Up,
-                        turn_state: CartTurnState::Left,
-                    }),
-                ),
-                'v' => (
-                    TrackKind::UpDown,
-                    Some(Cart {
-                        direction: CartDirection::Down,
-                        turn_state: CartTurnState::Left,
-                    }),
-                ),
-                '<' => (
-                    TrackKind::LeftRight,
-                    Some(Cart {
-                        direction: CartDirection::Left,
-                        turn_state: CartTurnState::Left,
-                    }),
-                ),
-                '>' => (
-                    TrackKind::LeftRight,
-                    Some(Cart {
-                        direction: CartDirection::Right,
-                        turn_state: CartTurnState::Left,
-                    }),
-                ),
-                _ => (TrackKind::Empty, None),
-            };
-
-            mine_arr[[i, j]] = Track { kind, cart };
-        }
-    }
-
-    Ok(mine_arr)
+fn gen_mine<T: BufRead>(reader: T) -> Result<Grid<Track>> {
+    Grid::parse(reader, |ch| {
+        let cart = |direction| {
+            Some(Cart {
+                direction,
+                turn_state: CartTurnState::Left,
+            })
+        };
+        let (kind, cart) = match ch {
+            '/' => (TrackKind::CurveRight, None),
+            '\\' => (TrackKind::CurveLeft, None),
+            '|' => (TrackKind::UpDown, None),
+            '-' => (TrackKind::LeftRight, None),
+            '+' => (TrackKind::Junction, None),
+            '^' => (TrackKind::UpDown, cart(CartDirection::Up)),
+            'v' => (TrackKind::UpDown, cart(CartDirection::Down)),
+            '<' => (TrackKind::LeftRight, cart(CartDirection::Left)),
+            '>' => (TrackKind::LeftRight, cart(CartDirection::Right)),
+            _ => (TrackKind::Empty, None),
+        };
+        Ok(Track { kind, cart })
+    })
 }
 
-fn run_carts<T: BufRead>(reader: T, i: usize, j: usize, second_star: bool, test: bool) -> Result<Option<(usize, usize)>> {
-    if test {
-        println!();
-    }
-    let mut mine_arr = gen_mine(reader, i, j)?;
-    if test {
-        print_mine_arr(&mine_arr);
+/// Move the carts until the first crash, or until one cart is left for the second star, drawing every tick when visualizing.
+fn run_carts<T: BufRead>(reader: T, second_star: bool, ctx: &Context) -> Result<Option<(usize, usize)>> {
+    let mut mine_arr = gen_mine(reader)?;
+    if ctx.visualizing() {
+        ctx.frame(&render_mine_arr(&mine_arr));
     }
 
     let mut res;
@@ -194,8 +198,8 @@ fn run_carts<T: BufRead>(reader: T, i: usize, j: usize, second_star: bool, test:
         let cart_map = find_carts(&mine_arr);
         res = move_carts(&cart_map, &mut mine_arr, second_star)?;
 
-        if test {
-            print_mine_arr(&mine_arr);
+        if ctx.visualizing() {
+            ctx.frame(&render_mine_arr(&mine_arr));
         }
 
         if res.is_some() {
@@ -206,17 +210,17 @@ fn run_carts<T: BufRead>(reader: T, i: usize, j: usize, second_star: bool, test:
     Ok(res)
 }
 
-fn find_carts(mine_arr: &Array2<Track>) -> BTreeMap<CartPoint, CartDirection> {
+fn find_carts(mine_arr: &Grid<Track>) -> BTreeMap<CartPoint, CartDirection> {
     let mut cart_map = BTreeMap::new();
-    for (idx, t) in mine_arr.indexed_iter() {
+    for ([i, j], t) in mine_arr.iter() {
         if let Some(ref c) = t.cart {
-            cart_map.insert(CartPoint { i: idx.0, j: idx.1 }, c.direction);
+            cart_map.insert(CartPoint { i, j }, c.direction);
         }
     }
     cart_map
 }
 
-fn move_carts(cart_map: &BTreeMap<CartPoint, CartDirection>, mine_arr: &mut Array2<Track>, second_star: bool) -> Result<Option<(usize, usize)>> {
+fn move_carts(cart_map: &BTreeMap<CartPoint, CartDirection>, mine_arr: &mut Grid<Track>, second_star: bool) -> Result<Option<(usize, usize)>> {
     for (cart_point, direction) in cart_map.iter() {
         let i = cart_point.i;
         let j = cart_point.j;
@@ -361,19 +365,15 @@ fn next_turn_state(turn_state: CartTurnState) -> CartTurnState {
     }
 }
 
-fn print_mine_arr(mine_arr: &Array2<Track>) {
-    for row in mine_arr.axis_iter(Axis(1)) {
-        for cell in row {
-            print!("{cell}");
-        }
-        println!();
-    }
-    println!();
+/// Draw the tracks and the carts on them.
+fn render_mine_arr(mine_arr: &Grid<Track>) -> String {
+    mine_arr.to_string()
 }
 
 #[cfg(test)]
 mod one_star {
     use super::run_carts;
+    use crate::context::Context;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -394,13 +394,13 @@ v
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(run_carts(Cursor::new(TEST_STATE), 1, 7, false, true)?, Some((0, 3)));
+        assert_eq!(run_carts(Cursor::new(TEST_STATE), false, &Context::default())?, Some((0, 3)));
         Ok(())
     }
 
     #[test]
     fn solution2() -> Result<()> {
-        assert_eq!(run_carts(Cursor::new(TEST_STATE_2), 13, 6, false, true)?, Some((7, 3)));
+        assert_eq!(run_carts(Cursor::new(TEST_STATE_2), false, &Context::default())?, Some((7, 3)));
         Ok(())
     }
 }
@@ -408,6 +408,7 @@ v
 #[cfg(test)]
 mod two_star {
     use super::run_carts;
+    use crate::context::Context;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -421,7 +422,7 @@ mod two_star {
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(run_carts(Cursor::new(TEST_STATE), 7, 7, true, true)?, Some((6, 4)));
+        assert_eq!(run_carts(Cursor::new(TEST_STATE), true, &Context::default())?, Some((6, 4)));
         Ok(())
     }
 }
@@ -1,17 +1,55 @@
 //! Advent of Code - Day 14 "Chocolate Charts" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::parse::ParseError;
+use crate::solver::Solver;
 use anyhow::Result;
 use std::collections::VecDeque;
 use std::io::BufRead;
 
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
+/// Day 14 "Chocolate Charts"
+pub(crate) struct Day14;
+
+impl Solver for Day14 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD14
+    }
+
+    fn title(&self) -> &'static str {
+        "Chocolate Charts"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
+    }
+}
+
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
     let mut recipe_count = String::new();
-    for line in reader.lines().map_while(Result::ok) {
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        if let Some(col) = line.find(|ch: char| !ch.is_ascii_digit()) {
+            let found = &line[col..col + line[col..].chars().next().map_or(0, char::len_utf8)];
+            return Err(ParseError::new(AoCYear::AOC2018, AoCDay::AOCD14, idx, &line, found, "a digit").into());
+        }
         recipe_count.push_str(&line);
     }
 
     let result = score_recipes(&recipe_count, second_star)?;
-    println!("{result}");
-    Ok(0)
+
+    if second_star {
+        Ok(result.parse::<usize>()?.into())
+    } else {
+        Ok(result.into())
+    }
 }
 
 fn check_patt(recipes: &VecDeque<u8>, pattern: &str, len: usize) -> bool {
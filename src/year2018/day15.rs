@@ -1,10 +1,62 @@
 //! Advent of Code - Day 15 "Beverage Bandits" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::solver::{Solver, Star};
 use anyhow::{Result, anyhow};
-use ndarray::{Array2, Axis, Zip};
-use std::collections::{HashMap, VecDeque};
+use std::collections::VecDeque;
 use std::fmt;
 use std::io::BufRead;
 
+/// Day 15 "Beverage Bandits"
+pub(crate) struct Day15;
+
+impl Solver for Day15 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD15
+    }
+
+    fn title(&self) -> &'static str {
+        "Beverage Bandits"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false, &Context::default())
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true, &Context::default())
+    }
+
+    fn star(&self, part: u8, reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer> {
+        find_solution(reader, part == 2, ctx)
+    }
+
+    fn both(&self, reader: &mut dyn BufRead, ctx: &Context) -> Result<(Star, Star)> {
+        let lines: Vec<String> = reader.lines().collect::<Result<_, _>>()?;
+        let mut first_battle = None;
+        let first = Star::time(|| {
+            let battle = fight(&lines, 3, false, ctx)?;
+            let outcome = battle.outcome;
+            first_battle = Some(battle);
+            Ok(outcome.into())
+        });
+        let second = Star::time(|| match first_battle {
+            // No elf fell at the default attack power, so the first battle is also the second answer.
+            Some(Battle { outcome, flawless: true }) => Ok(outcome.into()),
+            Some(_) => Ok(rescue_elves(&lines, 4, ctx)?.into()),
+            None => Ok(rescue_elves(&lines, 3, ctx)?.into()),
+        });
+        Ok((first, second))
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 enum UnitKind {
     Elf,
@@ -100,202 +152,89 @@ enum Action {
     No,
 }
 
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    let outcome = run_battle(reader, 32, 32, second_star, false)?;
-    Ok(outcome as u32)
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool, ctx: &Context) -> Result<Answer> {
+    Ok(run_battle(reader, second_star, ctx)?.into())
 }
 
-fn generate_map(lines: &[String], max_i: usize, max_j: usize, elf_attack_power: usize) -> Result<Array2<Element>> {
-    let mut board: Array2<Element> = Array2::default((max_i, max_j));
+fn generate_map(lines: &[String], elf_attack_power: usize) -> Result<Grid<Element>> {
+    let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+    let mut board = Grid::new(width, lines.len(), Element::default());
     for (j, line) in lines.iter().enumerate() {
-        for (i, ch) in line.chars().enumerate() {
-            match ch {
-                '#' => {
-                    board[[i, j]] = Element {
-                        kind: ElementKind::Wall,
-                        unit: None,
-                    }
-                }
-                '.' => {
-                    board[[i, j]] = Element {
-                        kind: ElementKind::Cavern,
-                        unit: None,
-                    }
-                }
-                'E' => {
-                    board[[i, j]] = Element {
-                        kind: ElementKind::Unit,
-                        unit: Some(Unit::new_elf(elf_attack_power)),
-                    }
-                }
-                'G' => {
-                    board[[i, j]] = Element {
-                        kind: ElementKind::Unit,
-                        unit: Some(Unit::new_goblin()),
-                    }
+        for (i, (col, ch)) in line.char_indices().enumerate() {
+            board[[i, j]] = match ch {
+                '#' => Element {
+                    kind: ElementKind::Wall,
+                    unit: None,
+                },
+                '.' => Element {
+                    kind: ElementKind::Cavern,
+                    unit: None,
+                },
+                'E' => Element {
+                    kind: ElementKind::Unit,
+                    unit: Some(Unit::new_elf(elf_attack_power)),
+                },
+                'G' => Element {
+                    kind: ElementKind::Unit,
+                    unit: Some(Unit::new_goblin()),
+                },
+                _ => {
+                    let found = &line[col..col + ch.len_utf8()];
+                    return Err(ParseError::new(AoCYear::AOC2018, AoCDay::AOCD15, j, line, found, "`#`, `.`, `E` or `G`").into());
                 }
-                _ => return Err(anyhow!("invalid game element type!")),
-            }
+            };
         }
     }
 
     Ok(board)
 }
 
-fn find_enemy_targets(board: &Array2<Element>, unit_kind: UnitKind) -> Vec<[usize; 2]> {
-    let units: HashMap<(usize, usize), &Element> = board
-        .indexed_iter()
+fn find_enemy_targets(board: &Grid<Element>, unit_kind: UnitKind) -> Vec<[usize; 2]> {
+    board
+        .iter()
         .filter(|(_idx, element)| element.kind == ElementKind::Unit)
         .filter(|(_idx, element)| if let Some(ref unit) = element.unit { unit.kind != unit_kind } else { false })
-        .collect();
-    units.keys().map(|(i, j)| [*i, *j]).collect()
+        .map(|(idx, _element)| idx)
+        .collect()
 }
 
-fn calculate_attack(board: &Array2<Element>, curr_unit: &Unit, coord: [usize; 2], target: &mut Option<[usize; 2]>, min_hit_points: &mut usize) {
+/// Whether the element at `coord` is a unit on the other side from `curr_unit`.
+fn is_enemy(board: &Grid<Element>, curr_unit: &Unit, coord: [usize; 2]) -> bool {
     let element = &board[coord];
-
-    if element.kind == ElementKind::Unit
-        && let Some(ref unit) = element.unit
-        && unit.kind != curr_unit.kind
-    {
-        let hit_points = unit.hit_points;
-
-        if hit_points < *min_hit_points {
-            *target = Some(coord);
-            *min_hit_points = hit_points;
-        }
-    }
+    element.kind == ElementKind::Unit && element.unit.as_ref().is_some_and(|unit| unit.kind != curr_unit.kind)
 }
 
-fn attack_adjacent(board: &Array2<Element>, curr_unit: &Unit, i: usize, j: usize, max_i: usize, max_j: usize) -> Option<[usize; 2]> {
+fn attack_adjacent(board: &Grid<Element>, curr_unit: &Unit, coord: [usize; 2]) -> Option<[usize; 2]> {
     let mut target = None;
     let mut min_hit_points = usize::MAX;
 
-    let above = [i, j - 1];
-    let left = [i - 1, j];
-    let right = [i + 1, j];
-    let down = [i, j + 1];
-
-    // Check up first (reading order and all)
-    if j > 0 {
-        calculate_attack(board, curr_unit, above, &mut target, &mut min_hit_points);
-    }
-
-    if i > 0 {
-        calculate_attack(board, curr_unit, left, &mut target, &mut min_hit_points);
-    }
-
-    if i < max_i - 1 {
-        calculate_attack(board, curr_unit, right, &mut target, &mut min_hit_points);
-    }
-
-    if j < max_j - 1 {
-        calculate_attack(board, curr_unit, down, &mut target, &mut min_hit_points);
+    // Neighbors come in reading order, so ties go to the first enemy read.
+    for adjacent in board.neighbors4(coord).filter(|adjacent| is_enemy(board, curr_unit, *adjacent)) {
+        if let Some(ref unit) = board[adjacent].unit
+            && unit.hit_points < min_hit_points
+        {
+            target = Some(adjacent);
+            min_hit_points = unit.hit_points;
+        }
     }
     target
 }
 
-#[allow(clippy::cognitive_complexity)]
-fn move_if_not_adjacent(
-    board: &Array2<Element>,
-    targets: &[[usize; 2]],
-    curr_unit: &Unit,
-    i: usize,
-    j: usize,
-    max_i: usize,
-    max_j: usize,
-) -> Result<Option<[usize; 2]>> {
+fn move_if_not_adjacent(board: &Grid<Element>, targets: &[[usize; 2]], curr_unit: &Unit, coord: [usize; 2]) -> Result<Option<[usize; 2]>> {
     // If the unit has already moved, don't move again.
     if curr_unit.has_moved {
         return Ok(None);
     }
 
     // Check for adjacent units, and return if there are any.
-    if j > 0 {
-        let above = &board[[i, j - 1]];
-
-        if above.kind == ElementKind::Unit
-            && let Some(ref adj_unit) = above.unit
-            && adj_unit.kind != curr_unit.kind
-        {
-            return Ok(None);
-        }
-    }
-
-    if i > 0 {
-        let left = &board[[i - 1, j]];
-
-        if left.kind == ElementKind::Unit
-            && let Some(ref adj_unit) = left.unit
-            && adj_unit.kind != curr_unit.kind
-        {
-            return Ok(None);
-        }
-    }
-
-    if i < max_i - 1 {
-        let right = &board[[i + 1, j]];
-
-        if right.kind == ElementKind::Unit
-            && let Some(ref adj_unit) = right.unit
-            && adj_unit.kind != curr_unit.kind
-        {
-            return Ok(None);
-        }
-    }
-
-    if j < max_j - 1 {
-        let down = &board[[i, j + 1]];
-
-        if down.kind == ElementKind::Unit
-            && let Some(ref adj_unit) = down.unit
-            && adj_unit.kind != curr_unit.kind
-        {
-            return Ok(None);
-        }
+    if board.neighbors4(coord).any(|adjacent| is_enemy(board, curr_unit, adjacent)) {
+        return Ok(None);
     }
 
     // Find the empty spots nearest the targets.
     let actual_locs: Vec<[usize; 2]> = targets
         .iter()
-        .flat_map(|x| {
-            let i = x[0];
-            let j = x[1];
-            let mut caverns = Vec::new();
-            if j > 0 {
-                let above = &board[[i, j - 1]];
-
-                if above.kind == ElementKind::Cavern {
-                    caverns.push([i, j - 1]);
-                }
-            }
-
-            if i > 0 {
-                let left = &board[[i - 1, j]];
-
-                if left.kind == ElementKind::Cavern {
-                    caverns.push([i - 1, j]);
-                }
-            }
-
-            if i < max_i - 1 {
-                let right = &board[[i + 1, j]];
-
-                if right.kind == ElementKind::Cavern {
-                    caverns.push([i + 1, j]);
-                }
-            }
-
-            if j < max_j - 1 {
-                let down = &board[[i, j + 1]];
-
-                if down.kind == ElementKind::Cavern {
-                    caverns.push([i, j + 1]);
-                }
-            }
-
-            caverns
-        })
+        .flat_map(|target| board.neighbors4(*target).filter(|adjacent| board[*adjacent].kind == ElementKind::Cavern))
         .collect();
 
     let mut min_dist = usize::MAX;
@@ -307,22 +246,17 @@ fn move_if_not_adjacent(
     let mut first_step_vec = Vec::new();
 
     for target in actual_locs {
-        let mut visited: Array2<bool> = Array2::default((max_i, max_j));
-
-        Zip::from(&mut visited)
-            .and(board)
-            .for_each(|visited, element| *visited = matches!(element.kind, ElementKind::Wall | ElementKind::Unit));
-
+        let mut visited = board.map(|element| matches!(element.kind, ElementKind::Wall | ElementKind::Unit));
         visited[target] = false;
 
         let mut queue = VecDeque::new();
         let move_queue = VecDeque::new();
-        queue.push_back(([i, j], move_queue, 0));
+        queue.push_back((coord, move_queue, 0));
 
         while !queue.is_empty() {
-            let (coord, mut path, dist) = queue.pop_front().ok_or(anyhow!(""))?;
+            let (curr, mut path, dist) = queue.pop_front().ok_or(anyhow!(""))?;
 
-            if coord == target {
+            if curr == target {
                 #[allow(clippy::comparison_chain)]
                 if dist < min_dist {
                     min_dist = dist;
@@ -337,74 +271,28 @@ fn move_if_not_adjacent(
                 break;
             }
 
-            // Moving up
-            let up_coord = [coord[0], coord[1] - 1];
-            if j > 0 && !visited[up_coord] {
-                let mut new_path = path.clone();
-                new_path.push_back(up_coord);
-                queue.push_back((up_coord, new_path, dist + 1));
-                visited[up_coord] = true;
-            }
-
-            // Moving left
-            let left_coord = [coord[0] - 1, coord[1]];
-            if i > 0 && !visited[left_coord] {
-                let mut new_path = path.clone();
-                new_path.push_back(left_coord);
-                queue.push_back((left_coord, new_path, dist + 1));
-                visited[left_coord] = true;
-            }
-
-            // Moving right
-            let right_coord = [coord[0] + 1, coord[1]];
-
-            if i + 1 < max_i && !visited[right_coord] {
-                let mut new_path = path.clone();
-                new_path.push_back(right_coord);
-                queue.push_back((right_coord, new_path, dist + 1));
-                visited[right_coord] = true;
-            }
-
-            // Moving down
-            let down_coord = [coord[0], coord[1] + 1];
-
-            if j + 1 < max_j && !visited[down_coord] {
-                let mut new_path = path.clone();
-                new_path.push_back(down_coord);
-                queue.push_back((down_coord, new_path, dist + 1));
-                visited[down_coord] = true;
+            // Moving up, left, right and then down
+            for next in board.neighbors4(curr) {
+                if !visited[next] {
+                    let mut new_path = path.clone();
+                    new_path.push_back(next);
+                    queue.push_back((next, new_path, dist + 1));
+                    visited[next] = true;
+                }
             }
         }
     }
 
-    first_step_vec.dedup();
-
-    if !first_step_vec.is_empty() {
-        let above = [i, j - 1];
-        let left = [i - 1, j];
-        let right = [i + 1, j];
-        let down = [i, j + 1];
-
-        if first_step_vec.contains(&above) {
-            Ok(Some(above))
-        } else if first_step_vec.contains(&left) {
-            Ok(Some(left))
-        } else if first_step_vec.contains(&right) {
-            Ok(Some(right))
-        } else {
-            Ok(Some(down))
-        }
-    } else {
-        Ok(None)
-    }
+    // Take the first step in reading order.
+    Ok(board.neighbors4(coord).find(|step| first_step_vec.contains(step)))
 }
 
-fn take_turn(board: &mut Array2<Element>, i: usize, j: usize, max_i: usize, max_j: usize, second_star: bool) -> Result<usize> {
+fn take_turn(board: &mut Grid<Element>, coord: [usize; 2], second_star: bool) -> Result<usize> {
     let mut move_vec = Vec::new();
 
     // Scope for mutable board change below.
     {
-        let curr_cell = &board[[i, j]];
+        let curr_cell = &board[coord];
 
         match curr_cell.kind {
             ElementKind::Wall | ElementKind::Cavern => {}
@@ -416,8 +304,8 @@ fn take_turn(board: &mut Array2<Element>, i: usize, j: usize, max_i: usize, max_
                         return Ok(0);
                     }
 
-                    if let Ok(Some(target)) = move_if_not_adjacent(board, &targets, unit, i, j, max_i, max_j) {
-                        move_vec.push((Action::Move([i, j]), target));
+                    if let Ok(Some(target)) = move_if_not_adjacent(board, &targets, unit, coord) {
+                        move_vec.push((Action::Move(coord), target));
                     } else {
                         move_vec.push((Action::No, [0, 0]));
                     }
@@ -429,18 +317,18 @@ fn take_turn(board: &mut Array2<Element>, i: usize, j: usize, max_i: usize, max_
     let mut next_coord = [0, 0];
     let mut moved = false;
 
-    for (action, coord) in move_vec {
+    for (action, target) in move_vec {
         match action {
             Action::Attack([_, _]) => return Err(anyhow!("Attack in Move Phase")),
-            Action::Move([i, j]) => {
-                board[coord] = board[[i, j]].clone();
+            Action::Move(from) => {
+                board[target] = board[from].clone();
                 moved = true;
-                next_coord = coord;
+                next_coord = target;
 
-                if let Some(ref mut unit) = board[coord].unit {
+                if let Some(ref mut unit) = board[target].unit {
                     unit.has_moved = true;
                 }
-                board[[i, j]] = Element {
+                board[from] = Element {
                     kind: ElementKind::Cavern,
                     unit: None,
                 };
@@ -452,9 +340,8 @@ fn take_turn(board: &mut Array2<Element>, i: usize, j: usize, max_i: usize, max_
     let mut attack_vec = Vec::new();
 
     {
-        let curr_cell = if moved { &board[next_coord] } else { &board[[i, j]] };
-        let i = if moved { next_coord[0] } else { i };
-        let j = if moved { next_coord[1] } else { j };
+        let coord = if moved { next_coord } else { coord };
+        let curr_cell = &board[coord];
 
         match curr_cell.kind {
             ElementKind::Wall | ElementKind::Cavern => {}
@@ -462,8 +349,8 @@ fn take_turn(board: &mut Array2<Element>, i: usize, j: usize, max_i: usize, max_
                 if let Some(ref unit) = curr_cell.unit
                     && !unit.has_attacked
                 {
-                    if let Some(target) = attack_adjacent(board, unit, i, j, max_i, max_j) {
-                        attack_vec.push((Action::Attack([i, j]), target, Some(unit.attack_power)));
+                    if let Some(target) = attack_adjacent(board, unit, coord) {
+                        attack_vec.push((Action::Attack(coord), target, Some(unit.attack_power)));
                     } else {
                         attack_vec.push((Action::No, [0, 0], None));
                     }
@@ -472,14 +359,14 @@ fn take_turn(board: &mut Array2<Element>, i: usize, j: usize, max_i: usize, max_
         }
     }
 
-    for (action, coord, atk_pwr_opt) in attack_vec {
+    for (action, target, atk_pwr_opt) in attack_vec {
         match action {
-            Action::Attack([i, j]) => {
+            Action::Attack(attacker) => {
                 let mut dead = false;
-                if let Some(ref mut unit) = board[[i, j]].unit {
+                if let Some(ref mut unit) = board[attacker].unit {
                     unit.has_attacked = true;
                 }
-                if let Some(ref mut unit) = board[coord].unit
+                if let Some(ref mut unit) = board[target].unit
                     && let Some(atk_pwr) = atk_pwr_opt
                 {
                     unit.hit_points = unit.hit_points.saturating_sub(atk_pwr);
@@ -494,7 +381,7 @@ fn take_turn(board: &mut Array2<Element>, i: usize, j: usize, max_i: usize, max_
                 }
 
                 if dead {
-                    board[coord] = Element {
+                    board[target] = Element {
                         kind: ElementKind::Cavern,
                         unit: None,
                     };
@@ -508,8 +395,8 @@ fn take_turn(board: &mut Array2<Element>, i: usize, j: usize, max_i: usize, max_
     Ok(1)
 }
 
-fn reset_units(board: &mut Array2<Element>, i: usize, j: usize) {
-    let element = &mut board[[i, j]];
+fn reset_units(board: &mut Grid<Element>, coord: [usize; 2]) {
+    let element = &mut board[coord];
 
     if element.kind == ElementKind::Unit
         && let Some(ref mut unit) = element.unit
@@ -525,30 +412,26 @@ enum Outcome {
     BattleOn,
 }
 
-fn round(board: &mut Array2<Element>, i: usize, j: usize, second_star: bool) -> Result<Outcome> {
+fn round(board: &mut Grid<Element>, second_star: bool) -> Result<Outcome> {
     let mut done = false;
     let mut dead_elf = false;
 
-    'outer: for row in 0..j {
-        for col in 0..i {
-            let result = take_turn(board, col, row, i, j, second_star)?;
+    for coord in board.positions() {
+        let result = take_turn(board, coord, second_star)?;
 
-            if result == 0 {
-                done = true;
-                break 'outer;
-            } else if result == 2 {
-                done = true;
-                dead_elf = true;
-                break 'outer;
-            }
+        if result == 0 {
+            done = true;
+            break;
+        } else if result == 2 {
+            done = true;
+            dead_elf = true;
+            break;
         }
     }
 
     if !done {
-        for row in 0..j {
-            for col in 0..i {
-                reset_units(board, col, row);
-            }
+        for coord in board.positions() {
+            reset_units(board, coord);
         }
     }
 
@@ -563,218 +446,159 @@ fn round(board: &mut Array2<Element>, i: usize, j: usize, second_star: bool) ->
     }
 }
 
-fn run_battle<T>(reader: T, max_i: usize, max_j: usize, second_star: bool, test: bool) -> Result<usize>
+/// The result of a single battle.
+struct Battle {
+    /// The number of full rounds times the hit points left standing
+    outcome: usize,
+    /// Whether every elf survived the battle
+    flawless: bool,
+}
+
+fn run_battle<T>(reader: T, second_star: bool, ctx: &Context) -> Result<usize>
 where
     T: BufRead,
 {
-    let lines: Vec<String> = reader.lines().map_while(Result::ok).collect();
-    let mut board = Array2::default((0, 0));
-    let mut dead_elf = true;
+    let lines: Vec<String> = reader.lines().collect::<Result<_, _>>()?;
+
+    if second_star {
+        rescue_elves(&lines, 3, ctx)
+    } else {
+        Ok(fight(&lines, 3, false, ctx)?.outcome)
+    }
+}
+
+/// Raise the elf attack power, starting at `elf_attack_power`, until a battle is won without losing an elf.
+fn rescue_elves(lines: &[String], elf_attack_power: usize, ctx: &Context) -> Result<usize> {
+    for attack_power in elf_attack_power.. {
+        if ctx.tracing() {
+            eprintln!("Elf attack power {attack_power}");
+        }
+        let battle = fight(lines, attack_power, true, ctx)?;
+
+        if battle.flawless {
+            return Ok(battle.outcome);
+        }
+    }
+    Err(anyhow!("the elves can never win without a loss"))
+}
+
+/// Fight a single battle with the given elf attack power.
+///
+/// When `second_star` is set the battle stops as soon as an elf dies.  Every round is drawn
+/// when visualizing, and the final board is traced.
+fn fight(lines: &[String], elf_attack_power: usize, second_star: bool, ctx: &Context) -> Result<Battle> {
+    let mut board = generate_map(lines, elf_attack_power)?;
+    let elves = count_elves(&board);
     let mut round_count = 0;
-    let mut elf_attack_power = 3;
-
-    while dead_elf {
-        round_count = 0;
-        board = generate_map(&lines, max_i, max_j, elf_attack_power)?;
-
-        let mut done = false;
-        while !done {
-            match round(&mut board, max_i, max_j, second_star)? {
-                Outcome::NoMoreEnemies => {
-                    dead_elf = false;
-                    done = true;
-                }
-                Outcome::DeadElf => {
-                    if second_star {
-                        done = true;
-                    }
-                }
-                _ => {}
-            }
+    let mut dead_elf = false;
 
-            if !done {
-                round_count += 1;
-            } else if done && test {
-                print_board(&board, round_count);
+    loop {
+        if ctx.visualizing() {
+            ctx.frame(&render_board(&board, round_count));
+        }
+        match round(&mut board, second_star)? {
+            Outcome::NoMoreEnemies => break,
+            Outcome::DeadElf => {
+                dead_elf = true;
+                break;
             }
+            Outcome::BattleOn => round_count += 1,
         }
-        elf_attack_power += 1;
+    }
+
+    if ctx.tracing() {
+        eprint!("{}", render_board(&board, round_count));
     }
 
     let hps: usize = board
-        .iter()
+        .cells()
         .filter_map(|x| if x.kind == ElementKind::Unit { x.unit.clone() } else { None })
         .map(|u| u.hit_points)
         .sum();
-    Ok(round_count * hps)
+    Ok(Battle {
+        outcome: round_count * hps,
+        flawless: !dead_elf && count_elves(&board) == elves,
+    })
 }
 
-fn print_board(board: &Array2<Element>, round: usize) {
+/// Count the elves left on the board.
+fn count_elves(board: &Grid<Element>) -> usize {
+    board.cells().filter(|x| x.unit.as_ref().is_some_and(|u| u.kind == UnitKind::Elf)).count()
+}
+
+/// Draw the board after `round` rounds, with each row's units and their hit points.
+fn render_board(board: &Grid<Element>, round: usize) -> String {
     use std::fmt::Write;
 
-    if round == 0 {
-        println!("Initially:");
-    } else if round == 1 {
-        println!("After 1 round:");
-    } else {
-        println!("After {round} rounds:");
-    }
-    for row in board.axis_iter(Axis(1)) {
+    let mut buffer = match round {
+        0 => "Initially:\n".to_string(),
+        1 => "After 1 round:\n".to_string(),
+        _ => format!("After {round} rounds:\n"),
+    };
+    for row in board.rows() {
         let mut unit_vec = Vec::new();
         for cell in row {
             if let Some(ref unit) = cell.unit {
                 unit_vec.push((unit.kind, unit.hit_points));
             }
-            print!("{cell}");
+            write!(buffer, "{cell}").expect("");
         }
 
-        let mut buffer = String::new();
         if !unit_vec.is_empty() {
-            buffer.push_str("  ");
-
-            for (kind, hitpoints) in unit_vec {
-                write!(buffer, "{kind}({hitpoints}), ").expect("");
-            }
+            let units: Vec<String> = unit_vec.iter().map(|(kind, hitpoints)| format!("{kind}({hitpoints})")).collect();
+            write!(buffer, "  {}", units.join(", ")).expect("");
         }
-        let x: &[_] = &[',', ' '];
-        print!("{}", buffer.trim_end_matches(x));
-        println!();
+        buffer.push('\n');
     }
-    println!();
+    buffer.push('\n');
+    buffer
 }
 
 #[cfg(test)]
 mod one_star {
     use super::run_battle;
+    use crate::constants::{AoCDay, AoCYear};
+    use crate::context::Context;
+    use crate::examples;
     use anyhow::Result;
     use std::io::Cursor;
 
-    const TEST_BOARD: &str = r"#######
-#.G...#
-#...EG#
-#.#.#G#
-#..G#E#
-#.....#
-#######";
-
-    const TEST_BOARD_2: &str = r"#######
-#G..#E#
-#E#E.E#
-#G.##.#
-#...#E#
-#...E.#
-#######";
-
-    const TEST_BOARD_3: &str = r"#######
-#E..EG#
-#.#G.E#
-#E.##E#
-#G..#.#
-#..E#.#
-#######";
-
-    const TEST_BOARD_4: &str = r"#######
-#E.G#.#
-#.#G..#
-#G.#.G#
-#G..#.#
-#...E.#
-#######";
-
-    const TEST_BOARD_5: &str = r"#######
-#.E...#
-#.#..G#
-#.###.#
-#E#G#G#
-#...#G#
-#######";
-
-    const TEST_BOARD_6: &str = r"#########
-#G......#
-#.E.#...#
-#..##..G#
-#...##..#
-#...#...#
-#.G...G.#
-#.....G.#
-#########";
-
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(run_battle(Cursor::new(TEST_BOARD), 7, 7, false, true)?, 27730);
-        assert_eq!(run_battle(Cursor::new(TEST_BOARD_2), 7, 7, false, true)?, 36334);
-        assert_eq!(run_battle(Cursor::new(TEST_BOARD_3), 7, 7, false, true)?, 39514);
-        assert_eq!(run_battle(Cursor::new(TEST_BOARD_4), 7, 7, false, true)?, 27755);
-        assert_eq!(run_battle(Cursor::new(TEST_BOARD_5), 7, 7, false, true)?, 28944);
-        assert_eq!(run_battle(Cursor::new(TEST_BOARD_6), 9, 9, false, true)?, 18740);
+        for (name, outcome) in [("1", 27730), ("2", 36334), ("3", 39514), ("4", 27755), ("5", 28944), ("6", 18740)] {
+            let board = examples::input(AoCYear::AOC2018, AoCDay::AOCD15, name)?;
+            assert_eq!(run_battle(Cursor::new(board), false, &Context::default())?, outcome, "example {name}");
+        }
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod two_star {
-    use super::run_battle;
+    use super::{Day15, run_battle};
+    use crate::answer::Answer;
+    use crate::constants::{AoCDay, AoCYear};
+    use crate::context::Context;
+    use crate::examples;
+    use crate::solver::Solver;
     use anyhow::Result;
     use std::io::Cursor;
 
-    const TEST_BOARD: &str = r"#######
-#.G...#
-#...EG#
-#.#.#G#
-#..G#E#
-#.....#
-#######";
-
-    const TEST_BOARD_2: &str = r"#######
-#G..#E#
-#E#E.E#
-#G.##.#
-#...#E#
-#...E.#
-#######";
-
-    const TEST_BOARD_3: &str = r"#######
-#E..EG#
-#.#G.E#
-#E.##E#
-#G..#.#
-#..E#.#
-#######";
-
-    const TEST_BOARD_4: &str = r"#######
-#E.G#.#
-#.#G..#
-#G.#.G#
-#G..#.#
-#...E.#
-#######";
-
-    const TEST_BOARD_5: &str = r"#######
-#.E...#
-#.#..G#
-#.###.#
-#E#G#G#
-#...#G#
-#######";
-
-    const TEST_BOARD_6: &str = r"#########
-#G......#
-#.E.#...#
-#..##..G#
-#...##..#
-#...#...#
-#.G...G.#
-#.....G.#
-#########";
-
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(run_battle(Cursor::new(TEST_BOARD), 7, 7, true, false)?, 4988);
-        assert_eq!(run_battle(Cursor::new(TEST_BOARD_2), 7, 7, true, false)?, 29064);
-        assert_eq!(run_battle(Cursor::new(TEST_BOARD_3), 7, 7, true, false)?, 31284);
-        assert_eq!(run_battle(Cursor::new(TEST_BOARD_4), 7, 7, true, false)?, 3478);
-        assert_eq!(run_battle(Cursor::new(TEST_BOARD_5), 7, 7, true, false)?, 6474);
-        assert_eq!(run_battle(Cursor::new(TEST_BOARD_6), 9, 9, true, true)?, 1140);
+        for (name, outcome) in [("1", 4988), ("2", 29064), ("3", 31284), ("4", 3478), ("5", 6474), ("6", 1140)] {
+            let board = examples::input(AoCYear::AOC2018, AoCDay::AOCD15, name)?;
+            assert_eq!(run_battle(Cursor::new(board), true, &Context::default())?, outcome, "example {name}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn both() -> Result<()> {
+        let board = examples::input(AoCYear::AOC2018, AoCDay::AOCD15, "1")?;
+        let (first, second) = Day15.both(&mut Cursor::new(board), &Context::default())?;
+        assert_eq!(first.answer().as_ref().ok(), Some(&Answer::Unsigned(27730)));
+        assert_eq!(second.answer().as_ref().ok(), Some(&Answer::Unsigned(4988)));
         Ok(())
     }
 }
@@ -1,71 +1,43 @@
 //! Advent of Code - Day 16 "Chronal Classification" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::parse::{self, ParseError};
+use crate::solver::Solver;
+use crate::year2018::elfcode::{self, Machine, OpCode, Program};
 use anyhow::{Result, anyhow};
 use regex::Regex;
 use std::collections::BTreeMap;
 use std::io::BufRead;
 
-type Registers = [usize; 4];
-type Instruction = [usize; 4];
+/// Day 16 "Chronal Classification"
+pub(crate) struct Day16;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
-enum OpCode {
-    /// (add register) stores into register C the result of adding register A and register B.
-    Addr,
-    /// (add immediate) stores into register C the result of adding register A and value B.
-    Addi,
-    /// (multiply register) stores into register C the result of multiplying register A and register B.
-    Mulr,
-    /// (multiply immediate) stores into register C the result of multiplying register A and value B.
-    Muli,
-    /// (bitwise AND register) stores into register C the result of the bitwise AND of register A and register B.
-    Banr,
-    /// (bitwise AND immediate) stores into register C the result of the bitwise AND of register A and value B.
-    Bani,
-    /// (bitwise OR register) stores into register C the result of the bitwise OR of register A and register B.
-    Borr,
-    /// (bitwise OR immediate) stores into register C the result of the bitwise OR of register A and value B.
-    Bori,
-    /// (set register) copies the contents of register A into register C. (Input B is ignored.)
-    Setr,
-    /// (set immediate) stores value A into register C. (Input B is ignored.)
-    Seti,
-    /// (greater-than immediate/register) sets register C to 1 if value A is greater than register B. Otherwise, register C is set to 0.
-    Gtir,
-    /// (greater-than register/immediate) sets register C to 1 if register A is greater than value B. Otherwise, register C is set to 0.
-    Gtri,
-    /// (greater-than register/register) sets register C to 1 if register A is greater than register B. Otherwise, register C is set to 0.
-    Gtrr,
-    /// (equal immediate/register) sets register C to 1 if value A is equal to register B. Otherwise, register C is set to 0.
-    Eqir,
-    /// (equal register/immediate) sets register C to 1 if register A is equal to value B. Otherwise, register C is set to 0.
-    Eqri,
-    /// (equal register/register) sets register C to 1 if register A is equal to register B. Otherwise, register C is set to 0.
-    Eqrr,
-}
+impl Solver for Day16 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
 
-impl OpCode {
-    fn execute(&self, reg: &mut Registers, ins: Instruction) {
-        match self {
-            OpCode::Addr => reg[ins[3]] = reg[ins[1]] + reg[ins[2]],
-            OpCode::Addi => reg[ins[3]] = reg[ins[1]] + ins[2],
-            OpCode::Mulr => reg[ins[3]] = reg[ins[1]] * reg[ins[2]],
-            OpCode::Muli => reg[ins[3]] = reg[ins[1]] * ins[2],
-            OpCode::Banr => reg[ins[3]] = reg[ins[1]] & reg[ins[2]],
-            OpCode::Bani => reg[ins[3]] = reg[ins[1]] & ins[2],
-            OpCode::Borr => reg[ins[3]] = reg[ins[1]] | reg[ins[2]],
-            OpCode::Bori => reg[ins[3]] = reg[ins[1]] | ins[2],
-            OpCode::Setr => reg[ins[3]] = reg[ins[1]],
-            OpCode::Seti => reg[ins[3]] = ins[1],
-            OpCode::Gtir => reg[ins[3]] = usize::from(ins[1] > reg[ins[2]]),
-            OpCode::Gtri => reg[ins[3]] = usize::from(reg[ins[1]] > ins[2]),
-            OpCode::Gtrr => reg[ins[3]] = usize::from(reg[ins[1]] > reg[ins[2]]),
-            OpCode::Eqir => reg[ins[3]] = usize::from(ins[1] == reg[ins[2]]),
-            OpCode::Eqri => reg[ins[3]] = usize::from(reg[ins[1]] == ins[2]),
-            OpCode::Eqrr => reg[ins[3]] = usize::from(reg[ins[1]] == reg[ins[2]]),
-        }
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD16
+    }
+
+    fn title(&self) -> &'static str {
+        "Chronal Classification"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false)
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true)
     }
 }
 
+type Registers = [usize; 4];
+type Instruction = [usize; 4];
+
 fn execute_opcode(
     opcode: OpCode,
     before: [usize; 4],
@@ -76,7 +48,7 @@ fn execute_opcode(
     opcode_map: &mut BTreeMap<usize, Vec<OpCode>>,
 ) {
     let mut regs = before;
-    opcode.execute(&mut regs, ins);
+    opcode.execute(&mut regs, [ins[1], ins[2], ins[3]]);
     if regs == after {
         *count += 1;
     }
@@ -89,48 +61,52 @@ fn execute_opcode(
     }
 }
 
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    let before_re = Regex::new(r"Before: \[(\d), (\d), (\d), (\d)\]")?;
-    let after_re = Regex::new(r"After:  \[(\d), (\d), (\d), (\d)\]")?;
-    let instruction_re = Regex::new(r"(\d+) (\d) (\d) (\d)")?;
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<Answer> {
+    let before_re = Regex::new(r"^Before: \[(\d+), (\d+), (\d+), (\d+)\]$")?;
+    let after_re = Regex::new(r"^After:  \[(\d+), (\d+), (\d+), (\d+)\]$")?;
+    let instruction_re = Regex::new(r"^(\d+) (\d+) (\d+) (\d+)$")?;
 
     let mut before_vec = Vec::new();
     let mut after_vec = Vec::new();
     let mut instructions_vec = Vec::new();
 
-    for line in reader.lines().map_while(Result::ok) {
-        if before_re.is_match(&line) {
-            for caps in before_re.captures_iter(&line) {
-                let opcode = (caps[1]).parse::<usize>()?;
-                let reg_a = (caps[2]).parse::<usize>()?;
-                let reg_b = (caps[3]).parse::<usize>()?;
-                let reg_c = (caps[4]).parse::<usize>()?;
-
-                before_vec.push([opcode, reg_a, reg_b, reg_c]);
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let error = |token: &str, expected: &str| ParseError::new(AoCYear::AOC2018, AoCDay::AOCD16, idx, &line, token, expected);
+        let numbers = |caps: &regex::Captures| -> Result<[usize; 4]> {
+            let number = |i: usize| parse::number(AoCYear::AOC2018, AoCDay::AOCD16, idx, &line, &caps[i]);
+            Ok([number(1)?, number(2)?, number(3)?, number(4)?])
+        };
+        if let Some(caps) = before_re.captures(&line) {
+            before_vec.push(numbers(&caps)?);
+        } else if let Some(caps) = after_re.captures(&line) {
+            after_vec.push(numbers(&caps)?);
+        } else if let Some(caps) = instruction_re.captures(&line) {
+            let ins = numbers(&caps)?;
+            if ins[0] >= OpCode::ALL.len() {
+                return Err(error(&caps[1], "an opcode from 0 to 15").into());
             }
-        } else if after_re.is_match(&line) {
-            for caps in after_re.captures_iter(&line) {
-                let opcode = (caps[1]).parse::<usize>()?;
-                let reg_a = (caps[2]).parse::<usize>()?;
-                let reg_b = (caps[3]).parse::<usize>()?;
-                let reg_c = (caps[4]).parse::<usize>()?;
-
-                after_vec.push([opcode, reg_a, reg_b, reg_c]);
-            }
-        } else if instruction_re.is_match(&line) {
-            for caps in instruction_re.captures_iter(&line) {
-                let opcode = (caps[1]).parse::<usize>()?;
-                let reg_a = (caps[2]).parse::<usize>()?;
-                let reg_b = (caps[3]).parse::<usize>()?;
-                let reg_c = (caps[4]).parse::<usize>()?;
-
-                instructions_vec.push([opcode, reg_a, reg_b, reg_c]);
+            // A sample is tried against every opcode, so each of its operands may be read as a register.
+            let in_sample = before_vec.len() > instructions_vec.len();
+            let registers = if in_sample { 1..4 } else { 3..4 };
+            if let Some(i) = registers.into_iter().find(|&i| ins[i] >= 4) {
+                return Err(error(&caps[i + 1], "a register from 0 to 3").into());
             }
+            instructions_vec.push(ins);
+        } else if !line.trim().is_empty() {
+            return Err(ParseError::whole_line(
+                AoCYear::AOC2018,
+                AoCDay::AOCD16,
+                idx,
+                &line,
+                "a `Before:` or `After:` sample, or an instruction",
+            )
+            .into());
         }
     }
 
-    if before_vec.len() != after_vec.len() {
-        return Err(anyhow!("Bad input file"));
+    if before_vec.len() != after_vec.len() || instructions_vec.len() < before_vec.len() {
+        return Err(anyhow!("every sample needs a `Before:` line, an instruction and an `After:` line"));
     }
 
     let instructions = instructions_vec.split_off(before_vec.len());
@@ -148,22 +124,9 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
     for (before, ins, after) in tuples_vec {
         let mut count = 0;
 
-        execute_opcode(OpCode::Addr, before, ins, after, &mut count, second_star, &mut opcode_map);
-        execute_opcode(OpCode::Addi, before, ins, after, &mut count, second_star, &mut opcode_map);
-        execute_opcode(OpCode::Mulr, before, ins, after, &mut count, second_star, &mut opcode_map);
-        execute_opcode(OpCode::Muli, before, ins, after, &mut count, second_star, &mut opcode_map);
-        execute_opcode(OpCode::Banr, before, ins, after, &mut count, second_star, &mut opcode_map);
-        execute_opcode(OpCode::Bani, before, ins, after, &mut count, second_star, &mut opcode_map);
-        execute_opcode(OpCode::Borr, before, ins, after, &mut count, second_star, &mut opcode_map);
-        execute_opcode(OpCode::Bori, before, ins, after, &mut count, second_star, &mut opcode_map);
-        execute_opcode(OpCode::Setr, before, ins, after, &mut count, second_star, &mut opcode_map);
-        execute_opcode(OpCode::Seti, before, ins, after, &mut count, second_star, &mut opcode_map);
-        execute_opcode(OpCode::Gtir, before, ins, after, &mut count, second_star, &mut opcode_map);
-        execute_opcode(OpCode::Gtri, before, ins, after, &mut count, second_star, &mut opcode_map);
-        execute_opcode(OpCode::Gtrr, before, ins, after, &mut count, second_star, &mut opcode_map);
-        execute_opcode(OpCode::Eqir, before, ins, after, &mut count, second_star, &mut opcode_map);
-        execute_opcode(OpCode::Eqri, before, ins, after, &mut count, second_star, &mut opcode_map);
-        execute_opcode(OpCode::Eqrr, before, ins, after, &mut count, second_star, &mut opcode_map);
+        for opcode in OpCode::ALL {
+            execute_opcode(opcode, before, ins, after, &mut count, second_star, &mut opcode_map);
+        }
 
         if count >= 3 {
             three_or_more += 1;
@@ -171,37 +134,47 @@ pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
     }
 
     if second_star {
-        let mut known = OpCode::Addr;
         let mut op_map = BTreeMap::new();
 
         while op_map.len() != 16 {
-            for (opcode, poss) in &opcode_map {
-                if poss.len() == 1 {
-                    known = poss[0].clone();
-                    op_map.insert(*opcode, known.clone());
-                    break;
-                }
-            }
+            let found = opcode_map.iter().find(|(_, poss)| poss.len() == 1);
+            let Some((opcode, poss)) = found else {
+                return Err(anyhow!("the samples do not pin down every opcode"));
+            };
+            let known = poss[0];
+            op_map.insert(*opcode, known);
 
             for poss in opcode_map.values_mut() {
                 poss.retain(|x| *x != known);
             }
         }
 
-        let mut registers = [0, 0, 0, 0];
-        for ins in instructions {
-            let opcode = op_map.get(&ins[0]).ok_or(anyhow!("invalid opcode"))?;
-            opcode.execute(&mut registers, ins);
-        }
-        Ok(registers[0] as u32)
+        let instructions = instructions
+            .into_iter()
+            .map(|ins| {
+                let opcode = *op_map.get(&ins[0]).ok_or(anyhow!("opcode {} was never identified from the samples", ins[0]))?;
+                Ok(elfcode::Instruction {
+                    opcode,
+                    args: [ins[1], ins[2], ins[3]],
+                })
+            })
+            .collect::<Result<_>>()?;
+        let program = Program {
+            instructions,
+            ..Program::default()
+        };
+        let mut machine = Machine::<4>::new(&program)?;
+        machine.run(&Context::default())?;
+        Ok(machine.registers[0].into())
     } else {
-        Ok(three_or_more)
+        Ok(three_or_more.into())
     }
 }
 
 #[cfg(test)]
 mod one_star {
     use super::find_solution;
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -217,7 +190,7 @@ After:  [3, 2, 2, 1]
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(find_solution(Cursor::new(TEST_CODE), false)?, 1);
+        assert_eq!(find_solution(Cursor::new(TEST_CODE), false)?, Answer::Signed(1));
         Ok(())
     }
 }
@@ -225,6 +198,7 @@ After:  [3, 2, 2, 1]
 #[cfg(test)]
 mod two_star {
     use super::find_solution;
+    use crate::answer::Answer;
     use anyhow::Result;
     use std::fs::File;
     use std::io::BufReader;
@@ -233,7 +207,7 @@ mod two_star {
     fn solution() -> Result<()> {
         let data_file = File::open("data/2018/day16/data_file")?;
         let reader = BufReader::new(data_file);
-        assert_eq!(find_solution(reader, true)?, 481);
+        assert_eq!(find_solution(reader, true)?, Answer::Unsigned(481));
         Ok(())
     }
 }
@@ -1,11 +1,49 @@
 //! Advent of Code - Day 17 "Reservoir Research" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::grid::Grid;
+use crate::parse::{self, ParseError};
+use crate::solver::{Solver, Unsolved};
 use anyhow::{Result, anyhow};
-use ndarray::Array2;
 use regex::Regex;
 use std::collections::HashMap;
 use std::fmt;
 use std::io::BufRead;
 
+/// Day 17 "Reservoir Research"
+pub(crate) struct Day17;
+
+impl Solver for Day17 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD17
+    }
+
+    fn title(&self) -> &'static str {
+        "Reservoir Research"
+    }
+
+    fn stars(&self) -> u8 {
+        0
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, &Context::default())
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, &Context::default())
+    }
+
+    fn star(&self, _part: u8, reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer> {
+        find_solution(reader, ctx)
+    }
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 enum SoilKind {
     Clay,
@@ -38,105 +76,84 @@ struct Soil {
     moved: bool,
 }
 
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    let result = run_scan(reader, second_star, false)?;
-    Ok(result as u32)
+/// Neither star is solved yet: the scan is read and its first drips drawn when visualizing, but
+/// the water never settles into an answer.
+pub fn find_solution<T: BufRead>(reader: T, ctx: &Context) -> Result<Answer> {
+    run_scan(reader, ctx)?;
+    Err(Unsolved.into())
 }
 
-fn run_scan<T: BufRead>(reader: T, _second_star: bool, test: bool) -> Result<usize> {
-    let vein_re = Regex::new(r"(x|y)=(\d+), (x|y)=(\d+)\.\.(\d+)")?;
+fn run_scan<T: BufRead>(reader: T, ctx: &Context) -> Result<()> {
+    let vein_re = Regex::new(r"^(x|y)=(\d+), (x|y)=(\d+)\.\.(\d+)$")?;
     let mut x_coord_map = HashMap::new();
     let mut y_coord_map = HashMap::new();
 
-    for line in reader.lines().map_while(Result::ok) {
-        for caps in vein_re.captures_iter(&line) {
-            let c1 = (caps[1]).to_string();
-            let v1 = (caps[2]).parse::<usize>()?;
-            let r1 = (caps[4]).parse::<usize>()?;
-            let r2 = (caps[5]).parse::<usize>()?;
-
-            match &c1[..] {
-                "x" => {
-                    let range_vec = x_coord_map.entry(v1).or_insert_with(Vec::new);
-
-                    for i in r1..=r2 {
-                        range_vec.push(i);
-                    }
-                }
-                "y" => {
-                    let range_vec = y_coord_map.entry(v1).or_insert_with(Vec::new);
-
-                    for i in r1..=r2 {
-                        range_vec.push(i);
-                    }
-                }
-                _ => return Err(anyhow!("invalid coordinate")),
-            }
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let caps = vein_re
+            .captures(&line)
+            .ok_or_else(|| ParseError::whole_line(AoCYear::AOC2018, AoCDay::AOCD17, idx, &line, "a vein like `x=495, y=2..7`"))?;
+        if caps[1] == caps[3] {
+            return Err(ParseError::new(AoCYear::AOC2018, AoCDay::AOCD17, idx, &line, &caps[3], "the other axis").into());
         }
+        let number = |i: usize| parse::number::<usize>(AoCYear::AOC2018, AoCDay::AOCD17, idx, &line, &caps[i]);
+        let (v1, r1, r2) = (number(2)?, number(4)?, number(5)?);
+        let coord_map = if &caps[1] == "x" { &mut x_coord_map } else { &mut y_coord_map };
+        coord_map.entry(v1).or_insert_with(Vec::new).extend(r1..=r2);
     }
 
     let mins_maxes = calculate_mins_maxes(&x_coord_map, &y_coord_map)?;
-    let mut scan_arr = setup_scan(mins_maxes, &x_coord_map, &y_coord_map, test);
+    let mut scan_arr = setup_scan(mins_maxes, &x_coord_map, &y_coord_map);
+    if ctx.visualizing() {
+        ctx.frame(&render_scan_arr(mins_maxes, &scan_arr));
+    }
 
     for _ in 0..17 {
-        drip(mins_maxes, &mut scan_arr, test)?;
+        drip(mins_maxes, &mut scan_arr, ctx);
     }
 
-    Ok(1)
+    Ok(())
 }
 
-fn drip(mins_maxes: (usize, usize, usize, usize), scan_arr: &mut Array2<Soil>, test: bool) -> Result<()> {
-    move_flowing_water(mins_maxes, scan_arr)?;
+fn drip(mins_maxes: (usize, usize, usize, usize), scan_arr: &mut Grid<Soil>, ctx: &Context) {
+    move_flowing_water(scan_arr, ctx);
 
     if scan_arr[[500, 1]].kind == SoilKind::Sand {
         scan_arr[[500, 1]].kind = SoilKind::FlowingWater;
     }
 
-    if test {
-        print_scan_arr(mins_maxes, scan_arr);
+    if ctx.visualizing() {
+        ctx.frame(&render_scan_arr(mins_maxes, scan_arr));
     }
-
-    Ok(())
 }
 
-fn move_flowing_water(mins_maxes: (usize, usize, usize, usize), scan_arr: &mut Array2<Soil>) -> Result<()> {
-    let (_, max_i, _, max_j) = mins_maxes;
-
+fn move_flowing_water(scan_arr: &mut Grid<Soil>, ctx: &Context) {
     let mut flowing_water: Vec<[usize; 2]> = scan_arr
-        .indexed_iter()
+        .iter()
         .filter_map(|(x, y)| if y.kind == SoilKind::FlowingWater { Some(x) } else { None })
-        .map(Into::into)
         .collect();
 
     flowing_water.sort_by(|a, b| if a[1] == b[1] { a[0].cmp(&b[0]) } else { b[1].cmp(&a[1]) });
-    println!("Flowing Water: {:?}", &flowing_water);
+    if ctx.tracing() {
+        eprintln!("Flowing Water: {:?}", &flowing_water);
+    }
 
-    for idx in &flowing_water {
-        let i = idx[0];
-        let j = idx[1];
+    for &[i, j] in &flowing_water {
+        let is_sand = |pos: [usize; 2]| scan_arr.get(pos).is_some_and(|soil| soil.kind == SoilKind::Sand);
 
         // Check if down is sand and move if it is, else check
         // if left is sand and move if it is, else check if right
         // is sand and move if it is.
-        if j < max_j && scan_arr[[i, j + 1]].kind == SoilKind::Sand {
-            scan_arr[[i, j]].kind = SoilKind::Sand;
-            scan_arr[[i, j + 1]].kind = SoilKind::FlowingWater;
-            scan_arr[[i, j + 1]].moved = true;
-        } else if i > 0 && scan_arr[[i - 1, j]].kind == SoilKind::Sand {
+        let next = [[i, j + 1], [i.wrapping_sub(1), j], [i + 1, j]].into_iter().find(|pos| is_sand(*pos));
+        if let Some(next) = next {
             scan_arr[[i, j]].kind = SoilKind::Sand;
-            scan_arr[[i - 1, j]].kind = SoilKind::FlowingWater;
-            scan_arr[[i - 1, j]].moved = true;
-        } else if i < max_i && scan_arr[[i + 1, j]].kind == SoilKind::Sand {
-            scan_arr[[i, j]].kind = SoilKind::Sand;
-            scan_arr[[i + 1, j]].kind = SoilKind::FlowingWater;
-            scan_arr[[i + 1, j]].moved = true;
+            scan_arr[next].kind = SoilKind::FlowingWater;
+            scan_arr[next].moved = true;
         } else {
             // Am i bounded left and right?  If so, I'm standing
             scan_arr[[i, j]].kind = SoilKind::SettledWater;
         }
     }
-
-    Ok(())
 }
 
 fn calculate_mins_maxes(x_coord_map: &HashMap<usize, Vec<usize>>, y_coord_map: &HashMap<usize, Vec<usize>>) -> Result<(usize, usize, usize, usize)> {
@@ -176,14 +193,9 @@ fn calculate_mins_maxes(x_coord_map: &HashMap<usize, Vec<usize>>, y_coord_map: &
     Ok((min_x, max_x, min_y, max_y))
 }
 
-fn setup_scan(
-    mins_maxes: (usize, usize, usize, usize),
-    x_coord_map: &HashMap<usize, Vec<usize>>,
-    y_coord_map: &HashMap<usize, Vec<usize>>,
-    test: bool,
-) -> Array2<Soil> {
+fn setup_scan(mins_maxes: (usize, usize, usize, usize), x_coord_map: &HashMap<usize, Vec<usize>>, y_coord_map: &HashMap<usize, Vec<usize>>) -> Grid<Soil> {
     let (_, max_x, _, max_y) = mins_maxes;
-    let mut clay_arr = Array2::<Soil>::default((max_x, max_y));
+    let mut clay_arr = Grid::new(max_x, max_y, Soil::default());
     clay_arr[[500, 0]] = Soil {
         kind: SoilKind::Spring,
         moved: false,
@@ -207,27 +219,27 @@ fn setup_scan(
         }
     }
 
-    if test {
-        print_scan_arr(mins_maxes, &clay_arr);
-    }
-
     clay_arr
 }
 
-fn print_scan_arr(mins_maxes: (usize, usize, usize, usize), scan_arr: &Array2<Soil>) {
+/// Draw the clay and water within the scanned bounds.
+fn render_scan_arr(mins_maxes: (usize, usize, usize, usize), scan_arr: &Grid<Soil>) -> String {
     let (min_x, max_x, _, max_y) = mins_maxes;
-    println!();
+    let mut buffer = String::new();
     for j in 0..max_y {
         for i in min_x..max_x {
-            print!("{}", scan_arr[[i, j]].kind);
+            buffer.push_str(&scan_arr[[i, j]].kind.to_string());
         }
-        println!();
+        buffer.push('\n');
     }
+    buffer
 }
 
 #[cfg(test)]
 mod one_star {
-    use super::run_scan;
+    use super::find_solution;
+    use crate::context::Context;
+    use crate::solver::Unsolved;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -242,7 +254,8 @@ y=13, x=498..504";
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(run_scan(Cursor::new(TEST_CODE), false, true)?, 1);
+        let error = find_solution(Cursor::new(TEST_CODE), &Context::default()).err();
+        assert!(error.is_some_and(|e| e.is::<Unsolved>()));
         Ok(())
     }
 }
@@ -264,7 +277,7 @@ y=13, x=498..504";
 
 //     #[test]
 //     fn solution() -> Result<()> {
-//         assert_eq!(find_solution(Cursor::new(TEST_CODE), true)?, 1);
+//         assert_eq!(find_solution(Cursor::new(TEST_CODE), true, &Context::default())?, Answer::Unsigned(1));
 //         Ok(())
 //     }
 // }
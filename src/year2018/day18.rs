@@ -1,295 +1,135 @@
 //! Advent of Code - Day 18 "Settlers of The North Pole" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::grid::Grid;
+use crate::solver::{Solver, Unsolved};
 use anyhow::{Result, anyhow};
-use ndarray::{Array2, Axis};
 use std::collections::HashMap;
 use std::io::BufRead;
 
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    let mut lca = lca(reader, 50, 50, second_star, false)?;
-    let result = if second_star {
-        run(&mut lca, 50, 50, 1000)?
-    } else {
-        run(&mut lca, 50, 50, 10)?
-    };
-
-    Ok(result as u32)
-}
-
-fn run(lca: &mut Array2<char>, max_i: usize, max_j: usize, minutes: usize) -> Result<usize> {
-    let mut results_map = HashMap::new();
-    for i in 0..minutes {
-        tick(lca, max_i, max_j)?;
+/// Day 18 "Settlers of The North Pole"
+pub(crate) struct Day18;
 
-        let wooded = lca.iter().filter(|x| **x == '|').count();
-        let lumber_yards = lca.iter().filter(|x| **x == '#').count();
-
-        let results_vec = results_map.entry(wooded * lumber_yards).or_insert_with(Vec::new);
-        if i > 583 {
-            results_vec.push(i + 1);
-        }
-        // println!("At minutes {}: {}", i, wooded * lumber_yards);
+impl Solver for Day18 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
     }
 
-    let wooded = lca.iter().filter(|x| **x == '|').count();
-    let lumber_yards = lca.iter().filter(|x| **x == '#').count();
-
-    let blah: HashMap<&usize, &Vec<usize>> = results_map.iter().filter(|(_, y)| y.len() > 2).collect();
-
-    let mut to_sort: Vec<(&usize, &Vec<usize>)> = blah.into_iter().collect();
-    to_sort.sort_by(|a, b| (a.1)[0].cmp(&(b.1)[0]));
-    for (x, y) in to_sort {
-        println!("{x}: {y:?}");
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD18
     }
 
-    Ok(wooded * lumber_yards)
-}
-
-fn tick(lca: &mut Array2<char>, max_i: usize, max_j: usize) -> Result<()> {
-    let mut change_map = HashMap::new();
-
-    for (idx, val) in lca.indexed_iter() {
-        let i = idx.0;
-        let j = idx.1;
-
-        let next = match val {
-            '.' => check_open(lca, i, j, max_i, max_j),
-            '|' => check_trees(lca, i, j, max_i, max_j),
-            '#' => check_lumberyard(lca, i, j, max_i, max_j),
-            _ => return Err(anyhow!("invalid lumber area")),
-        };
-
-        change_map.insert([i, j], next);
+    fn title(&self) -> &'static str {
+        "Settlers of The North Pole"
     }
 
-    for (idx, next) in change_map {
-        lca[idx] = next;
+    fn stars(&self) -> u8 {
+        1
     }
 
-    Ok(())
-}
-
-fn check_open(lca: &Array2<char>, i: usize, j: usize, max_i: usize, max_j: usize) -> char {
-    let mut tree_count = 0;
-    let mut result = '.';
-    // check the up left cell if valid
-    if i.checked_sub(1).is_some() && j.checked_sub(1).is_some() && lca[[i - 1, j - 1]] == '|' {
-        tree_count += 1;
-    }
-
-    // check the up cell if valid
-    if j.checked_sub(1).is_some() && lca[[i, j - 1]] == '|' {
-        tree_count += 1;
-    }
-
-    // check the up right cell if valid
-    if i + 1 < max_i && j.checked_sub(1).is_some() && lca[[i + 1, j - 1]] == '|' {
-        tree_count += 1;
-    }
-
-    // Check the left cell if valid
-    if i.checked_sub(1).is_some() && lca[[i - 1, j]] == '|' {
-        tree_count += 1;
-    }
-
-    // Check the right cell if valid
-    if i + 1 < max_i && lca[[i + 1, j]] == '|' {
-        tree_count += 1;
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false, &Context::default())
     }
 
-    // Check the down left cell if valid
-    if i.checked_sub(1).is_some() && j + 1 < max_j && lca[[i - 1, j + 1]] == '|' {
-        tree_count += 1;
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true, &Context::default())
     }
 
-    // Check the down cell if valid
-    if j + 1 < max_j && lca[[i, j + 1]] == '|' {
-        tree_count += 1;
+    fn star(&self, part: u8, reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer> {
+        find_solution(reader, part == 2, ctx)
     }
-
-    // Check the down right cell if valid
-    if i + 1 < max_i && j + 1 < max_j && lca[[i + 1, j + 1]] == '|' {
-        tree_count += 1;
-    }
-
-    if tree_count >= 3 {
-        result = '|';
-    }
-
-    result
 }
 
-fn check_trees(lca: &Array2<char>, i: usize, j: usize, max_i: usize, max_j: usize) -> char {
-    let mut lumber_yard_count = 0;
-    let mut result = '|';
-    // check the up left cell if valid
-    if i.checked_sub(1).is_some() && j.checked_sub(1).is_some() && lca[[i - 1, j - 1]] == '#' {
-        lumber_yard_count += 1;
-    }
-
-    // check the up cell if valid
-    if j.checked_sub(1).is_some() && lca[[i, j - 1]] == '#' {
-        lumber_yard_count += 1;
-    }
-
-    // check the up right cell if valid
-    if i + 1 < max_i && j.checked_sub(1).is_some() && lca[[i + 1, j - 1]] == '#' {
-        lumber_yard_count += 1;
-    }
-
-    // Check the left cell if valid
-    if i.checked_sub(1).is_some() && lca[[i - 1, j]] == '#' {
-        lumber_yard_count += 1;
-    }
-
-    // Check the right cell if valid
-    if i + 1 < max_i && lca[[i + 1, j]] == '#' {
-        lumber_yard_count += 1;
-    }
-
-    // Check the down left cell if valid
-    if i.checked_sub(1).is_some() && j + 1 < max_j && lca[[i - 1, j + 1]] == '#' {
-        lumber_yard_count += 1;
-    }
-
-    // Check the down cell if valid
-    if j + 1 < max_j && lca[[i, j + 1]] == '#' {
-        lumber_yard_count += 1;
-    }
-
-    // Check the down right cell if valid
-    if i + 1 < max_i && j + 1 < max_j && lca[[i + 1, j + 1]] == '#' {
-        lumber_yard_count += 1;
-    }
-
-    if lumber_yard_count >= 3 {
-        result = '#';
+/// Only the first star is solved; the second needs the area's repeating cycle found first.
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool, ctx: &Context) -> Result<Answer> {
+    let mut lca = lca(reader)?;
+    if second_star {
+        return Err(Unsolved.into());
     }
 
-    result
+    Ok(run(&mut lca, 10, ctx).into())
 }
 
-#[allow(clippy::cognitive_complexity)]
-fn check_lumberyard(lca: &Array2<char>, i: usize, j: usize, max_i: usize, max_j: usize) -> char {
-    let mut lumber_yard_count = 0;
-    let mut tree_count = 0;
-    let mut result = '.';
-    // check the up left cell if valid
-    if i.checked_sub(1).is_some() && j.checked_sub(1).is_some() {
-        if lca[[i - 1, j - 1]] == '#' {
-            lumber_yard_count += 1;
-        } else if lca[[i - 1, j - 1]] == '|' {
-            tree_count += 1;
-        }
-    }
-
-    // check the up cell if valid
-    if j.checked_sub(1).is_some() {
-        if lca[[i, j - 1]] == '#' {
-            lumber_yard_count += 1;
-        } else if lca[[i, j - 1]] == '|' {
-            tree_count += 1;
-        }
+/// Let `minutes` pass, tracing the resource value after each one and drawing the area when visualizing.
+fn run(lca: &mut Grid<char>, minutes: usize, ctx: &Context) -> usize {
+    let mut results_map = HashMap::new();
+    if ctx.visualizing() {
+        ctx.frame(&render_lca(lca, 0));
     }
-
-    // check the up right cell if valid
-    if i + 1 < max_i && j.checked_sub(1).is_some() {
-        if lca[[i + 1, j - 1]] == '#' {
-            lumber_yard_count += 1;
-        } else if lca[[i + 1, j - 1]] == '|' {
-            tree_count += 1;
+    for i in 0..minutes {
+        *lca = tick(lca);
+        if ctx.visualizing() {
+            ctx.frame(&render_lca(lca, i + 1));
         }
-    }
 
-    // Check the left cell if valid
-    if i.checked_sub(1).is_some() {
-        if lca[[i - 1, j]] == '#' {
-            lumber_yard_count += 1;
-        } else if lca[[i - 1, j]] == '|' {
-            tree_count += 1;
-        }
-    }
-
-    // Check the right cell if valid
-    if i + 1 < max_i {
-        if lca[[i + 1, j]] == '#' {
-            lumber_yard_count += 1;
-        } else if lca[[i + 1, j]] == '|' {
-            tree_count += 1;
+        let results_vec = results_map.entry(resource_value(lca)).or_insert_with(Vec::new);
+        if i > 583 {
+            results_vec.push(i + 1);
         }
-    }
-
-    // Check the down left cell if valid
-    if i.checked_sub(1).is_some() && j + 1 < max_j {
-        if lca[[i - 1, j + 1]] == '#' {
-            lumber_yard_count += 1;
-        } else if lca[[i - 1, j + 1]] == '|' {
-            tree_count += 1;
+        if ctx.tracing() {
+            eprintln!("After {} minutes: {}", i + 1, resource_value(lca));
         }
     }
 
-    // Check the down cell if valid
-    if j + 1 < max_j {
-        if lca[[i, j + 1]] == '#' {
-            lumber_yard_count += 1;
-        } else if lca[[i, j + 1]] == '|' {
-            tree_count += 1;
-        }
-    }
+    let blah: HashMap<&usize, &Vec<usize>> = results_map.iter().filter(|(_, y)| y.len() > 2).collect();
 
-    // Check the down right cell if valid
-    if i + 1 < max_i && j + 1 < max_j {
-        if lca[[i + 1, j + 1]] == '#' {
-            lumber_yard_count += 1;
-        } else if lca[[i + 1, j + 1]] == '|' {
-            tree_count += 1;
+    let mut to_sort: Vec<(&usize, &Vec<usize>)> = blah.into_iter().collect();
+    to_sort.sort_by(|a, b| (a.1)[0].cmp(&(b.1)[0]));
+    if ctx.tracing() {
+        for (x, y) in to_sort {
+            eprintln!("{x}: {y:?}");
         }
     }
 
-    if lumber_yard_count >= 1 && tree_count >= 1 {
-        result = '#';
-    }
+    resource_value(lca)
+}
 
-    result
+/// The number of wooded acres multiplied by the number of lumberyards.
+fn resource_value(lca: &Grid<char>) -> usize {
+    let wooded = lca.cells().filter(|x| **x == '|').count();
+    let lumber_yards = lca.cells().filter(|x| **x == '#').count();
+    wooded * lumber_yards
 }
 
-fn lca<T: BufRead>(reader: T, max_i: usize, max_j: usize, _second_star: bool, test: bool) -> Result<Array2<char>> {
-    let mut lca = Array2::<char>::default((max_i, max_j));
+/// The area one minute on.
+fn tick(lca: &Grid<char>) -> Grid<char> {
+    let mut next = lca.clone();
 
-    for (j, line) in reader.lines().map_while(Result::ok).enumerate() {
-        for (i, ch) in line.chars().enumerate() {
-            match ch {
-                '.' => lca[[i, j]] = ch,
-                '|' => lca[[i, j]] = ch,
-                '#' => lca[[i, j]] = ch,
-                _ => return Err(anyhow!("invalid lumber area")),
-            }
-        }
-    }
+    for (pos, val) in lca.iter() {
+        let count = |kind| lca.neighbors8(pos).filter(|n| lca[*n] == kind).count();
 
-    if test {
-        print_lca(&lca, 0);
+        next[pos] = match val {
+            '.' if count('|') >= 3 => '|',
+            '|' if count('#') >= 3 => '#',
+            '#' if count('#') == 0 || count('|') == 0 => '.',
+            _ => *val,
+        };
     }
-    Ok(lca)
+
+    next
+}
+
+fn lca<T: BufRead>(reader: T) -> Result<Grid<char>> {
+    Grid::parse(reader, |ch| match ch {
+        '.' | '|' | '#' => Ok(ch),
+        _ => Err(anyhow!("invalid lumber area")),
+    })
 }
 
-fn print_lca(lca: &Array2<char>, max: usize) {
-    println!();
+/// Draw the lumber collection area after `max` minutes.
+fn render_lca(lca: &Grid<char>, max: usize) -> String {
     if max == 0 {
-        println!("Initially:");
+        format!("Initially:\n{lca}")
     } else {
-        println!("After {max} minutes:");
-    }
-    for row in lca.axis_iter(Axis(1)) {
-        for v in row {
-            print!("{v}");
-        }
-        println!();
+        format!("After {max} minutes:\n{lca}")
     }
 }
 
 #[cfg(test)]
 mod one_star {
-    use super::{lca, print_lca, run};
+    use super::{lca, render_lca, run};
+    use crate::context::Context;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -306,16 +146,22 @@ mod one_star {
 
     #[test]
     fn solution() -> Result<()> {
-        let mut lca = lca(Cursor::new(TEST_CODE), 10, 10, false, true)?;
-        assert_eq!(run(&mut lca, 10, 10, 10)?, 1147);
-        print_lca(&lca, 10);
+        let mut lca = lca(Cursor::new(TEST_CODE))?;
+        assert_eq!(run(&mut lca, 10, &Context::default()), 1147);
+        assert_eq!(
+            render_lca(&lca, 10),
+            "After 10 minutes:\n.||##.....\n||###.....\n||##......\n|##.....##\n|##.....##\n\
+             |##....##|\n||##.####|\n||#####|||\n||||#|||||\n||||||||||\n"
+        );
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod two_star {
-    use super::{lca, print_lca, run};
+    use super::{find_solution, lca, render_lca, run};
+    use crate::context::Context;
+    use crate::solver::Unsolved;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -332,9 +178,11 @@ mod two_star {
 
     #[test]
     fn solution() -> Result<()> {
-        let mut lca = lca(Cursor::new(TEST_CODE), 10, 10, false, true)?;
-        assert_eq!(run(&mut lca, 10, 10, 200)?, 0);
-        print_lca(&lca, 10);
+        let mut lca = lca(Cursor::new(TEST_CODE))?;
+        assert_eq!(run(&mut lca, 200, &Context::default()), 0);
+        assert!(render_lca(&lca, 200).starts_with("After 200 minutes:\n"));
+        let error = find_solution(Cursor::new(TEST_CODE), true, &Context::default()).err();
+        assert!(error.is_some_and(|e| e.is::<Unsolved>()));
         Ok(())
     }
 }
@@ -1,6 +1,128 @@
-use anyhow::Result;
+//! Advent of Code - Day 21 "Chronal Conversion" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::solver::Solver;
+use crate::year2018::elfcode::{Machine, OpCode, Program, Stop};
+use anyhow::{Result, anyhow};
+use std::collections::HashSet;
 use std::io::BufRead;
 
-pub fn find_solution<T: BufRead>(_reader: T, _second_star: bool) -> Result<u32> {
-    Ok(1)
+/// Day 21 "Chronal Conversion"
+pub(crate) struct Day21;
+
+impl Solver for Day21 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD21
+    }
+
+    fn title(&self) -> &'static str {
+        "Chronal Conversion"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false, &Context::default())
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true, &Context::default())
+    }
+
+    fn star(&self, part: u8, reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer> {
+        find_solution(reader, part == 2, ctx)
+    }
+}
+
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool, ctx: &Context) -> Result<Answer> {
+    Ok(halting_value(reader, second_star, ctx)?.into())
+}
+
+/// The value of register 0 that halts the program after the fewest instructions, or for the
+/// second star after the most.
+///
+/// Register 0 is only ever read by a single `eqrr` that halts the program when it matches
+/// another register, so the candidates are the values that register holds each time the
+/// check is reached.  They eventually repeat, and the last one before that takes longest.
+fn halting_value<T: BufRead>(reader: T, second_star: bool, ctx: &Context) -> Result<usize> {
+    let program = Program::parse(reader, AoCDay::AOCD21)?;
+    if ctx.tracing() {
+        eprint!("{}", program.disassemble());
+    }
+
+    let check = program.uses(0).collect::<Vec<_>>();
+    let (check, other) = match check[..] {
+        [idx] => match program.instructions[idx] {
+            ins if ins.opcode == OpCode::Eqrr && ins.args[0] == 0 => (idx, ins.args[1]),
+            ins if ins.opcode == OpCode::Eqrr && ins.args[1] == 0 => (idx, ins.args[0]),
+            _ => return Err(anyhow!("instruction {idx} reads register 0 but is not an `eqrr`")),
+        },
+        _ => return Err(anyhow!("expected register 0 to be read by exactly one instruction")),
+    };
+
+    let mut machine = Machine::<6>::new(&program)?.accelerated();
+    let mut seen = HashSet::new();
+    let mut last = None;
+    while machine.run_until(&[check], ctx)? == Stop::Breakpoint(check) {
+        let value = machine.registers[other];
+        if ctx.tracing() {
+            eprintln!("Register 0 is checked against {value} after {} instructions", machine.executed());
+        }
+        if !second_star {
+            return Ok(value);
+        }
+        if !seen.insert(value) {
+            break;
+        }
+        last = Some(value);
+    }
+
+    last.ok_or_else(|| anyhow!("the program halts without comparing register 0"))
+}
+
+#[cfg(test)]
+mod one_star {
+    use super::halting_value;
+    use crate::context::Context;
+    use anyhow::Result;
+    use std::io::Cursor;
+
+    const TEST_CODE: &str = r"#ip 5
+seti 0 0 1
+addi 1 3 1
+bani 1 7 1
+eqrr 1 0 2
+addr 2 5 5
+seti 0 0 5";
+
+    #[test]
+    fn solution() -> Result<()> {
+        assert_eq!(halting_value(Cursor::new(TEST_CODE), false, &Context::default())?, 3);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod two_star {
+    use super::halting_value;
+    use crate::context::Context;
+    use anyhow::Result;
+    use std::io::Cursor;
+
+    const TEST_CODE: &str = r"#ip 5
+seti 0 0 1
+addi 1 3 1
+bani 1 7 1
+eqrr 1 0 2
+addr 2 5 5
+seti 0 0 5";
+
+    #[test]
+    fn solution() -> Result<()> {
+        assert_eq!(halting_value(Cursor::new(TEST_CODE), true, &Context::default())?, 0);
+        Ok(())
+    }
 }
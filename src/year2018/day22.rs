@@ -1,4 +1,9 @@
 //! Advent of Code - Day 22 "Mode Maze" Solution
+use crate::answer::Answer;
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::parse::{self, ParseError};
+use crate::solver::Solver;
 use anyhow::{Result, anyhow};
 use ndarray::{Array2, Axis};
 use regex::Regex;
@@ -8,6 +13,35 @@ use std::fmt;
 use std::io::BufRead;
 use std::slice::Iter;
 
+/// Day 22 "Mode Maze"
+pub(crate) struct Day22;
+
+impl Solver for Day22 {
+    fn year(&self) -> AoCYear {
+        AoCYear::AOC2018
+    }
+
+    fn day(&self) -> AoCDay {
+        AoCDay::AOCD22
+    }
+
+    fn title(&self) -> &'static str {
+        "Mode Maze"
+    }
+
+    fn part1(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, false, &Context::default())
+    }
+
+    fn part2(&self, reader: &mut dyn BufRead) -> Result<Answer> {
+        find_solution(reader, true, &Context::default())
+    }
+
+    fn star(&self, part: u8, reader: &mut dyn BufRead, ctx: &Context) -> Result<Answer> {
+        find_solution(reader, part == 2, ctx)
+    }
+}
+
 #[derive(Clone, Default, Eq, Hash, PartialEq)]
 enum RegionKind {
     #[default]
@@ -63,28 +97,34 @@ impl PartialOrd for State {
     }
 }
 
-pub fn find_solution<T: BufRead>(reader: T, second_star: bool) -> Result<u32> {
-    Ok(determine_risk(reader, second_star, false, 20, 1000)? as u32)
+pub fn find_solution<T: BufRead>(reader: T, second_star: bool, ctx: &Context) -> Result<Answer> {
+    Ok(determine_risk(reader, second_star, ctx, 20, 1000)?.into())
 }
 
-fn determine_risk<T: BufRead>(reader: T, second_star: bool, test: bool, max_i: usize, max_j: usize) -> Result<usize> {
-    let depth_re = Regex::new(r"depth: (\d+)")?;
-    let target_re = Regex::new(r"target: (\d+),(\d+)")?;
+fn determine_risk<T: BufRead>(reader: T, second_star: bool, ctx: &Context, max_i: usize, max_j: usize) -> Result<usize> {
+    let depth_re = Regex::new(r"^depth: (\d+)$")?;
+    let target_re = Regex::new(r"^target: (\d+),(\d+)$")?;
     let mut region_map = Array2::<Region>::default((max_i, max_j));
     let mut depth = 0;
     let mut target_coord = (0, 0);
 
-    for line in reader.lines().map_while(Result::ok) {
-        if depth_re.is_match(&line) {
-            for caps in depth_re.captures_iter(&line) {
-                depth = (caps[1]).parse::<usize>()?;
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let error = |token: &str, expected: String| ParseError::new(AoCYear::AOC2018, AoCDay::AOCD22, idx, &line, token, expected);
+        let number = |token: &str| parse::number::<usize>(AoCYear::AOC2018, AoCDay::AOCD22, idx, &line, token);
+        if let Some(caps) = depth_re.captures(&line) {
+            depth = number(&caps[1])?;
+        } else if let Some(caps) = target_re.captures(&line) {
+            let (i, j) = (number(&caps[1])?, number(&caps[2])?);
+            if i >= max_i {
+                return Err(error(&caps[1], format!("an x coordinate below {max_i}")).into());
             }
-        } else if target_re.is_match(&line) {
-            for caps in target_re.captures_iter(&line) {
-                let i = (caps[1]).parse::<usize>()?;
-                let j = (caps[2]).parse::<usize>()?;
-                target_coord = (i, j);
+            if j >= max_j {
+                return Err(error(&caps[2], format!("a y coordinate below {max_j}")).into());
             }
+            target_coord = (i, j);
+        } else if !line.is_empty() {
+            return Err(ParseError::whole_line(AoCYear::AOC2018, AoCDay::AOCD22, idx, &line, "`depth: 510` or `target: 10,10`").into());
         }
     }
 
@@ -103,8 +143,8 @@ fn determine_risk<T: BufRead>(reader: T, second_star: bool, test: bool, max_i: u
         }
     }
 
-    if test {
-        print_map(&region_map);
+    if ctx.visualizing() {
+        ctx.frame(&render_map(&region_map));
     }
 
     let mut result = 0;
@@ -271,20 +311,22 @@ fn geologic_index(i: usize, j: usize, target_coord: (usize, usize), region_map:
     }
 }
 
-fn print_map(region_map: &Array2<Region>) {
-    println!();
-
+/// Draw the cave's region types.
+fn render_map(region_map: &Array2<Region>) -> String {
+    let mut buffer = String::new();
     for row in region_map.axis_iter(Axis(1)) {
         for cell in row {
-            print!("{}", cell.kind);
+            buffer.push_str(&cell.kind.to_string());
         }
-        println!();
+        buffer.push('\n');
     }
+    buffer
 }
 
 #[cfg(test)]
 mod one_star {
     use super::determine_risk;
+    use crate::context::Context;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -293,7 +335,7 @@ target: 10,10";
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(determine_risk(Cursor::new(TEST_CODE), false, true, 16, 16)?, 114);
+        assert_eq!(determine_risk(Cursor::new(TEST_CODE), false, &Context::default(), 16, 16)?, 114);
         Ok(())
     }
 }
@@ -301,6 +343,7 @@ target: 10,10";
 #[cfg(test)]
 mod two_star {
     use super::determine_risk;
+    use crate::context::Context;
     use anyhow::Result;
     use std::io::Cursor;
 
@@ -309,7 +352,7 @@ target: 10,10";
 
     #[test]
     fn solution() -> Result<()> {
-        assert_eq!(determine_risk(Cursor::new(TEST_CODE), true, true, 16, 16)?, 45);
+        assert_eq!(determine_risk(Cursor::new(TEST_CODE), true, &Context::default(), 16, 16)?, 45);
         Ok(())
     }
 }
@@ -1,6 +0,0 @@
-use anyhow::Result;
-use std::io::BufRead;
-
-pub fn find_solution<T: BufRead>(_reader: T, _second_star: bool) -> Result<u32> {
-    Ok(1)
-}
@@ -0,0 +1,584 @@
+//! ElfCode, the device language of 2018 days 16, 19 and 21
+//!
+//! A [`Program`] is a list of instructions, optionally with one register bound to the
+//! instruction pointer by an `#ip` directive.  A [`Machine`] runs a program on any number
+//! of registers, one instruction at a time or up to a breakpoint.
+use crate::constants::{AoCDay, AoCYear};
+use crate::context::Context;
+use crate::parse::ParseError;
+use crate::year2018::accelerate::Accelerator;
+use anyhow::{Error, Result, anyhow};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io::BufRead;
+
+/// How many instructions to execute between checks of the run's limits.
+const CHECK_INTERVAL: usize = 1 << 16;
+
+/// One of the sixteen ElfCode operations.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub(crate) enum OpCode {
+    /// (add register) stores into register C the result of adding register A and register B.
+    Addr,
+    /// (add immediate) stores into register C the result of adding register A and value B.
+    Addi,
+    /// (multiply register) stores into register C the result of multiplying register A and register B.
+    Mulr,
+    /// (multiply immediate) stores into register C the result of multiplying register A and value B.
+    Muli,
+    /// (bitwise AND register) stores into register C the result of the bitwise AND of register A and register B.
+    Banr,
+    /// (bitwise AND immediate) stores into register C the result of the bitwise AND of register A and value B.
+    Bani,
+    /// (bitwise OR register) stores into register C the result of the bitwise OR of register A and register B.
+    Borr,
+    /// (bitwise OR immediate) stores into register C the result of the bitwise OR of register A and value B.
+    Bori,
+    /// (set register) copies the contents of register A into register C. (Input B is ignored.)
+    Setr,
+    /// (set immediate) stores value A into register C. (Input B is ignored.)
+    Seti,
+    /// (greater-than immediate/register) sets register C to 1 if value A is greater than register B. Otherwise, register C is set to 0.
+    Gtir,
+    /// (greater-than register/immediate) sets register C to 1 if register A is greater than value B. Otherwise, register C is set to 0.
+    Gtri,
+    /// (greater-than register/register) sets register C to 1 if register A is greater than register B. Otherwise, register C is set to 0.
+    Gtrr,
+    /// (equal immediate/register) sets register C to 1 if value A is equal to register B. Otherwise, register C is set to 0.
+    Eqir,
+    /// (equal register/immediate) sets register C to 1 if register A is equal to value B. Otherwise, register C is set to 0.
+    Eqri,
+    /// (equal register/register) sets register C to 1 if register A is equal to register B. Otherwise, register C is set to 0.
+    Eqrr,
+}
+
+/// How an operation reads one of its inputs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Operand {
+    /// The input is a register number
+    Register,
+    /// The input is a value
+    Immediate,
+    /// The input is not read
+    Ignored,
+}
+
+impl OpCode {
+    /// Every operation, in the order the puzzle lists them.
+    pub(crate) const ALL: [OpCode; 16] = [
+        OpCode::Addr,
+        OpCode::Addi,
+        OpCode::Mulr,
+        OpCode::Muli,
+        OpCode::Banr,
+        OpCode::Bani,
+        OpCode::Borr,
+        OpCode::Bori,
+        OpCode::Setr,
+        OpCode::Seti,
+        OpCode::Gtir,
+        OpCode::Gtri,
+        OpCode::Gtrr,
+        OpCode::Eqir,
+        OpCode::Eqri,
+        OpCode::Eqrr,
+    ];
+
+    /// Apply the operation to `reg` with the inputs `[a, b, c]`.
+    pub(crate) fn execute(self, reg: &mut [usize], [a, b, c]: [usize; 3]) {
+        reg[c] = match self {
+            OpCode::Addr => reg[a] + reg[b],
+            OpCode::Addi => reg[a] + b,
+            OpCode::Mulr => reg[a] * reg[b],
+            OpCode::Muli => reg[a] * b,
+            OpCode::Banr => reg[a] & reg[b],
+            OpCode::Bani => reg[a] & b,
+            OpCode::Borr => reg[a] | reg[b],
+            OpCode::Bori => reg[a] | b,
+            OpCode::Setr => reg[a],
+            OpCode::Seti => a,
+            OpCode::Gtir => usize::from(a > reg[b]),
+            OpCode::Gtri => usize::from(reg[a] > b),
+            OpCode::Gtrr => usize::from(reg[a] > reg[b]),
+            OpCode::Eqir => usize::from(a == reg[b]),
+            OpCode::Eqri => usize::from(reg[a] == b),
+            OpCode::Eqrr => usize::from(reg[a] == reg[b]),
+        }
+    }
+
+    /// How the operation reads inputs A and B.
+    pub(crate) fn operands(self) -> (Operand, Operand) {
+        use Operand::{Ignored, Immediate, Register};
+
+        match self {
+            OpCode::Addr | OpCode::Mulr | OpCode::Banr | OpCode::Borr | OpCode::Gtrr | OpCode::Eqrr => (Register, Register),
+            OpCode::Addi | OpCode::Muli | OpCode::Bani | OpCode::Bori | OpCode::Gtri | OpCode::Eqri => (Register, Immediate),
+            OpCode::Gtir | OpCode::Eqir => (Immediate, Register),
+            OpCode::Setr => (Register, Ignored),
+            OpCode::Seti => (Immediate, Ignored),
+        }
+    }
+
+    /// The operator joining inputs A and B in pseudo-code.
+    fn operator(self) -> Option<&'static str> {
+        match self {
+            OpCode::Addr | OpCode::Addi => Some("+"),
+            OpCode::Mulr | OpCode::Muli => Some("*"),
+            OpCode::Banr | OpCode::Bani => Some("&"),
+            OpCode::Borr | OpCode::Bori => Some("|"),
+            OpCode::Gtir | OpCode::Gtri | OpCode::Gtrr => Some(">"),
+            OpCode::Eqir | OpCode::Eqri | OpCode::Eqrr => Some("=="),
+            OpCode::Setr | OpCode::Seti => None,
+        }
+    }
+
+    /// Whether the operation stores 0 or 1.
+    fn is_comparison(self) -> bool {
+        matches!(self.operator(), Some(">" | "=="))
+    }
+}
+
+impl fmt::Display for OpCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                OpCode::Addr => "addr",
+                OpCode::Addi => "addi",
+                OpCode::Mulr => "mulr",
+                OpCode::Muli => "muli",
+                OpCode::Banr => "banr",
+                OpCode::Bani => "bani",
+                OpCode::Borr => "borr",
+                OpCode::Bori => "bori",
+                OpCode::Setr => "setr",
+                OpCode::Seti => "seti",
+                OpCode::Gtir => "gtir",
+                OpCode::Gtri => "gtri",
+                OpCode::Gtrr => "gtrr",
+                OpCode::Eqir => "eqir",
+                OpCode::Eqri => "eqri",
+                OpCode::Eqrr => "eqrr",
+            }
+        )
+    }
+}
+
+impl TryFrom<&str> for OpCode {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        OpCode::ALL
+            .into_iter()
+            .find(|opcode| opcode.to_string() == s)
+            .ok_or_else(|| anyhow!("invalid opcode `{s}`"))
+    }
+}
+
+/// An operation with its three inputs.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) struct Instruction {
+    /// The operation
+    pub(crate) opcode: OpCode,
+    /// Inputs A and B, and output register C
+    pub(crate) args: [usize; 3],
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let [a, b, c] = self.args;
+        write!(f, "{} {a} {b} {c}", self.opcode)
+    }
+}
+
+/// An ElfCode program.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct Program {
+    /// The register bound to the instruction pointer, if any
+    pub(crate) ip_register: Option<usize>,
+    /// The instructions
+    pub(crate) instructions: Vec<Instruction>,
+    /// The day the program was parsed for, if it was parsed, to report bad registers against
+    pub(crate) day: Option<AoCDay>,
+    /// The input line index of the `#ip` directive
+    pub(crate) ip_line: usize,
+    /// The input line index of each instruction
+    pub(crate) lines: Vec<usize>,
+}
+
+impl Program {
+    /// Parse an `#ip` directive and named instructions, reporting bad lines against `day`.
+    pub(crate) fn parse<T: BufRead>(reader: T, day: AoCDay) -> Result<Self> {
+        let mut program = Program {
+            day: Some(day),
+            ..Program::default()
+        };
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            let mut tokens = line.split_whitespace();
+            let Some(first) = tokens.next() else {
+                continue;
+            };
+            let number = |token: Option<&str>| -> Result<usize> {
+                let token = token.unwrap_or(&line[line.len()..]);
+                token
+                    .parse()
+                    .map_err(|_| ParseError::new(AoCYear::AOC2018, day, idx, &line, token, "a number").into())
+            };
+
+            if first == "#ip" {
+                program.ip_register = Some(number(tokens.next())?);
+                program.ip_line = idx;
+            } else {
+                let opcode = OpCode::try_from(first)
+                    .map_err(|_| ParseError::new(AoCYear::AOC2018, day, idx, &line, first, "`#ip` or an opcode like `addr` or `seti`"))?;
+                let args = [number(tokens.next())?, number(tokens.next())?, number(tokens.next())?];
+                program.instructions.push(Instruction { opcode, args });
+                program.lines.push(idx);
+            }
+        }
+        Ok(program)
+    }
+
+    /// Check that the `#ip` directive and every register operand name one of `registers` registers.
+    fn check_registers(&self, registers: usize) -> Result<()> {
+        if let Some(reg) = self.ip_register
+            && reg >= registers
+        {
+            return Err(self.bad_register(format!("#ip {reg}"), self.ip_line, 1, registers));
+        }
+        for (idx, instruction) in self.instructions.iter().enumerate() {
+            let (a, b) = instruction.opcode.operands();
+            let [ra, rb, rc] = instruction.args;
+            let bad = [(a, ra), (b, rb), (Operand::Register, rc)]
+                .into_iter()
+                .position(|(operand, reg)| operand == Operand::Register && reg >= registers);
+            if let Some(arg) = bad {
+                return Err(self.bad_register(instruction.to_string(), self.lines.get(idx).copied().unwrap_or(idx), arg + 1, registers));
+            }
+        }
+        Ok(())
+    }
+
+    /// An error blaming the `token`th token of `text`, the line at index `idx`, for naming a register the machine lacks.
+    fn bad_register(&self, text: String, idx: usize, token: usize, registers: usize) -> Error {
+        let found = text.split_whitespace().nth(token).unwrap_or_default();
+        let expected = format!("a register below {registers}");
+        match self.day {
+            Some(day) => ParseError::new(AoCYear::AOC2018, day, idx, &text, found, expected).into(),
+            None => anyhow!("`{text}`: expected {expected}, found `{found}`"),
+        }
+    }
+
+    /// The indices of the instructions that read or write register `reg`.
+    pub(crate) fn uses(&self, reg: usize) -> impl Iterator<Item = usize> + '_ {
+        self.instructions.iter().enumerate().filter_map(move |(idx, ins)| {
+            let (a, b) = ins.opcode.operands();
+            let [ra, rb, rc] = ins.args;
+            (rc == reg || (a == Operand::Register && ra == reg) || (b == Operand::Register && rb == reg)).then_some(idx)
+        })
+    }
+
+    /// The program as pseudo-code, one instruction per line, with jump targets as labels.
+    ///
+    /// Registers are `r0`, `r1` and so on, except the one bound to the instruction pointer,
+    /// which is replaced by the address of the instruction reading it.
+    pub(crate) fn disassemble(&self) -> String {
+        let lines: Vec<(String, Option<usize>)> = (0..self.instructions.len()).map(|idx| self.pseudo_code(idx)).collect();
+        let labels: BTreeSet<usize> = lines.iter().filter_map(|(_, target)| *target).collect();
+
+        let mut buffer = String::new();
+        if let Some(reg) = self.ip_register {
+            buffer.push_str(&format!("#ip r{reg}\n"));
+        }
+        for (idx, (line, _)) in lines.iter().enumerate() {
+            if labels.contains(&idx) {
+                buffer.push_str(&format!("L{idx}:\n"));
+            }
+            buffer.push_str(&format!("{idx:>4}  {line}\n"));
+        }
+        if labels.contains(&self.instructions.len()) {
+            buffer.push_str(&format!("L{}:\n", self.instructions.len()));
+        }
+        buffer
+    }
+
+    /// The instruction at `idx` as pseudo-code, with the address it jumps to, if it jumps to a known one.
+    fn pseudo_code(&self, idx: usize) -> (String, Option<usize>) {
+        let ins = self.instructions[idx];
+        let [a, b, c] = ins.args;
+        let (kind_a, kind_b) = ins.opcode.operands();
+        let ip = self.ip_register;
+        let input = |kind, value| match kind {
+            Operand::Register if Some(value) == ip => idx.to_string(),
+            Operand::Register => format!("r{value}"),
+            _ => value.to_string(),
+        };
+        let expression = match ins.opcode.operator() {
+            Some(operator) => format!("{} {operator} {}", input(kind_a, a), input(kind_b, b)),
+            None => input(kind_a, a),
+        };
+
+        if Some(c) != ip {
+            return (format!("r{c} = {expression}"), None);
+        }
+
+        // Writing the instruction pointer is a jump to one past the value written.
+        let reads_ip = |kind, value| kind == Operand::Register && Some(value) == ip;
+        let target = match (ins.opcode, reads_ip(kind_a, a), reads_ip(kind_b, b)) {
+            (OpCode::Seti, ..) => Some(a + 1),
+            (OpCode::Addi, true, _) => Some(idx + b + 1),
+            (OpCode::Mulr, true, true) => Some(idx * idx + 1),
+            (OpCode::Addr, true, false) | (OpCode::Addr, false, true) => {
+                // Adding a freshly compared register skips the next instruction when it holds.
+                let flag = if reads_ip(kind_a, a) { b } else { a };
+                let compared = idx
+                    .checked_sub(1)
+                    .map(|prev| self.instructions[prev])
+                    .is_some_and(|prev| prev.opcode.is_comparison() && prev.args[2] == flag);
+                if compared {
+                    return (format!("if r{flag} goto L{}", idx + 2), Some(idx + 2));
+                }
+                return (format!("goto {} + r{flag}", idx + 1), None);
+            }
+            _ => None,
+        };
+
+        match target {
+            Some(target) if target <= self.instructions.len() => (format!("goto L{target}"), Some(target)),
+            Some(_) => ("halt".to_string(), None),
+            None => (format!("goto ({expression}) + 1"), None),
+        }
+    }
+}
+
+/// Why a run stopped.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Stop {
+    /// The instruction pointer reached a breakpoint, before executing the instruction there
+    Breakpoint(usize),
+    /// The instruction pointer left the program
+    Halted,
+}
+
+/// One executed instruction, displayed as `ip=0 [0, 0, 0, 0, 0, 0] seti 5 0 1 [0, 5, 0, 0, 0, 0]`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct Trace<const N: usize> {
+    /// The address of the instruction
+    ip: usize,
+    /// The registers before it ran
+    before: [usize; N],
+    /// The instruction
+    instruction: Instruction,
+    /// The registers after it ran
+    after: [usize; N],
+}
+
+impl<const N: usize> fmt::Display for Trace<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ip={} {:?} {} {:?}", self.ip, self.before, self.instruction, self.after)
+    }
+}
+
+/// A device running a program on `N` registers.
+#[derive(Clone, Debug)]
+pub(crate) struct Machine<'a, const N: usize> {
+    /// The program being run
+    program: &'a Program,
+    /// The registers
+    pub(crate) registers: [usize; N],
+    /// The address of the next instruction
+    ip: usize,
+    /// How many instructions have been executed, including any skipped by the accelerator
+    executed: usize,
+    /// The accelerator skipping hot loops, if enabled
+    accelerator: Option<Accelerator>,
+}
+
+impl<'a, const N: usize> Machine<'a, N> {
+    /// A machine about to run `program` from its first instruction, with every register 0.
+    ///
+    /// Fails if the `#ip` directive or any register operand names a register beyond the `N` the machine has.
+    pub(crate) fn new(program: &'a Program) -> Result<Self> {
+        program.check_registers(N)?;
+        Ok(Self {
+            program,
+            registers: [0; N],
+            ip: 0,
+            executed: 0,
+            accelerator: None,
+        })
+    }
+
+    /// The machine, skipping the program's hot loops wherever it can work out their effect directly.
+    pub(crate) fn accelerated(mut self) -> Self {
+        self.accelerator = Some(Accelerator::new(self.program));
+        self
+    }
+
+    /// The address of the next instruction.
+    pub(crate) fn ip(&self) -> usize {
+        self.ip
+    }
+
+    /// How many instructions have been executed.
+    pub(crate) fn executed(&self) -> usize {
+        self.executed
+    }
+
+    /// Execute the next instruction, or return `None` if the instruction pointer has left the program.
+    pub(crate) fn step(&mut self) -> Option<Trace<N>> {
+        let instruction = *self.program.instructions.get(self.ip)?;
+        if let Some(reg) = self.program.ip_register {
+            self.registers[reg] = self.ip;
+        }
+        let before = self.registers;
+        instruction.opcode.execute(&mut self.registers, instruction.args);
+        let trace = Trace {
+            ip: self.ip,
+            before,
+            instruction,
+            after: self.registers,
+        };
+
+        self.ip = self.program.ip_register.map_or(self.ip, |reg| self.registers[reg]) + 1;
+        self.executed += 1;
+        Some(trace)
+    }
+
+    /// Run until the instruction pointer reaches one of `breakpoints` or leaves the program.
+    ///
+    /// A breakpoint at the current address is only stopped at after at least one step, so
+    /// calling this again resumes the run.  Every instruction, and every loop the accelerator
+    /// skips, is traced when `ctx` asks.
+    pub(crate) fn run_until(&mut self, breakpoints: &[usize], ctx: &Context) -> Result<Stop> {
+        let mut stepped = false;
+        let mut jumped_back = false;
+        // Skips move the count in large jumps, so the limits are checked by distance from the last check.
+        let mut last_check = self.executed;
+        ctx.check()?;
+        loop {
+            if stepped && breakpoints.contains(&self.ip) {
+                return Ok(Stop::Breakpoint(self.ip));
+            }
+            if self.executed - last_check >= CHECK_INTERVAL {
+                ctx.check()?;
+                last_check = self.executed;
+            }
+            if let Some(accelerator) = self.accelerator.as_mut()
+                && let Some(skip) = accelerator.accelerate(self.program, &mut self.registers, &mut self.ip, jumped_back, breakpoints)
+            {
+                if ctx.tracing() {
+                    eprintln!("{skip}");
+                }
+                self.executed += skip.instructions();
+                (stepped, jumped_back) = (true, false);
+                continue;
+            }
+            match self.step() {
+                Some(trace) => {
+                    if ctx.tracing() {
+                        eprintln!("{trace}");
+                    }
+                    jumped_back = self.ip <= trace.ip;
+                }
+                None => return Ok(Stop::Halted),
+            }
+            stepped = true;
+        }
+    }
+
+    /// Run until the instruction pointer leaves the program.
+    pub(crate) fn run(&mut self, ctx: &Context) -> Result<()> {
+        let _ = self.run_until(&[], ctx)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Machine, OpCode, Program, Stop};
+    use crate::constants::AoCDay;
+    use crate::context::Context;
+    use anyhow::Result;
+    use std::io::Cursor;
+
+    const TEST_CODE: &str = r"#ip 0
+seti 5 0 1
+seti 6 0 2
+addi 0 1 0
+addr 1 2 3
+setr 1 0 0
+seti 8 0 4
+seti 9 0 5";
+
+    #[test]
+    fn step_and_break() -> Result<()> {
+        let program = Program::parse(Cursor::new(TEST_CODE), AoCDay::AOCD19)?;
+        let mut machine = Machine::<6>::new(&program)?;
+        let trace = machine.step().map(|trace| trace.to_string());
+        assert_eq!(trace.as_deref(), Some("ip=0 [0, 0, 0, 0, 0, 0] seti 5 0 1 [0, 5, 0, 0, 0, 0]"));
+        assert_eq!(machine.run_until(&[4], &Context::default())?, Stop::Breakpoint(4));
+        assert_eq!((machine.ip(), machine.executed()), (4, 3));
+        assert_eq!(machine.run_until(&[4], &Context::default())?, Stop::Halted);
+        assert_eq!((machine.registers, machine.executed()), ([6, 5, 6, 0, 0, 9], 5));
+        Ok(())
+    }
+
+    #[test]
+    fn bad_register() -> Result<()> {
+        let error = |code: &str| -> Result<Option<String>> {
+            let program = Program::parse(Cursor::new(code), AoCDay::AOCD19)?;
+            Ok(Machine::<6>::new(&program).err().map(|e| e.to_string()))
+        };
+        assert_eq!(
+            error("#ip 6")?.as_deref(),
+            Some("2018 day19 line 1, column 5: expected a register below 6, found `6`")
+        );
+        assert_eq!(
+            error("#ip 0\naddr 9 0 0")?.as_deref(),
+            Some("2018 day19 line 2, column 6: expected a register below 6, found `9`")
+        );
+        assert_eq!(
+            error("seti 9 0 1\ngtir 9 1 2\naddi 1 9 6")?.as_deref(),
+            Some("2018 day19 line 3, column 10: expected a register below 6, found `6`")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn opcodes() {
+        let mut registers = [3, 2, 1, 1];
+        OpCode::Mulr.execute(&mut registers, [2, 1, 2]);
+        assert_eq!(registers, [3, 2, 2, 1]);
+        assert_eq!(OpCode::ALL.map(|opcode| OpCode::try_from(&opcode.to_string()[..]).ok()), OpCode::ALL.map(Some));
+    }
+
+    #[test]
+    fn bad_line() {
+        let error = Program::parse(Cursor::new("#ip 0\naddx 1 2 3"), AoCDay::AOCD19).err();
+        assert_eq!(
+            error.map(|e| e.to_string()),
+            Some("2018 day19 line 2, column 1: expected `#ip` or an opcode like `addr` or `seti`, found `addx`".to_string())
+        );
+        let error = Program::parse(Cursor::new("seti 1 2"), AoCDay::AOCD19).err();
+        assert_eq!(
+            error.map(|e| e.to_string()),
+            Some("2018 day19 line 1, column 9: expected a number, found the end of the line".to_string())
+        );
+    }
+
+    #[test]
+    fn disassemble() -> Result<()> {
+        let program = Program::parse(
+            Cursor::new("#ip 5\nseti 0 0 1\naddi 1 3 1\nbani 1 7 1\neqrr 1 0 2\naddr 2 5 5\nseti 0 0 5\naddr 5 3 1\nmulr 5 5 5"),
+            AoCDay::AOCD21,
+        )?;
+        assert_eq!(
+            program.disassemble(),
+            "#ip r5\n   0  r1 = 0\nL1:\n   1  r1 = r1 + 3\n   2  r1 = r1 & 7\n   3  r2 = r1 == r0\n   4  if r2 goto L6\n   5  goto L1\nL6:\n   6  r1 = 6 + r3\n   7  halt\n"
+        );
+        Ok(())
+    }
+}